@@ -1,4 +1,5 @@
 use crate::app::TmuxSession;
+use crate::config::SessionTemplate;
 use tokio::sync::oneshot;
 
 // =============================================================================
@@ -10,25 +11,92 @@ pub enum TmuxCommand {
     /// Refresh all sessions, windows, and panes
     RefreshAll,
 
-    /// Capture pane content
-    CapturePane { target: String, start: i32, end: i32 },
+    /// Capture lines `start`..=`end` of a pane (`-J` when `join`)
+    CapturePane { target: String, start: i32, end: i32, join: bool },
+
+    /// Capture a pane as plain text from `start` (a history line) to the
+    /// bottom, answered with [`TmuxResponse::PaneSnapshot`]
+    SnapshotPane { target: String, start: i32 },
 
     /// Create a new session
     NewSession { name: String },
 
+    /// Create a new session laid out from a config template, optionally as
+    /// one item of a UI batch
+    NewSessionFromTemplate {
+        name: String,
+        template: SessionTemplate,
+        batch: Option<u64>,
+    },
+
     /// Rename an existing session
     RenameSession { old_name: String, new_name: String },
 
-    /// Kill a session
-    KillSession { name: String },
+    /// Kill a session, optionally as one item of a UI batch
+    KillSession { name: String, batch: Option<u64> },
+
+    /// Create a window in `session` (a target such as `=dev`), named `name`
+    /// or left for tmux to name
+    NewWindow { session: String, name: Option<String> },
+
+    /// Rename a window
+    RenameWindow { target: String, new_name: String },
+
+    /// Kill a window
+    KillWindow { target: String },
+
+    /// Flip a window's `synchronize-panes` option
+    ToggleSyncPanes { target: String },
+
+    /// Move the window of `src_target` (a pane id, which the move keeps) to
+    /// the end of `dst_session` (a target such as `=dev`)
+    MoveWindow { src_target: String, dst_session: String },
+
+    /// Rotate pane positions within a window (`-D` when `reverse`)
+    RotateWindow { target: String, reverse: bool },
+
+    /// Apply a preset layout to a window
+    SelectLayout { target: String, layout: String },
+
+    /// Move a pane's `direction` edge by `amount` cells (`resize-pane`)
+    ResizePane {
+        target: String,
+        direction: PaneDirection,
+        amount: u16,
+    },
+
+    /// Split a pane: side by side (`-h`), or stacked (`-v`) when `vertical`
+    SplitPane { target: String, vertical: bool },
+
+    /// Kill a pane by id, optionally as one item of a UI batch
+    KillPane { id: String, batch: Option<u64> },
+
+    /// Restart a dead pane's command in place by id
+    RespawnPane { id: String, batch: Option<u64> },
 
-    /// Send keys to a pane
+    /// Send keys to a pane, followed by the `submit_keys` key names. With
+    /// `literal`, `keys` is typed as text; otherwise it is whitespace-separated
+    /// tmux key names (`Escape`, `C-c`, `F5`, …). `batch` tags one pane of a
+    /// broadcast
     SendKeys {
         target: String,
         keys: String,
+        literal: bool,
+        submit_keys: Vec<String>,
+        batch: Option<u64>,
         reply: Option<oneshot::Sender<TmuxResponse>>,
     },
 
+    /// Put `content` in a new tmux paste buffer (`load-buffer -`)
+    LoadBuffer { content: String },
+
+    /// Point the actor at another tmux server (see [`crate::server`])
+    SwitchServer { server: Option<String> },
+
+    /// Leave `fields` (a reloaded `behavior.skip_fields`) out of the refresh
+    /// listings from now on, and refresh with them
+    SetSkipFields { fields: Vec<String> },
+
     /// Switch client to a target
     SwitchClient {
         target: String,
@@ -36,25 +104,69 @@ pub enum TmuxCommand {
     },
 }
 
+impl TmuxCommand {
+    /// Whether the command leaves tmux as it is: listing and capturing, or
+    /// pointing the deck at another server. Only these run in `--read-only`.
+    pub fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            TmuxCommand::RefreshAll
+                | TmuxCommand::CapturePane { .. }
+                | TmuxCommand::SnapshotPane { .. }
+                | TmuxCommand::SwitchServer { .. }
+                | TmuxCommand::SetSkipFields { .. }
+        )
+    }
+}
+
+/// Which edge of a pane [`TmuxCommand::ResizePane`] moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl PaneDirection {
+    /// The `resize-pane` flag for this direction.
+    pub fn flag(self) -> &'static str {
+        match self {
+            PaneDirection::Left => "-L",
+            PaneDirection::Right => "-R",
+            PaneDirection::Up => "-U",
+            PaneDirection::Down => "-D",
+        }
+    }
+}
+
 // =============================================================================
 // TmuxActor Responses (TmuxActor → UIActor)
 // =============================================================================
 
 #[derive(Debug, Clone)]
 pub enum TmuxResponse {
-    /// Sessions data refreshed
-    SessionsRefreshed { sessions: Vec<TmuxSession> },
+    /// Sessions data refreshed, `elapsed_ms` after the refresh began
+    SessionsRefreshed {
+        sessions: Vec<TmuxSession>,
+        elapsed_ms: u64,
+    },
 
-    /// Pane content captured
+    /// Pane content captured, with the fraction of its raw bytes that are
+    /// printable (see [`crate::preview::printable_ratio`])
     PaneCaptured {
-        #[allow(dead_code)]
         target: String,
         content: String,
+        printable: f64,
     },
 
-    /// Session created result
+    /// Plain pane capture for an output inspection
+    PaneSnapshot { target: String, content: String },
+
+    /// Session created result (`batch` echoes a template command's)
     SessionCreated {
         name: String,
+        batch: Option<u64>,
         success: bool,
         error: Option<String>,
     },
@@ -65,14 +177,97 @@ pub enum TmuxResponse {
         error: Option<String>,
     },
 
-    /// Session killed result
+    /// Session killed result (`name` and `batch` echo the command)
     SessionKilled {
+        name: String,
+        batch: Option<u64>,
         success: bool,
         error: Option<String>,
     },
 
-    /// Keys sent result
+    /// Window created result
+    WindowCreated {
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Window renamed result
+    WindowRenamed {
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Window killed result
+    WindowKilled {
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// `synchronize-panes` toggled result; `on` is the new setting
+    PanesSynchronized {
+        on: bool,
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Window moved result, for the pane id the move was given
+    WindowMoved {
+        src_target: String,
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Window rotated result
+    WindowRotated {
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Layout applied result
+    LayoutSelected {
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Pane resize result
+    PaneResized {
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Pane split result
+    PaneSplit {
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Pane killed result (`id` and `batch` echo the command)
+    PaneKilled {
+        id: String,
+        batch: Option<u64>,
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Pane respawned result (`id` and `batch` echo the command)
+    PaneRespawned {
+        id: String,
+        batch: Option<u64>,
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Paste buffer loaded result, with the number of lines copied
+    BufferLoaded {
+        lines: usize,
+        success: bool,
+        error: Option<String>,
+    },
+
+    /// Keys sent result (`target` and `batch` echo the command)
     KeysSent {
+        target: String,
+        batch: Option<u64>,
         #[allow(dead_code)]
         success: bool,
         error: Option<String>,
@@ -86,6 +281,16 @@ pub enum TmuxResponse {
         error: Option<String>,
     },
 
+    /// The actor now talks to `server`
+    ServerSwitched { server: Option<String> },
+
+    /// The tmux server came back under a new pid; sent just before the
+    /// listing that noticed it
+    ServerRestarted,
+
+    /// A refresh was abandoned after the UI cancelled it
+    RefreshCancelled,
+
     /// Error occurred
     Error { message: String },
 }
@@ -111,19 +316,36 @@ pub enum UIEvent {
 // Shared State for RefreshActor coordination
 // =============================================================================
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct RefreshControl {
     /// Whether refresh is paused (during input mode or popup)
     paused: Arc<AtomicBool>,
+    /// Bumped by every cancel; a refresh begun under an older epoch stops
+    /// between steps and the queued captures are dropped.
+    cancel_epoch: Arc<AtomicU64>,
+    /// Whether the TmuxActor is running a refresh, i.e. there is work to cancel.
+    busy: Arc<AtomicBool>,
+    /// Whether a tick's captures are queued or running. The RefreshActor
+    /// holds back the next tick until they are done, so a short interval
+    /// against a slow tmux never piles up work.
+    capture_pending: Arc<AtomicBool>,
+    /// The tick interval in milliseconds. The RefreshActor re-reads it every
+    /// tick and restarts its ticker when it changed (config reload).
+    interval_ms: Arc<AtomicU64>,
 }
 
 impl RefreshControl {
     pub fn new() -> Self {
         Self {
             paused: Arc::new(AtomicBool::new(false)),
+            cancel_epoch: Arc::new(AtomicU64::new(0)),
+            busy: Arc::new(AtomicBool::new(false)),
+            capture_pending: Arc::new(AtomicBool::new(false)),
+            interval_ms: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -138,6 +360,38 @@ impl RefreshControl {
     pub fn is_paused(&self) -> bool {
         self.paused.load(Ordering::SeqCst)
     }
+
+    pub fn cancel(&self) {
+        self.cancel_epoch.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn cancel_epoch(&self) -> u64 {
+        self.cancel_epoch.load(Ordering::SeqCst)
+    }
+
+    pub fn set_busy(&self, busy: bool) {
+        self.busy.store(busy, Ordering::SeqCst);
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.busy.load(Ordering::SeqCst)
+    }
+
+    pub fn set_capture_pending(&self, pending: bool) {
+        self.capture_pending.store(pending, Ordering::SeqCst);
+    }
+
+    pub fn is_capture_pending(&self) -> bool {
+        self.capture_pending.load(Ordering::SeqCst)
+    }
+
+    pub fn set_interval(&self, interval: Duration) {
+        self.interval_ms.store(interval.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.load(Ordering::SeqCst))
+    }
 }
 
 impl Default for RefreshControl {
@@ -1,6 +1,6 @@
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::time::interval;
+use tokio::time::{Instant, interval, interval_at};
 
 use crate::actor::messages::{RefreshControl, TmuxCommand, UIEvent};
 
@@ -23,6 +23,7 @@ impl RefreshActor {
         refresh_control: RefreshControl,
         interval: Duration,
     ) -> Self {
+        refresh_control.set_interval(interval);
         Self {
             tmux_tx,
             ui_event_tx,
@@ -32,13 +33,23 @@ impl RefreshActor {
     }
 
     pub async fn run(self) {
-        let mut ticker = interval(self.interval);
+        let mut current = self.interval;
+        let mut ticker = interval(current);
 
         loop {
             ticker.tick().await;
 
-            // Check if refresh is paused (input mode or popup active)
-            if self.refresh_control.is_paused() {
+            // A reloaded config may have changed the interval: restart the
+            // ticker on the new period, starting one period from now.
+            let wanted = self.refresh_control.interval();
+            if wanted != current && !wanted.is_zero() {
+                current = wanted;
+                ticker = interval_at(Instant::now() + current, current);
+            }
+
+            // Check if refresh is paused (input mode or popup active), or the
+            // last tick's captures have not come back yet.
+            if self.refresh_control.is_paused() || self.refresh_control.is_capture_pending() {
                 continue;
             }
 
@@ -56,3 +67,55 @@ impl RefreshActor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A tmux slower than the interval: while the captures of one tick are
+    /// outstanding, no further ticks are sent, however many intervals pass.
+    #[tokio::test]
+    async fn ticks_wait_for_outstanding_captures() {
+        let (tmux_tx, _tmux_rx) = mpsc::channel(32);
+        let (ui_tx, mut ui_rx) = mpsc::channel(32);
+        let control = RefreshControl::new();
+        let interval = Duration::from_millis(5);
+        let actor = RefreshActor::new(tmux_tx, ui_tx, control.clone(), interval);
+        control.set_capture_pending(true);
+        let handle = tokio::spawn(actor.run());
+
+        tokio::time::sleep(interval * 20).await;
+        assert!(ui_rx.try_recv().is_err(), "ticked while a capture was pending");
+
+        // The capture comes back: ticking resumes.
+        control.set_capture_pending(false);
+        let tick = tokio::time::timeout(interval * 20, ui_rx.recv()).await;
+        assert!(matches!(tick, Ok(Some(UIEvent::Tick))));
+        handle.abort();
+    }
+
+    /// A config reload sets a new interval: the ticker picks it up after the
+    /// tick it is waiting for.
+    #[tokio::test]
+    async fn a_new_interval_restarts_the_ticker() {
+        let (tmux_tx, _tmux_rx) = mpsc::channel(32);
+        let (ui_tx, mut ui_rx) = mpsc::channel(32);
+        let control = RefreshControl::new();
+        let actor = RefreshActor::new(tmux_tx, ui_tx, control.clone(), Duration::from_millis(300));
+        assert_eq!(control.interval(), Duration::from_millis(300));
+        let handle = tokio::spawn(actor.run());
+        // The first tick is immediate.
+        assert!(matches!(ui_rx.recv().await, Some(UIEvent::Tick)));
+
+        control.set_interval(Duration::from_millis(5));
+        // Ten ticks at the old interval would take three seconds.
+        let ticks = tokio::time::timeout(Duration::from_secs(1), async {
+            for _ in 0..10 {
+                ui_rx.recv().await;
+            }
+        })
+        .await;
+        assert!(ticks.is_ok(), "still ticking at the old interval");
+        handle.abort();
+    }
+}
@@ -1,6 +1,7 @@
+use std::collections::VecDeque;
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, ChildStdin, ChildStdout, Command};
@@ -8,8 +9,11 @@ use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tracing::{debug, warn};
 
-use crate::actor::messages::{TmuxCommand, TmuxResponse};
-use crate::app::{TmuxPane, TmuxSession, TmuxWindow};
+use crate::actor::messages::{PaneDirection, RefreshControl, TmuxCommand, TmuxResponse};
+use crate::app::{TmuxPane, TmuxSession, TmuxWindow, session_target};
+use crate::config::{SessionTemplate, TemplateWindow};
+use crate::preview::printable_ratio;
+use crate::server::socket_args;
 
 // =============================================================================
 // TmuxActor — control-mode based, with fork+exec fallback
@@ -27,8 +31,24 @@ use crate::app::{TmuxPane, TmuxSession, TmuxWindow};
 pub struct TmuxActor {
     command_rx: mpsc::Receiver<TmuxCommand>,
     capture_rx: mpsc::Receiver<TmuxCommand>,
+    /// Captures taken off `capture_rx` and not run yet, at most one per
+    /// target (see [`queue_capture`]).
+    captures: VecDeque<TmuxCommand>,
     response_tx: mpsc::Sender<TmuxResponse>,
     ctrl: Option<ControlMode>,
+    refresh_control: RefreshControl,
+    /// Last cancel epoch acted on (see [`TmuxActor::take_cancel`]).
+    seen_epoch: u64,
+    /// tmux server every command goes to (see [`crate::server`]).
+    server: Option<String>,
+    /// `-F` formats of the four `refresh_all` listings, with the
+    /// `behavior.skip_fields` variables left out.
+    formats: [String; 4],
+    /// Pid of the tmux server the last refresh listed, to notice a restart.
+    server_pid: Option<u32>,
+    /// `--read-only`: refuse every command that would change tmux, whatever
+    /// the UI let through.
+    read_only: bool,
 }
 
 struct ControlMode {
@@ -55,7 +75,7 @@ enum CtrlEvent {
     Begin,
     End,
     Error,
-    Line(String),
+    Line(Vec<u8>),
     Closed,
 }
 
@@ -64,29 +84,58 @@ impl TmuxActor {
         command_rx: mpsc::Receiver<TmuxCommand>,
         capture_rx: mpsc::Receiver<TmuxCommand>,
         response_tx: mpsc::Sender<TmuxResponse>,
+        refresh_control: RefreshControl,
+        skip_fields: &[String],
+        read_only: bool,
     ) -> Self {
+        let seen_epoch = refresh_control.cancel_epoch();
+        let formats = refresh_formats(skip_fields);
         Self {
             command_rx,
             capture_rx,
+            captures: VecDeque::new(),
             response_tx,
             ctrl: None,
+            refresh_control,
+            seen_epoch,
+            server: None,
+            formats,
+            server_pid: None,
+            read_only,
         }
     }
 
     pub async fn run(mut self) {
         // Try to connect control mode eagerly so the first refresh is fast.
-        self.ctrl = Self::try_connect_control().await;
+        self.ctrl = Self::try_connect_control(None).await;
 
         loop {
             // tokio::select! requires the future inside notify_rx.recv() to be
             // present; build it as a guarded branch so it's only polled when a
             // connection exists.
-            let cmd = {
+            let (cmd, capture) = {
                 let notify_available = self.ctrl.is_some();
+                let queued = !self.captures.is_empty();
                 tokio::select! {
                     biased;
-                    Some(c) = self.command_rx.recv() => c,
-                    Some(c) = self.capture_rx.recv() => c,
+                    Some(c) = self.command_rx.recv() => (c, false),
+                    // Captures run one at a time between user commands, the
+                    // oldest target first.
+                    () = std::future::ready(()), if queued => {
+                        self.drain_captures();
+                        if self.take_cancel() {
+                            continue;
+                        }
+                        (self.captures.pop_front().expect("queued"), true)
+                    }
+                    Some(c) = self.capture_rx.recv() => {
+                        queue_capture(&mut self.captures, c);
+                        self.drain_captures();
+                        if self.take_cancel() {
+                            continue;
+                        }
+                        (self.captures.pop_front().expect("just queued"), true)
+                    }
                     Some(()) = async {
                         if notify_available {
                             self.ctrl.as_mut().unwrap().notify_rx.recv().await
@@ -100,12 +149,19 @@ impl TmuxActor {
                         if let Some(ctrl) = self.ctrl.as_mut() {
                             while ctrl.notify_rx.try_recv().is_ok() {}
                         }
-                        TmuxCommand::RefreshAll
+                        (TmuxCommand::RefreshAll, false)
                     }
                     else => break,
                 }
             };
             let response = self.handle_command(cmd).await;
+            // The tick's captures are done once none is left to run.
+            if capture {
+                self.drain_captures();
+                if self.captures.is_empty() {
+                    self.refresh_control.set_capture_pending(false);
+                }
+            }
             if self.response_tx.send(response).await.is_err() {
                 break;
             }
@@ -117,40 +173,158 @@ impl TmuxActor {
         }
     }
 
+    /// Whether the UI cancelled since this was last asked. A cancel also
+    /// drops every capture still queued.
+    fn take_cancel(&mut self) -> bool {
+        let epoch = self.refresh_control.cancel_epoch();
+        if epoch == self.seen_epoch {
+            return false;
+        }
+        self.seen_epoch = epoch;
+        while self.capture_rx.try_recv().is_ok() {}
+        self.captures.clear();
+        self.refresh_control.set_capture_pending(false);
+        true
+    }
+
+    /// Move every capture waiting on `capture_rx` into the queue.
+    fn drain_captures(&mut self) {
+        while let Ok(c) = self.capture_rx.try_recv() {
+            queue_capture(&mut self.captures, c);
+        }
+    }
+
     async fn handle_command(&mut self, cmd: TmuxCommand) -> TmuxResponse {
+        if self.read_only && !cmd.is_read_only() {
+            debug!("read-only mode: refused a command that changes tmux");
+            let response = TmuxResponse::Error {
+                message: "read-only mode: commands that change tmux are disabled".to_string(),
+            };
+            // A caller waiting on the reply hears the refusal too.
+            if let TmuxCommand::SendKeys { reply: Some(tx), .. }
+            | TmuxCommand::SwitchClient { reply: Some(tx), .. } = cmd
+            {
+                let _ = tx.send(response.clone());
+            }
+            return response;
+        }
         match cmd {
             TmuxCommand::RefreshAll => {
                 debug!("refresh all");
-                self.refresh_all().await
+                self.refresh_control.set_busy(true);
+                let response = self.refresh_all().await;
+                self.refresh_control.set_busy(false);
+                response
+            }
+            TmuxCommand::CapturePane { target, start, end, join } => {
+                debug!("capture-pane: target={target} range({start}, {end}) join={join}");
+                self.capture_pane(&target, start, end, join).await
             }
-            TmuxCommand::CapturePane { target, start, end } => {
-                debug!("capture-pane: target={target} range({start}, {end})");
-                self.capture_pane(&target, start, end).await
+            TmuxCommand::SnapshotPane { target, start } => {
+                debug!("snapshot pane: target={target} from {start}");
+                self.snapshot_pane(&target, start).await
             }
             TmuxCommand::NewSession { name } => {
                 debug!("new-session");
                 self.new_session(&name).await
             }
+            TmuxCommand::NewSessionFromTemplate { name, template, batch } => {
+                debug!("new-session (template {})", template.name);
+                self.new_session_from_template(&name, &template, batch).await
+            }
             TmuxCommand::RenameSession { old_name, new_name } => {
                 debug!("rename-session");
                 self.rename_session(&old_name, &new_name).await
             }
-            TmuxCommand::KillSession { name } => {
+            TmuxCommand::KillSession { name, batch } => {
                 debug!("kill-session");
-                self.kill_session(&name).await
+                self.kill_session(&name, batch).await
+            }
+            TmuxCommand::NewWindow { session, name } => {
+                debug!("new-window");
+                self.new_window(&session, name.as_deref()).await
+            }
+            TmuxCommand::ToggleSyncPanes { target } => {
+                debug!("toggle synchronize-panes: {target}");
+                self.toggle_sync_panes(&target).await
+            }
+            TmuxCommand::MoveWindow { src_target, dst_session } => {
+                debug!("move-window: {src_target} -> {dst_session}");
+                self.move_window(&src_target, &dst_session).await
+            }
+            TmuxCommand::RenameWindow { target, new_name } => {
+                debug!("rename-window");
+                self.rename_window(&target, &new_name).await
+            }
+            TmuxCommand::KillWindow { target } => {
+                debug!("kill-window");
+                self.kill_window(&target).await
+            }
+            TmuxCommand::RotateWindow { target, reverse } => {
+                debug!("rotate-window");
+                self.rotate_window(&target, reverse).await
+            }
+            TmuxCommand::SelectLayout { target, layout } => {
+                debug!("select-layout");
+                self.select_layout(&target, &layout).await
+            }
+            TmuxCommand::ResizePane {
+                target,
+                direction,
+                amount,
+            } => {
+                debug!("resize-pane");
+                self.resize_pane(&target, direction, amount).await
+            }
+            TmuxCommand::SplitPane { target, vertical } => {
+                debug!("split-window");
+                self.split_pane(&target, vertical).await
+            }
+            TmuxCommand::KillPane { id, batch } => {
+                debug!("kill-pane");
+                self.kill_pane(id, batch).await
+            }
+            TmuxCommand::RespawnPane { id, batch } => {
+                debug!("respawn-pane");
+                self.respawn_pane(id, batch).await
             }
             TmuxCommand::SendKeys {
                 target,
                 keys,
+                literal,
+                submit_keys,
+                batch,
                 reply,
             } => {
                 debug!("send-keys");
-                let response = self.send_keys(&target, &keys).await;
+                let response = self.send_keys(target, &keys, literal, &submit_keys, batch).await;
                 if let Some(tx) = reply {
                     let _ = tx.send(response.clone());
                 }
                 response
             }
+            TmuxCommand::LoadBuffer { content } => {
+                debug!("load-buffer");
+                let lines = content.lines().count();
+                let result = Self::load_buffer(self.server.as_deref(), &content).await;
+                TmuxResponse::BufferLoaded {
+                    lines,
+                    success: result.is_ok(),
+                    error: result.err(),
+                }
+            }
+            TmuxCommand::SwitchServer { server } => {
+                debug!("switch server: {server:?}");
+                self.switch_server(server).await
+            }
+            TmuxCommand::SetSkipFields { fields } => {
+                debug!("skip fields: {fields:?}");
+                self.formats = refresh_formats(&fields);
+                self.refresh_control.set_busy(true);
+                let response = self.refresh_all().await;
+                self.refresh_control.set_busy(false);
+                response
+            }
             TmuxCommand::SwitchClient { target, reply } => {
                 debug!("switch-client");
                 let response = self.switch_client(&target).await;
@@ -166,31 +340,85 @@ impl TmuxActor {
     // Refresh All Sessions
     // =========================================================================
 
+    /// An actor that is never run, for one-shot commands outside the TUI.
+    /// Without control mode its commands go through fork-exec.
+    fn standalone(skip_fields: &[String]) -> Self {
+        let (_command_tx, command_rx) = mpsc::channel(1);
+        let (_capture_tx, capture_rx) = mpsc::channel(1);
+        let (response_tx, _response_rx) = mpsc::channel(1);
+        let control = RefreshControl::new();
+        Self::new(command_rx, capture_rx, response_tx, control, skip_fields, false)
+    }
+
+    /// One `refresh_all` outside the actor loop, for `--dump-json`.
+    pub async fn snapshot(skip_fields: &[String]) -> Result<Vec<TmuxSession>, String> {
+        match Self::standalone(skip_fields).refresh_all().await {
+            TmuxResponse::SessionsRefreshed { sessions, .. } => Ok(sessions),
+            TmuxResponse::Error { message } => Err(message),
+            other => Err(format!("unexpected response to the listing: {other:?}")),
+        }
+    }
+
     async fn refresh_all(&mut self) -> TmuxResponse {
-        // Three commands; outputs prefixed so they can be concatenated.
-        let s_args: &[&str] = &[
-            "list-sessions",
-            "-F",
-            "SESS\t#{session_name}\t#{session_activity}\t#{session_last_attached}",
-        ];
-        let w_args: &[&str] = &[
-            "list-windows",
-            "-a",
-            "-F",
-            "WIN\t#{session_name}\t#{window_index}\t#{window_name}\t#{window_active}\t#{window_activity}",
-        ];
-        let p_args: &[&str] = &[
-            "list-panes",
-            "-a",
-            "-F",
-            "PANE\t#{session_name}\t#{window_index}\t#{pane_id}\t#{pane_index}\t#{pane_width}\t#{pane_height}\t#{pane_active}\t#{pane_last}\t#{pane_current_command}\t#{pane_pid}",
-        ];
+        let started = Instant::now();
+        // A cancel issued before this refresh began is stale.
+        self.take_cancel();
+
+        // The process scan needs nothing from the listing, so the two run
+        // side by side: on a busy machine `ps` takes about as long as the
+        // four tmux listings.
+        let (listing, processes) = tokio::join!(self.list_tree(), scan_processes());
+        let stdout = match listing {
+            Ok(stdout) => stdout,
+            Err(response) => return response,
+        };
+        if self.take_cancel() {
+            debug!("refresh cancelled");
+            return TmuxResponse::RefreshCancelled;
+        }
+        // A server that came back under a new pid has new pane and window
+        // ids; the UI resets what it holds by id before this listing lands.
+        let pid = server_pid(&stdout);
+        if let (Some(old), Some(new)) = (self.server_pid, pid)
+            && old != new
+        {
+            debug!("tmux server restarted: pid {old} -> {new}");
+            let _ = self.response_tx.send(TmuxResponse::ServerRestarted).await;
+        }
+        if pid.is_some() {
+            self.server_pid = pid;
+        }
+        let mut sessions = build_sessions(&stdout);
+        if let Some(processes) = processes {
+            mark_claude_panes(&mut sessions, &processes);
+        }
+        crate::hook::apply_states(&mut sessions);
+        TmuxResponse::SessionsRefreshed {
+            sessions,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        }
+    }
 
-        // If control mode is up, send 3 commands as 3 blocks; otherwise one
+    /// The four `refresh_all` listings, concatenated. A cancel or a failure
+    /// comes back as the response to send instead.
+    async fn list_tree(&mut self) -> Result<String, TmuxResponse> {
+        // Four commands; outputs prefixed so they can be concatenated.
+        let [s_fmt, w_fmt, p_fmt, c_fmt] = self.formats.clone();
+        let s_args: &[&str] = &["list-sessions", "-F", &s_fmt];
+        let w_args: &[&str] = &["list-windows", "-a", "-F", &w_fmt];
+        let p_args: &[&str] = &["list-panes", "-a", "-F", &p_fmt];
+        // Only attached clients are listed, so unattached sessions get no size.
+        let c_args: &[&str] = &["list-clients", "-F", &c_fmt];
+
+        // If control mode is up, send 4 commands as 4 blocks; otherwise one
         // fork+exec with `;` chaining.
-        let stdout = if self.ctrl.is_some() {
+        if self.ctrl.is_some() {
             let mut buf = String::new();
-            for args in [s_args, w_args, p_args] {
+            for args in [s_args, w_args, p_args, c_args] {
+                if self.take_cancel() {
+                    debug!("refresh cancelled");
+                    return Err(TmuxResponse::RefreshCancelled);
+                }
                 match self.exec_args(args).await {
                     Ok(out) => {
                         buf.push_str(&out);
@@ -199,43 +427,62 @@ impl TmuxActor {
                         }
                     }
                     Err(e) => {
-                        return TmuxResponse::Error { message: e };
+                        return Err(TmuxResponse::Error { message: e });
                     }
                 }
             }
-            buf
+            Ok(buf)
         } else {
             // Single fork+exec with `;` chaining
-            let mut chained: Vec<&str> = Vec::with_capacity(s_args.len() + w_args.len() + p_args.len() + 2);
+            let mut chained: Vec<&str> = Vec::with_capacity(
+                s_args.len() + w_args.len() + p_args.len() + c_args.len() + 3,
+            );
             chained.extend_from_slice(s_args);
             chained.push(";");
             chained.extend_from_slice(w_args);
             chained.push(";");
             chained.extend_from_slice(p_args);
-            match Self::fork_exec(&chained).await {
-                Ok(out) => out,
-                Err(e) => return TmuxResponse::Error { message: e },
-            }
-        };
-
-        let mut sessions = build_sessions(&stdout);
-        annotate_claude_panes(&mut sessions).await;
-        crate::hook::apply_states(&mut sessions);
-        TmuxResponse::SessionsRefreshed { sessions }
+            chained.push(";");
+            chained.extend_from_slice(c_args);
+            Self::fork_exec(self.server.as_deref(), &chained)
+                .await
+                .map(|out| String::from_utf8_lossy(&out).into_owned())
+                .map_err(|e| TmuxResponse::Error { message: e })
+        }
     }
 
     // =========================================================================
     // Capture Pane
     // =========================================================================
 
-    async fn capture_pane(&mut self, target: &str, start: i32, end: i32) -> TmuxResponse {
+    async fn capture_pane(
+        &mut self,
+        target: &str,
+        start: i32,
+        end: i32,
+        join: bool,
+    ) -> TmuxResponse {
+        let args = capture_args(target, start, end, join);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        // The printable share is taken before decoding, which would fold
+        // runs of invalid bytes into single U+FFFDs.
+        match self.exec_args_raw(&args).await {
+            Ok(out) => TmuxResponse::PaneCaptured {
+                target: target.to_string(),
+                printable: printable_ratio(&out),
+                content: String::from_utf8_lossy(&out).into_owned(),
+            },
+            Err(e) => TmuxResponse::Error { message: e },
+        }
+    }
+
+    /// Like [`Self::capture_pane`], but without escapes and down to the
+    /// bottom of the pane, for comparing two captures line by line.
+    async fn snapshot_pane(&mut self, target: &str, start: i32) -> TmuxResponse {
         let start = start.to_string();
-        let end = end.to_string();
-        let args: &[&str] = &[
-            "capture-pane", "-e", "-p", "-J", "-S", &start, "-E", &end, "-t", target,
-        ];
+        let args: &[&str] = &["capture-pane", "-p", "-J", "-S", &start, "-t", target];
         match self.exec_args(args).await {
-            Ok(out) => TmuxResponse::PaneCaptured {
+            Ok(out) => TmuxResponse::PaneSnapshot {
                 target: target.to_string(),
                 content: out,
             },
@@ -252,19 +499,175 @@ impl TmuxActor {
         match self.exec_args(args).await {
             Ok(_) => TmuxResponse::SessionCreated {
                 name: name.to_string(),
+                batch: None,
                 success: true,
                 error: None,
             },
             Err(e) => TmuxResponse::SessionCreated {
                 name: name.to_string(),
+                batch: None,
                 success: false,
                 error: Some(e),
             },
         }
     }
 
+    /// Create the session `template` describes, named after the template,
+    /// outside the actor loop (`tmux-deck load`).
+    pub async fn load_template(template: &SessionTemplate) -> Result<(), String> {
+        let mut actor = Self::standalone(&[]);
+        match actor.new_session_from_template(&template.name, template, None).await {
+            TmuxResponse::SessionCreated { error: Some(e), .. } => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// The live session `name` as a template (`tmux-deck save`): its windows
+    /// with their layouts, and its panes' working directories.
+    pub async fn save_template(name: &str) -> Result<SessionTemplate, String> {
+        let mut actor = Self::standalone(&[]);
+        let target = session_target(name);
+        let [w_fmt, p_fmt] = SAVE_FORMATS;
+        let windows = actor
+            .exec_args(&["list-windows", "-t", &target, "-F", w_fmt])
+            .await?;
+        let panes = actor
+            .exec_args(&["list-panes", "-s", "-t", &target, "-F", p_fmt])
+            .await?;
+        Ok(template_from_listing(name, &windows, &panes))
+    }
+
+    /// Create `name` and lay it out from `template`. Any failure after the
+    /// session exists kills it again, so a half-built session is never left
+    /// behind; the error names the step that failed.
+    async fn new_session_from_template(
+        &mut self,
+        name: &str,
+        template: &SessionTemplate,
+        batch: Option<u64>,
+    ) -> TmuxResponse {
+        let root = template.root_dir();
+        let first = template.windows.first();
+        let mut args = vec!["new-session", "-d", "-P", "-F", "#{window_id}", "-s", name];
+        if let Some(win_name) = first.and_then(|w| w.name.as_deref()) {
+            args.extend(["-n", win_name]);
+        }
+        if let Some(dir) = root.as_deref() {
+            args.extend(["-c", dir]);
+        }
+        let first_window = match self.exec_args(&args).await {
+            Ok(out) => out.trim().to_string(),
+            // Nothing was created (e.g. the name is taken), so nothing to roll
+            // back — and killing `name` here could hit an unrelated session.
+            Err(e) => {
+                return TmuxResponse::SessionCreated {
+                    name: name.to_string(),
+                    batch,
+                    success: false,
+                    error: Some(format!("template '{}': new-session: {}", template.name, e)),
+                };
+            }
+        };
+
+        match self
+            .populate_template(name, template, root.as_deref(), first_window)
+            .await
+        {
+            Ok(()) => TmuxResponse::SessionCreated {
+                name: name.to_string(),
+                batch,
+                success: true,
+                error: None,
+            },
+            Err(step) => {
+                let _ = self
+                    .exec_args(&["kill-session", "-t", &session_target(name)])
+                    .await;
+                TmuxResponse::SessionCreated {
+                    name: name.to_string(),
+                    batch,
+                    success: false,
+                    error: Some(format!(
+                        "template '{}' aborted at {} (session rolled back)",
+                        template.name, step
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Build every window of `template` inside the freshly created session,
+    /// whose first window is `first_window`. Errors carry the failing step.
+    async fn populate_template(
+        &mut self,
+        session: &str,
+        template: &SessionTemplate,
+        root: Option<&str>,
+        first_window: String,
+    ) -> Result<(), String> {
+        let parent = format!("{}:", session_target(session));
+        for (i, window) in template.windows.iter().enumerate() {
+            let label = match window.name.as_deref() {
+                Some(n) => format!("window {} ('{}')", i + 1, n),
+                None => format!("window {}", i + 1),
+            };
+            let window_id = if i == 0 {
+                first_window.clone()
+            } else {
+                let mut args = vec!["new-window", "-d", "-P", "-F", "#{window_id}"];
+                args.extend(["-t", &parent]);
+                if let Some(n) = window.name.as_deref() {
+                    args.extend(["-n", n]);
+                }
+                if let Some(dir) = root {
+                    args.extend(["-c", dir]);
+                }
+                let out = self
+                    .exec_args(&args)
+                    .await
+                    .map_err(|e| format!("{label}: new-window: {e}"))?;
+                out.trim().to_string()
+            };
+
+            for (p, command) in window.panes.iter().enumerate() {
+                let pane_target = if p == 0 {
+                    window_id.clone()
+                } else {
+                    let mut args = vec!["split-window", "-d", "-P", "-F", "#{pane_id}"];
+                    args.extend(["-t", &window_id]);
+                    if let Some(dir) = root {
+                        args.extend(["-c", dir]);
+                    }
+                    let out = self
+                        .exec_args(&args)
+                        .await
+                        .map_err(|e| format!("{label}: split-window for pane {}: {e}", p + 1))?;
+                    out.trim().to_string()
+                };
+                // Typed literally, so a line like `Up` or `-la` is not read
+                // as a key name or a flag.
+                let enter = ["Enter".to_string()];
+                for line in command.lines().filter(|l| !l.trim().is_empty()) {
+                    for args in send_keys_args(&pane_target, line, true, &enter) {
+                        self.exec_args(&args)
+                            .await
+                            .map_err(|e| format!("{label}: send-keys to pane {}: {e}", p + 1))?;
+                    }
+                }
+            }
+
+            if let Some(layout) = window.layout.as_deref() {
+                self.exec_args(&["select-layout", "-t", &window_id, layout])
+                    .await
+                    .map_err(|e| format!("{label}: select-layout '{layout}': {e}"))?;
+            }
+        }
+        Ok(())
+    }
+
     async fn rename_session(&mut self, old_name: &str, new_name: &str) -> TmuxResponse {
-        let args: &[&str] = &["rename-session", "-t", old_name, new_name];
+        let target = session_target(old_name);
+        let args: &[&str] = &["rename-session", "-t", &target, new_name];
         match self.exec_args(args).await {
             Ok(_) => TmuxResponse::SessionRenamed {
                 success: true,
@@ -277,14 +680,137 @@ impl TmuxActor {
         }
     }
 
-    async fn kill_session(&mut self, name: &str) -> TmuxResponse {
-        let args: &[&str] = &["kill-session", "-t", name];
+    async fn kill_session(&mut self, name: &str, batch: Option<u64>) -> TmuxResponse {
+        let target = session_target(name);
+        let result = self.exec_args(&["kill-session", "-t", &target]).await;
+        TmuxResponse::SessionKilled {
+            name: name.to_string(),
+            batch,
+            success: result.is_ok(),
+            error: result.err(),
+        }
+    }
+
+    // =========================================================================
+    // Window Operations
+    // =========================================================================
+
+    async fn new_window(&mut self, session: &str, name: Option<&str>) -> TmuxResponse {
+        // A trailing `:` targets the session, so tmux picks the next free index.
+        let target = format!("{session}:");
+        let mut args: Vec<&str> = vec!["new-window", "-t", &target];
+        if let Some(name) = name {
+            args.extend(["-n", name]);
+        }
+        match self.exec_args(&args).await {
+            Ok(_) => TmuxResponse::WindowCreated {
+                success: true,
+                error: None,
+            },
+            Err(e) => TmuxResponse::WindowCreated {
+                success: false,
+                error: Some(e),
+            },
+        }
+    }
+
+    async fn rename_window(&mut self, target: &str, new_name: &str) -> TmuxResponse {
+        let args: &[&str] = &["rename-window", "-t", target, new_name];
+        match self.exec_args(args).await {
+            Ok(_) => TmuxResponse::WindowRenamed {
+                success: true,
+                error: None,
+            },
+            Err(e) => TmuxResponse::WindowRenamed {
+                success: false,
+                error: Some(e),
+            },
+        }
+    }
+
+    /// Read the window's `synchronize-panes` and set it the other way.
+    async fn toggle_sync_panes(&mut self, target: &str) -> TmuxResponse {
+        let args: &[&str] = &["display", "-p", "-t", target, "#{synchronize-panes}"];
+        let result = match self.exec_args(args).await {
+            Ok(current) => {
+                let on = current.trim() != "1";
+                let value = if on { "on" } else { "off" };
+                self.exec_args(&["set-option", "-w", "-t", target, "synchronize-panes", value])
+                    .await
+                    .map(|_| on)
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(on) => TmuxResponse::PanesSynchronized {
+                on,
+                success: true,
+                error: None,
+            },
+            Err(e) => TmuxResponse::PanesSynchronized {
+                on: false,
+                success: false,
+                error: Some(e),
+            },
+        }
+    }
+
+    /// `move-window` to the first free index of `dst_session`, or after its
+    /// current window when tmux finds the index taken (`index in use`).
+    async fn move_window(&mut self, src_target: &str, dst_session: &str) -> TmuxResponse {
+        let dst = format!("{dst_session}:");
+        let mut result = self.exec_args(&["move-window", "-s", src_target, "-t", &dst]).await;
+        if let Err(e) = &result
+            && e.contains("index in use")
+        {
+            debug!("move-window: {e}, appending instead");
+            result = self.exec_args(&["move-window", "-a", "-s", src_target, "-t", &dst]).await;
+        }
+        TmuxResponse::WindowMoved {
+            src_target: src_target.to_string(),
+            success: result.is_ok(),
+            error: result.err(),
+        }
+    }
+
+    async fn kill_window(&mut self, target: &str) -> TmuxResponse {
+        let args: &[&str] = &["kill-window", "-t", target];
+        match self.exec_args(args).await {
+            Ok(_) => TmuxResponse::WindowKilled {
+                success: true,
+                error: None,
+            },
+            Err(e) => TmuxResponse::WindowKilled {
+                success: false,
+                error: Some(e),
+            },
+        }
+    }
+
+    async fn rotate_window(&mut self, target: &str, reverse: bool) -> TmuxResponse {
+        // Upward (-U) is tmux's own default (`C-o`); -D mirrors `M-o`.
+        let direction = if reverse { "-D" } else { "-U" };
+        let args: &[&str] = &["rotate-window", direction, "-t", target];
         match self.exec_args(args).await {
-            Ok(_) => TmuxResponse::SessionKilled {
+            Ok(_) => TmuxResponse::WindowRotated {
                 success: true,
                 error: None,
             },
-            Err(e) => TmuxResponse::SessionKilled {
+            Err(e) => TmuxResponse::WindowRotated {
+                success: false,
+                error: Some(e),
+            },
+        }
+    }
+
+    async fn select_layout(&mut self, target: &str, layout: &str) -> TmuxResponse {
+        let args: &[&str] = &["select-layout", "-t", target, layout];
+        match self.exec_args(args).await {
+            Ok(_) => TmuxResponse::LayoutSelected {
+                success: true,
+                error: None,
+            },
+            Err(e) => TmuxResponse::LayoutSelected {
                 success: false,
                 error: Some(e),
             },
@@ -295,20 +821,84 @@ impl TmuxActor {
     // Pane Operations
     // =========================================================================
 
-    async fn send_keys(&mut self, target: &str, keys: &str) -> TmuxResponse {
-        let args: &[&str] = &["send-keys", "-t", target, keys, "Enter"];
+    async fn resize_pane(
+        &mut self,
+        target: &str,
+        direction: PaneDirection,
+        amount: u16,
+    ) -> TmuxResponse {
+        let amount = amount.to_string();
+        let args: &[&str] = &["resize-pane", "-t", target, direction.flag(), &amount];
         match self.exec_args(args).await {
-            Ok(_) => TmuxResponse::KeysSent {
+            Ok(_) => TmuxResponse::PaneResized {
                 success: true,
                 error: None,
             },
-            Err(e) => TmuxResponse::KeysSent {
+            Err(e) => TmuxResponse::PaneResized {
                 success: false,
                 error: Some(e),
             },
         }
     }
 
+    async fn send_keys(
+        &mut self,
+        target: String,
+        keys: &str,
+        literal: bool,
+        submit_keys: &[String],
+        batch: Option<u64>,
+    ) -> TmuxResponse {
+        let mut result = Ok(());
+        for args in send_keys_args(&target, keys, literal, submit_keys) {
+            if let Err(e) = self.exec_args(&args).await {
+                result = Err(e);
+                break;
+            }
+        }
+        TmuxResponse::KeysSent {
+            target,
+            batch,
+            success: result.is_ok(),
+            error: result.err(),
+        }
+    }
+
+    async fn split_pane(&mut self, target: &str, vertical: bool) -> TmuxResponse {
+        let direction = if vertical { "-v" } else { "-h" };
+        let args: &[&str] = &["split-window", direction, "-t", target];
+        match self.exec_args(args).await {
+            Ok(_) => TmuxResponse::PaneSplit {
+                success: true,
+                error: None,
+            },
+            Err(e) => TmuxResponse::PaneSplit {
+                success: false,
+                error: Some(e),
+            },
+        }
+    }
+
+    async fn kill_pane(&mut self, id: String, batch: Option<u64>) -> TmuxResponse {
+        let result = self.exec_args(&["kill-pane", "-t", &id]).await;
+        TmuxResponse::PaneKilled {
+            id,
+            batch,
+            success: result.is_ok(),
+            error: result.err(),
+        }
+    }
+
+    async fn respawn_pane(&mut self, id: String, batch: Option<u64>) -> TmuxResponse {
+        let result = self.exec_args(&["respawn-pane", "-t", &id]).await;
+        TmuxResponse::PaneRespawned {
+            id,
+            batch,
+            success: result.is_ok(),
+            error: result.err(),
+        }
+    }
+
     async fn switch_client(&mut self, target: &str) -> TmuxResponse {
         let log_path = "/tmp/tmux-deck.log";
         // Without -c, tmux's default target-client is the most recently
@@ -330,7 +920,7 @@ impl TmuxActor {
         // switch-client itself must still go via fork+exec — running it
         // through the control-mode pipe would just switch the control
         // client.
-        match Self::fork_exec(&args).await {
+        match Self::fork_exec(self.server.as_deref(), &args).await {
             Ok(_) => {
                 append_switch_log(log_path, target, true, None);
                 TmuxResponse::ClientSwitched {
@@ -355,38 +945,76 @@ impl TmuxActor {
     /// mode client and has a tty. Returns None if no such client exists
     /// (e.g. no one is attached) — the caller then falls back to running
     /// switch-client without -c.
+    ///
+    /// When tmux-deck itself runs inside tmux (`$TMUX_PANE` set, in a pane or
+    /// a popup), clients showing the deck's own session win over the rest, so
+    /// the switch moves the client the user is looking at rather than another
+    /// one that merely typed last.
     async fn find_interactive_client_tty(&mut self) -> Option<String> {
+        let own_session = match std::env::var("TMUX_PANE") {
+            Ok(pane) => self
+                .exec_args(&["display-message", "-p", "-t", &pane, "#{session_name}"])
+                .await
+                .ok()
+                .map(|s| s.trim().to_string()),
+            Err(_) => None,
+        };
         let args: &[&str] = &[
             "list-clients",
             "-F",
-            "#{client_tty}\t#{client_control_mode}\t#{client_activity}",
+            "#{client_tty}\t#{client_control_mode}\t#{client_activity}\t#{client_session}",
         ];
         let out = self.exec_args(args).await.ok()?;
-        let mut best: Option<(i64, String)> = None;
+        let mut best: Option<((bool, i64), String)> = None;
         for line in out.lines() {
             let mut it = line.split('\t');
             let tty = it.next().unwrap_or("");
             let control = it.next().unwrap_or("0");
             let activity: i64 = it.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+            let session = it.next().unwrap_or("");
             if control == "1" || tty.is_empty() {
                 continue;
             }
+            let rank = (own_session.as_deref() == Some(session), activity);
             match &best {
-                Some((a, _)) if *a >= activity => {}
-                _ => best = Some((activity, tty.to_string())),
+                Some((r, _)) if *r >= rank => {}
+                _ => best = Some((rank, tty.to_string())),
             }
         }
         best.map(|(_, t)| t)
     }
 
+    /// Point every later command at `server`. The control-mode client is
+    /// dropped and reconnected there; a server that is not running leaves the
+    /// deck on fork+exec until it is.
+    async fn switch_server(&mut self, server: Option<String>) -> TmuxResponse {
+        if let Some(mut ctrl) = self.ctrl.take() {
+            let _ = ctrl.child.kill().await;
+        }
+        self.server = server;
+        // Another server's pid is not a restart.
+        self.server_pid = None;
+        self.ctrl = Self::try_connect_control(self.server.as_deref()).await;
+        TmuxResponse::ServerSwitched {
+            server: self.server.clone(),
+        }
+    }
+
     // =========================================================================
     // Backend dispatch: control mode preferred, fork+exec fallback
     // =========================================================================
 
     async fn exec_args(&mut self, args: &[&str]) -> Result<String, String> {
+        let out = self.exec_args_raw(args).await?;
+        Ok(String::from_utf8_lossy(&out).into_owned())
+    }
+
+    /// Like [`Self::exec_args`], but with the output as tmux wrote it, for
+    /// callers that need to see bytes invalid UTF-8 would decode away.
+    async fn exec_args_raw(&mut self, args: &[&str]) -> Result<Vec<u8>, String> {
         // Ensure we have a connected control mode (lazy reconnect).
         if self.ctrl.is_none() {
-            self.ctrl = Self::try_connect_control().await;
+            self.ctrl = Self::try_connect_control(self.server.as_deref()).await;
         }
 
         if self.ctrl.is_some() {
@@ -405,10 +1033,10 @@ impl TmuxActor {
             }
         }
 
-        Self::fork_exec(args).await
+        Self::fork_exec(self.server.as_deref(), args).await
     }
 
-    async fn exec_via_ctrl(&mut self, cmd: &str) -> Result<String, ControlExecError> {
+    async fn exec_via_ctrl(&mut self, cmd: &str) -> Result<Vec<u8>, ControlExecError> {
         let ctrl = self
             .ctrl
             .as_mut()
@@ -435,7 +1063,7 @@ impl TmuxActor {
             .map_err(|e| ControlExecError::Io(e.to_string()))?;
 
         // Consume events from the reader task until End / Error / Closed.
-        let mut buf = String::new();
+        let mut buf = Vec::new();
         let mut in_block = false;
         loop {
             let event = match ctrl.response_rx.recv().await {
@@ -445,23 +1073,26 @@ impl TmuxActor {
             match event {
                 CtrlEvent::Begin => in_block = true,
                 CtrlEvent::Line(l) if in_block => {
-                    buf.push_str(&l);
-                    buf.push('\n');
+                    buf.extend_from_slice(&l);
+                    buf.push(b'\n');
                 }
                 CtrlEvent::Line(_) => {
                     // Out-of-block content is unexpected; ignore.
                 }
                 CtrlEvent::End => return Ok(buf),
-                CtrlEvent::Error => return Err(ControlExecError::Protocol(buf)),
+                CtrlEvent::Error => {
+                    let msg = String::from_utf8_lossy(&buf).into_owned();
+                    return Err(ControlExecError::Protocol(msg));
+                }
                 CtrlEvent::Closed => return Err(ControlExecError::Io("stdout closed".to_string())),
             }
         }
     }
 
-    async fn try_connect_control() -> Option<ControlMode> {
+    async fn try_connect_control(server: Option<&str>) -> Option<ControlMode> {
         // Pick any existing session to attach control mode to. Without a
         // session, `tmux -C attach` errors and exits immediately.
-        let session = match Self::first_session_name().await {
+        let session = match Self::first_session_name(server).await {
             Some(s) => s,
             None => {
                 debug!("no tmux sessions; control mode disabled");
@@ -469,8 +1100,8 @@ impl TmuxActor {
             }
         };
 
-        let mut child = match Command::new("tmux")
-            .args(["-C", "attach", "-t", &session])
+        let mut child = match tmux(server)
+            .args(["-C", "attach", "-t", &session_target(&session)])
             .stdin(std::process::Stdio::piped())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::null())
@@ -494,15 +1125,15 @@ impl TmuxActor {
         // produces a %begin..%end block; that first block belongs to the
         // implicit attach command and is drained here before we hand control
         // back to the actor.
-        let mut reader_stdout = BufReader::new(stdout).lines();
+        let mut reader_stdout = BufReader::new(stdout).split(b'\n');
         // Wait for the implicit attach block to complete.
         let mut saw_first_block = false;
         loop {
-            match tokio::time::timeout(Duration::from_millis(500), reader_stdout.next_line()).await {
+            match tokio::time::timeout(Duration::from_millis(500), next_line(&mut reader_stdout)).await {
                 Ok(Ok(Some(line))) => {
-                    if line.starts_with("%begin ") {
+                    if line.starts_with(b"%begin ") {
                         saw_first_block = true;
-                    } else if saw_first_block && (line.starts_with("%end ") || line.starts_with("%error ")) {
+                    } else if saw_first_block && (line.starts_with(b"%end ") || line.starts_with(b"%error ")) {
                         break;
                     }
                     // Drop notifications during preamble — they relate to our
@@ -531,8 +1162,8 @@ impl TmuxActor {
         })
     }
 
-    async fn first_session_name() -> Option<String> {
-        let output = Command::new("tmux")
+    async fn first_session_name(server: Option<&str>) -> Option<String> {
+        let output = tmux(server)
             .args(["list-sessions", "-F", "#{session_name}"])
             .output()
             .await
@@ -544,20 +1175,51 @@ impl TmuxActor {
         s.lines().next().map(|l| l.to_string())
     }
 
-    async fn fork_exec(args: &[&str]) -> Result<String, String> {
-        let output = Command::new("tmux")
+    /// `load-buffer -` with `content` on stdin. Always a fork+exec: control
+    /// mode has no way to feed a command's stdin.
+    async fn load_buffer(server: Option<&str>, content: &str) -> Result<(), String> {
+        let mut child = tmux(server)
+            .args(["load-buffer", "-"])
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("tmux: {e}"))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(content.as_bytes())
+                .await
+                .map_err(|e| format!("tmux load-buffer: {e}"))?;
+        }
+        let output = child.wait_with_output().await.map_err(|e| format!("tmux: {e}"))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).to_string())
+        }
+    }
+
+    async fn fork_exec(server: Option<&str>, args: &[&str]) -> Result<Vec<u8>, String> {
+        let output = tmux(server)
             .args(args)
             .output()
             .await
             .map_err(|e| format!("tmux: {e}"))?;
         if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+            Ok(output.stdout)
         } else {
             Err(String::from_utf8_lossy(&output.stderr).to_string())
         }
     }
 }
 
+/// A `tmux` invocation addressed to `server`.
+fn tmux(server: Option<&str>) -> Command {
+    let mut command = Command::new("tmux");
+    command.args(socket_args(server));
+    command
+}
+
 #[derive(Debug)]
 enum ControlExecError {
     /// Pipe broken / IO failure — connection should be dropped.
@@ -567,6 +1229,23 @@ enum ControlExecError {
     Protocol(String),
 }
 
+/// Control-mode stdout, split on `\n`. `Lines` is deliberately not used: it
+/// fails on invalid UTF-8, which would tear down the connection whenever a
+/// pane's captured output is not valid UTF-8.
+type CtrlLines = tokio::io::Split<BufReader<ChildStdout>>;
+
+/// Next line from control-mode stdout, undecoded, with any trailing `\r`
+/// stripped. [`TmuxActor::exec_args`] decodes it lossily (invalid bytes
+/// become U+FFFD).
+async fn next_line(stdout: &mut CtrlLines) -> std::io::Result<Option<Vec<u8>>> {
+    Ok(stdout.next_segment().await?.map(|mut bytes| {
+        if bytes.last() == Some(&b'\r') {
+            bytes.pop();
+        }
+        bytes
+    }))
+}
+
 /// Long-lived task that owns the control-mode stdout. Lines inside a command
 /// block are forwarded as CtrlEvent::{Begin,Line,End,Error}; lines outside a
 /// block are classified as notifications and the structural ones produce a
@@ -574,13 +1253,13 @@ enum ControlExecError {
 ///
 /// The task exits when stdout closes or either downstream channel is dropped.
 async fn reader_task(
-    mut stdout: tokio::io::Lines<BufReader<ChildStdout>>,
+    mut stdout: CtrlLines,
     response_tx: mpsc::Sender<CtrlEvent>,
     notify_tx: mpsc::Sender<()>,
 ) {
     let mut in_block = false;
     loop {
-        let line = match stdout.next_line().await {
+        let line = match next_line(&mut stdout).await {
             Ok(Some(l)) => l,
             _ => {
                 let _ = response_tx.send(CtrlEvent::Closed).await;
@@ -589,12 +1268,12 @@ async fn reader_task(
         };
 
         if in_block {
-            if line.starts_with("%end ") {
+            if line.starts_with(b"%end ") {
                 if response_tx.send(CtrlEvent::End).await.is_err() {
                     return;
                 }
                 in_block = false;
-            } else if line.starts_with("%error ") {
+            } else if line.starts_with(b"%error ") {
                 if response_tx.send(CtrlEvent::Error).await.is_err() {
                     return;
                 }
@@ -602,12 +1281,14 @@ async fn reader_task(
             } else if response_tx.send(CtrlEvent::Line(line)).await.is_err() {
                 return;
             }
-        } else if line.starts_with("%begin ") {
+        } else if line.starts_with(b"%begin ") {
             if response_tx.send(CtrlEvent::Begin).await.is_err() {
                 return;
             }
             in_block = true;
-        } else if line.starts_with('%') && is_structural_notification(&line) {
+        } else if line.starts_with(b"%")
+            && is_structural_notification(&String::from_utf8_lossy(&line))
+        {
             // try_send: if the channel is full the consumer is already going
             // to refresh, so dropping is harmless (coalesced upstream).
             let _ = notify_tx.try_send(());
@@ -676,18 +1357,179 @@ fn quote_for_control(arg: &str) -> String {
 struct SessionAccum {
     activity: i64,
     last_attached: i64,
+    attached: bool,
+    client_sizes: Vec<(u32, u32)>,
+    client_activity: i64,
     windows: Vec<WindowAccum>,
 }
 
 struct WindowAccum {
     activity: i64,
     active: bool,
+    synchronized: bool,
+    alerts: String,
     index: u32,
     name: String,
     /// (active, last, index, pane) — sorted then unwrapped
     panes_raw: Vec<(bool, bool, u32, TmuxPane)>,
 }
 
+/// `-F` formats of the session, window, pane and client listings
+/// `refresh_all` concatenates; each line is tagged so [`build_sessions`] can
+/// tell them apart.
+const REFRESH_FORMATS: [&str; 4] = [
+    "SESS\t#{session_name}\t#{session_activity}\t#{session_last_attached}\t#{pid}",
+    "WIN\t#{session_name}\t#{window_index}\t#{window_name}\t#{window_active}\t#{window_activity}\t#{synchronize-panes}\t#{window_flags}",
+    "PANE\t#{session_name}\t#{window_index}\t#{pane_id}\t#{pane_index}\t#{pane_width}\t#{pane_height}\t#{pane_active}\t#{pane_last}\t#{pane_current_command}\t#{pane_pid}\t#{pane_dead}\t#{pane_dead_status}",
+    "CLIENT\t#{client_session}\t#{client_width}\t#{client_height}\t#{client_control_mode}\t#{client_activity}",
+];
+
+/// The `send-keys` commands that deliver `keys` to `target`, then
+/// `submit_keys`. Literal text goes through `-l` (and after `--`), so text
+/// such as `Up` or `-la` is typed rather than read as a key name or flag;
+/// the submit keys, being key names, need a second command then.
+fn send_keys_args<'a>(
+    target: &'a str,
+    keys: &'a str,
+    literal: bool,
+    submit_keys: &'a [String],
+) -> Vec<Vec<&'a str>> {
+    let submit = submit_keys.iter().map(String::as_str);
+    if !literal {
+        let mut args = vec!["send-keys", "-t", target];
+        args.extend(keys.split_whitespace().chain(submit));
+        return vec![args];
+    }
+    let mut commands = Vec::new();
+    if !keys.is_empty() {
+        commands.push(vec!["send-keys", "-l", "-t", target, "--", keys]);
+    }
+    if !submit_keys.is_empty() {
+        let mut args = vec!["send-keys", "-t", target];
+        args.extend(submit);
+        commands.push(args);
+    }
+    commands
+}
+
+/// `-F` formats of the window and pane listings [`TmuxActor::save_template`]
+/// builds a template from.
+const SAVE_FORMATS: [&str; 2] = [
+    "#{window_index}\t#{window_name}\t#{window_layout}",
+    "#{window_index}\t#{pane_current_path}",
+];
+
+/// A template recreating the windows and pane directories of a session
+/// listed with [`SAVE_FORMATS`]. The first pane's directory becomes the
+/// root; a pane elsewhere gets a `cd` as its command. What runs in the panes
+/// is not recorded.
+fn template_from_listing(name: &str, windows: &str, panes: &str) -> SessionTemplate {
+    // Listed window by window, each in pane order.
+    let dirs: Vec<(&str, &str)> = panes.lines().filter_map(|l| l.split_once('\t')).collect();
+    let root = dirs.first().map(|&(_, dir)| dir.to_string());
+    let windows = windows
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let index = fields.next()?;
+            let name = fields.next()?;
+            let layout = fields.next()?;
+            let panes = dirs
+                .iter()
+                .filter(|&&(window, _)| window == index)
+                .map(|&(_, dir)| match root.as_deref() {
+                    Some(root) if root == dir => String::new(),
+                    _ => format!("cd '{}'", dir.replace('\'', "'\\''")),
+                })
+                .collect();
+            Some(TemplateWindow {
+                name: Some(name.to_string()),
+                panes,
+                layout: Some(layout.to_string()),
+            })
+        })
+        .collect();
+    SessionTemplate {
+        name: name.to_string(),
+        root,
+        windows,
+        ..SessionTemplate::default()
+    }
+}
+
+/// Add `cmd` to the capture queue. A newer capture of a target already
+/// queued takes the older one's place: that was asked for a range or a
+/// moment that has passed. Captures of other targets (a tick sends one per
+/// all-panes tile and monitored pane) all stay.
+fn queue_capture(queue: &mut VecDeque<TmuxCommand>, cmd: TmuxCommand) {
+    let queued = match &cmd {
+        TmuxCommand::CapturePane { target, .. } => queue.iter().position(
+            |q| matches!(q, TmuxCommand::CapturePane { target: t, .. } if t == target),
+        ),
+        _ => None,
+    };
+    match queued {
+        Some(i) => queue[i] = cmd,
+        None => queue.push_back(cmd),
+    }
+}
+
+/// `capture-pane` arguments for lines `start..=end` of `target` (negative
+/// lines are history), with escapes, joining wrapped lines when `join`.
+fn capture_args(target: &str, start: i32, end: i32, join: bool) -> Vec<String> {
+    let (start, end) = (start.to_string(), end.to_string());
+    let mut args = vec!["capture-pane", "-e", "-p"];
+    if join {
+        args.push("-J");
+    }
+    args.extend(["-S", &start, "-E", &end, "-t", target]);
+    args.into_iter().map(str::to_string).collect()
+}
+
+/// The server pid the session lines of a `refresh_all` listing carry.
+fn server_pid(stdout: &str) -> Option<u32> {
+    stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("SESS\t")?.split('\t').nth(3)?.parse().ok())
+}
+
+/// Variables of [`REFRESH_FORMATS`] `behavior.skip_fields` may leave out. The
+/// others place a row in the tree, so they are always requested.
+const OPTIONAL_FIELDS: [&str; 13] = [
+    "session_activity",
+    "session_last_attached",
+    "window_activity",
+    "synchronize-panes",
+    "window_flags",
+    "pane_width",
+    "pane_height",
+    "pane_last",
+    "pane_current_command",
+    "pane_pid",
+    "pane_dead",
+    "pane_dead_status",
+    "client_activity",
+];
+
+/// [`REFRESH_FORMATS`] without the `behavior.skip_fields` variables, warning
+/// about names that cannot be skipped.
+fn refresh_formats(skip_fields: &[String]) -> [String; 4] {
+    for field in skip_fields {
+        if !OPTIONAL_FIELDS.contains(&field.as_str()) {
+            warn!("behavior.skip_fields: '{field}' is not an optional field, ignoring");
+        }
+    }
+    REFRESH_FORMATS.map(|f| trim_format(f, skip_fields))
+}
+
+/// `format` without the optional variables named in `skip`. Their tabs stay,
+/// so the columns keep their positions and read empty.
+fn trim_format(format: &str, skip: &[String]) -> String {
+    skip.iter()
+        .filter(|f| OPTIONAL_FIELDS.contains(&f.as_str()))
+        .fold(format.to_string(), |format, f| format.replace(&format!("#{{{f}}}"), ""))
+}
+
 fn build_sessions(stdout: &str) -> Vec<TmuxSession> {
     use std::collections::HashMap;
 
@@ -717,6 +1559,10 @@ fn build_sessions(stdout: &str) -> Vec<TmuxSession> {
                     SessionAccum {
                         activity,
                         last_attached,
+                        // Set by the session's CLIENT rows, which follow.
+                        attached: false,
+                        client_sizes: Vec::new(),
+                        client_activity: 0,
                         windows: Vec::new(),
                     },
                 );
@@ -727,10 +1573,15 @@ fn build_sessions(stdout: &str) -> Vec<TmuxSession> {
                 let name = it.next().unwrap_or("").to_string();
                 let active = it.next() == Some("1");
                 let activity = it.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+                let synchronized = it.next() == Some("1");
+                // Of the flags, only the pending alerts: bell, activity, silence.
+                let alerts = it.next().unwrap_or("").chars().filter(|c| "!#~".contains(*c));
                 if let Some(s) = sessions.get_mut(session) {
                     s.windows.push(WindowAccum {
                         activity,
                         active,
+                        synchronized,
+                        alerts: alerts.collect(),
                         index,
                         name,
                         panes_raw: Vec::new(),
@@ -748,6 +1599,8 @@ fn build_sessions(stdout: &str) -> Vec<TmuxSession> {
                 let last = it.next() == Some("1");
                 let current_command = it.next().unwrap_or("").to_string();
                 let pid: u32 = it.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+                let dead = it.next() == Some("1");
+                let dead_status: Option<i32> = it.next().and_then(|s| s.parse().ok());
 
                 if let Some(s) = sessions.get_mut(session)
                     && let Some(w) = s.windows.iter_mut().find(|w| w.index == window_index)
@@ -764,6 +1617,10 @@ fn build_sessions(stdout: &str) -> Vec<TmuxSession> {
                             active,
                             current_command,
                             pid,
+                            dead,
+                            dead_status,
+                            cpu_percent: None,
+                            rss_kb: None,
                             has_claude: false,
                             claude_state: None,
                             claude_activity: None,
@@ -773,6 +1630,22 @@ fn build_sessions(stdout: &str) -> Vec<TmuxSession> {
                     ));
                 }
             }
+            "CLIENT" => {
+                let session = it.next().unwrap_or("");
+                let width = it.next().and_then(|s| s.parse().ok());
+                let height = it.next().and_then(|s| s.parse().ok());
+                // tmux-deck's own control-mode client has no real terminal and
+                // does not make a session attached.
+                let control = it.next() == Some("1");
+                let activity: i64 = it.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+                if let (Some(s), false) = (sessions.get_mut(session), control) {
+                    s.attached = true;
+                    s.client_activity = s.client_activity.max(activity);
+                    if let (Some(w), Some(h)) = (width, height) {
+                        s.client_sizes.push((w, h));
+                    }
+                }
+            }
             _ => {}
         }
     }
@@ -814,6 +1687,10 @@ fn build_sessions(stdout: &str) -> Vec<TmuxSession> {
                 .map(|w| TmuxWindow {
                     index: w.index,
                     name: w.name,
+                    active: w.active,
+                    synchronized: w.synchronized,
+                    alerts: w.alerts,
+                    activity: w.activity,
                     panes: w.panes_raw.into_iter().map(|(_, _, _, p)| p).collect(),
                     has_claude: false,
                     claude_state: None,
@@ -826,6 +1703,9 @@ fn build_sessions(stdout: &str) -> Vec<TmuxSession> {
                 claude_state: None,
                 last_attached: s.last_attached,
                 activity: s.activity,
+                attached: s.attached,
+                client_sizes: s.client_sizes,
+                client_activity: s.client_activity,
                 // Group labels are applied tmux-deck-side in UIState once the
                 // refreshed sessions reach the UI; the tmux layer is unaware.
                 group: None,
@@ -844,7 +1724,14 @@ fn build_sessions(stdout: &str) -> Vec<TmuxSession> {
 // any pane whose descendant tree contains such a process. The chrome native
 // host instance is excluded so it does not light up unrelated panes.
 
-async fn annotate_claude_panes(sessions: &mut [TmuxSession]) {
+/// The process table as far as Claude detection needs it.
+struct ProcessTable {
+    children: std::collections::HashMap<u32, Vec<u32>>,
+    claude_pids: std::collections::HashSet<u32>,
+}
+
+/// Snapshot the process table with `ps`. `None` when `ps` fails.
+async fn scan_processes() -> Option<ProcessTable> {
     use std::collections::{HashMap, HashSet};
 
     let output = match Command::new("ps")
@@ -853,7 +1740,7 @@ async fn annotate_claude_panes(sessions: &mut [TmuxSession]) {
         .await
     {
         Ok(o) if o.status.success() => o,
-        _ => return,
+        _ => return None,
     };
     let stdout = String::from_utf8_lossy(&output.stdout);
 
@@ -877,7 +1764,13 @@ async fn annotate_claude_panes(sessions: &mut [TmuxSession]) {
             claude_pids.insert(pid);
         }
     }
+    Some(ProcessTable { children, claude_pids })
+}
 
+/// Mark every pane with a claude process in its tree, and roll that up to
+/// its window and session.
+fn mark_claude_panes(sessions: &mut [TmuxSession], processes: &ProcessTable) {
+    let ProcessTable { children, claude_pids } = processes;
     if claude_pids.is_empty() {
         return;
     }
@@ -887,7 +1780,7 @@ async fn annotate_claude_panes(sessions: &mut [TmuxSession]) {
         for window in session.windows.iter_mut() {
             let mut window_has = false;
             for pane in window.panes.iter_mut() {
-                if pane.pid != 0 && pane_has_claude(pane.pid, &children, &claude_pids) {
+                if pane.pid != 0 && pane_has_claude(pane.pid, children, claude_pids) {
                     pane.has_claude = true;
                     window_has = true;
                 }
@@ -948,3 +1841,297 @@ fn append_switch_log(path: &str, target: &str, success: bool, error: Option<&str
         target, success, error
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skipped_fields_leave_empty_columns_that_parse_to_defaults() {
+        let skip = ["pane_pid".to_string(), "pane_current_command".to_string()];
+        let format = trim_format(REFRESH_FORMATS[2], &skip);
+        assert!(format.contains("#{pane_last}\t\t\t#{pane_dead}"));
+        // Required fields are never dropped.
+        assert_eq!(
+            trim_format(REFRESH_FORMATS[2], &["pane_id".to_string()]),
+            REFRESH_FORMATS[2]
+        );
+
+        let sessions = build_sessions(
+            "SESS\tmain\t\t\t\nWIN\tmain\t0\tw\t1\t\nPANE\tmain\t0\t%1\t0\t\t\t1\t\t\t\t\t\n",
+        );
+        let pane = &sessions[0].windows[0].panes[0];
+        assert_eq!(pane.id, "%1");
+        assert_eq!((pane.width, pane.height, pane.pid), (80, 24, 0));
+        assert!(pane.current_command.is_empty() && !pane.dead);
+        assert!(!sessions[0].attached);
+    }
+
+    #[test]
+    fn control_mode_clients_are_left_out_of_sizes_and_activity() {
+        let sessions = build_sessions(
+            "SESS\tmain\t5\t5\t42\n\
+             CLIENT\tmain\t120\t40\t0\t100\n\
+             CLIENT\tmain\t200\t60\t1\t999\n",
+        );
+        assert_eq!(sessions[0].client_sizes, [(120, 40)]);
+        assert_eq!(sessions[0].client_activity, 100);
+    }
+
+    #[test]
+    fn a_session_with_only_the_control_client_is_not_attached() {
+        let sessions = build_sessions("SESS\tmain\t5\t5\t42\nCLIENT\tmain\t80\t24\t1\t5\n");
+        assert!(!sessions[0].attached);
+        assert!(sessions[0].client_sizes.is_empty());
+    }
+
+    #[test]
+    fn capture_args_carry_the_requested_range() {
+        assert_eq!(
+            capture_args("%1", -100, 0, false),
+            ["capture-pane", "-e", "-p", "-S", "-100", "-E", "0", "-t", "%1"]
+        );
+        assert!(capture_args("%1", 0, 24, true).contains(&"-J".to_string()));
+    }
+
+    #[test]
+    fn server_pid_is_read_from_the_session_lines() {
+        assert_eq!(server_pid("WIN\tmain\t0\tw\t1\t0\nSESS\tmain\t1\t1\t4242\n"), Some(4242));
+        // Older listings without the column, and a server with no sessions.
+        assert_eq!(server_pid("SESS\tmain\t1\t1\n"), None);
+        assert_eq!(server_pid(""), None);
+    }
+
+    #[test]
+    fn colons_in_session_and_window_names_survive_parsing() {
+        let sessions = build_sessions(
+            "SESS\tmy:session\t5\t5\t42\n\
+             WIN\tmy:session\t0\t10:30-standup\t1\t5\n\
+             PANE\tmy:session\t0\t%1\t0\t80\t24\t1\t0\tzsh\t7\t0\t\n\
+             CLIENT\tmy:session\t80\t24\t0\t5\n",
+        );
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].name, "my:session");
+        assert!(sessions[0].attached);
+        assert_eq!(sessions[0].windows[0].name, "10:30-standup");
+        assert_eq!(sessions[0].windows[0].panes[0].id, "%1");
+    }
+
+    #[test]
+    fn synchronized_windows_are_marked() {
+        let sessions = build_sessions(
+            "SESS\tmain\t5\t5\t42\n\
+             WIN\tmain\t0\tssh\t1\t5\t1\n\
+             WIN\tmain\t1\tlogs\t0\t5\t0\n",
+        );
+        let windows = &sessions[0].windows;
+        assert!(windows.iter().find(|w| w.name == "ssh").unwrap().synchronized);
+        assert!(!windows.iter().find(|w| w.name == "logs").unwrap().synchronized);
+    }
+
+    #[test]
+    fn window_alerts_keep_only_the_alert_flags() {
+        let sessions = build_sessions(
+            "SESS\tmain\t5\t5\t42\n\
+             WIN\tmain\t0\tbuild\t0\t5\t0\t#!-\n\
+             WIN\tmain\t1\tlogs\t1\t5\t0\t*Z\n",
+        );
+        let windows = &sessions[0].windows;
+        assert_eq!(windows.iter().find(|w| w.name == "build").unwrap().alerts, "#!");
+        assert_eq!(windows.iter().find(|w| w.name == "logs").unwrap().alerts, "");
+    }
+
+    #[test]
+    fn capture_queue_keeps_the_newest_capture_per_target() {
+        let capture = |target: &str, start: i32| TmuxCommand::CapturePane {
+            target: target.to_string(),
+            start,
+            end: 24,
+            join: true,
+        };
+        let mut queue = VecDeque::new();
+        // One tick of a four-tile grid, then the next tick's first tile.
+        for target in ["%1", "%2", "%3", "%4"] {
+            queue_capture(&mut queue, capture(target, 0));
+        }
+        queue_capture(&mut queue, capture("%1", -50));
+        let queued: Vec<(&str, i32)> = queue
+            .iter()
+            .map(|c| match c {
+                TmuxCommand::CapturePane { target, start, .. } => (target.as_str(), *start),
+                other => panic!("not a capture: {other:?}"),
+            })
+            .collect();
+        assert_eq!(queued, [("%1", -50), ("%2", 0), ("%3", 0), ("%4", 0)]);
+
+        // A TreeView tick: the preview, then two monitored panes. None of the
+        // strip's captures pushes the preview's out.
+        let mut queue = VecDeque::new();
+        for (target, start) in [("=work:0.0", -30), ("%7", 0), ("%8", 0)] {
+            queue_capture(&mut queue, capture(target, start));
+        }
+        assert_eq!(queue.len(), 3);
+        let first = &queue[0];
+        assert!(matches!(first, TmuxCommand::CapturePane { target, .. } if target == "=work:0.0"));
+    }
+
+    #[tokio::test]
+    async fn read_only_actor_refuses_changes_before_reaching_tmux() {
+        let (_command_tx, command_rx) = mpsc::channel(1);
+        let (_capture_tx, capture_rx) = mpsc::channel(1);
+        let (response_tx, _response_rx) = mpsc::channel(1);
+        let control = RefreshControl::new();
+        let mut actor = TmuxActor::new(command_rx, capture_rx, response_tx, control, &[], true);
+        for cmd in [
+            TmuxCommand::KillSession { name: "work".to_string(), batch: None },
+            TmuxCommand::SendKeys {
+                target: "work:0.0".to_string(),
+                keys: "rm -rf build".to_string(),
+                literal: true,
+                submit_keys: vec!["Enter".to_string()],
+                batch: None,
+                reply: None,
+            },
+        ] {
+            assert!(!cmd.is_read_only());
+            match actor.handle_command(cmd).await {
+                TmuxResponse::Error { message } => assert!(message.starts_with("read-only mode")),
+                other => panic!("ran in read-only mode: {other:?}"),
+            }
+        }
+        let capture = TmuxCommand::CapturePane {
+            target: "%1".to_string(),
+            start: 0,
+            end: 24,
+            join: true,
+        };
+        assert!(capture.is_read_only() && TmuxCommand::RefreshAll.is_read_only());
+    }
+
+    #[test]
+    fn saved_template_keeps_layouts_and_directories() {
+        let windows = "0\teditor\tb25f,80x24,0,0{40x24,0,0,1,39x24,41,0,2}\n2\tlogs\ttiled\n";
+        let panes = "0\t/src/app\n0\t/tmp/it's\n2\t/src/app\n";
+        let template = template_from_listing("dev", windows, panes);
+        assert_eq!(template.name, "dev");
+        assert_eq!(template.root.as_deref(), Some("/src/app"));
+        assert_eq!(template.windows.len(), 2);
+        let editor = &template.windows[0];
+        assert_eq!(editor.name.as_deref(), Some("editor"));
+        assert!(editor.layout.as_deref().unwrap().starts_with("b25f,"));
+        assert_eq!(editor.panes, ["", "cd '/tmp/it'\\''s'"]);
+        assert_eq!(template.windows[1].panes, [""]);
+    }
+
+    #[test]
+    fn send_keys_types_text_literally_and_submits_separately() {
+        let enter = vec!["Enter".to_string()];
+        assert_eq!(
+            send_keys_args("%1", "-la Up", true, &enter),
+            [
+                vec!["send-keys", "-l", "-t", "%1", "--", "-la Up"],
+                vec!["send-keys", "-t", "%1", "Enter"],
+            ]
+        );
+        assert_eq!(
+            send_keys_args("%1", "ls", true, &[]),
+            [vec!["send-keys", "-l", "-t", "%1", "--", "ls"]]
+        );
+        // Submit keys alone, e.g. an empty line.
+        assert_eq!(
+            send_keys_args("%1", "", true, &enter),
+            [vec!["send-keys", "-t", "%1", "Enter"]]
+        );
+        // Key names are passed as names, one argument each.
+        assert_eq!(
+            send_keys_args("%1", "Escape C-c", false, &[]),
+            [vec!["send-keys", "-t", "%1", "Escape", "C-c"]]
+        );
+    }
+}
+
+// =============================================================================
+// Refresh pipeline budget / soak tests
+// =============================================================================
+//
+// These drive the tmux-independent half of a refresh — parsing the listing
+// (`build_sessions`) and folding it into the UI state — with a synthetic
+// server, so per-refresh work added later shows up as a failed budget rather
+// than a sluggish deck. The tmux round-trip itself is not covered. Both depend
+// on the machine, so they are `#[ignore]`d and run on demand.
+
+#[cfg(test)]
+mod budget_tests {
+    use super::*;
+    use crate::app::UIState;
+    use crate::config::Config;
+    use std::time::{Duration, Instant};
+
+    /// A `refresh_all` listing for `sessions` sessions of `windows` windows of
+    /// `panes` panes each, as of time `now` (session `i` was last active at
+    /// `now - i`, so activity advances as `now` does).
+    fn listing(sessions: usize, windows: usize, panes: usize, now: i64) -> String {
+        let mut out = String::new();
+        for s in 0..sessions {
+            let name = format!("s{s}");
+            let activity = now - s as i64;
+            out.push_str(&format!("SESS\t{name}\t{activity}\t{activity}\n"));
+            for w in 0..windows {
+                out.push_str(&format!("WIN\t{name}\t{w}\tw{w}\t{}\t{activity}\n", (w == 0) as u8));
+                for p in 0..panes {
+                    let id = (s * windows + w) * panes + p;
+                    out.push_str(&format!(
+                        "PANE\t{name}\t{w}\t%{id}\t{p}\t80\t24\t{}\t0\tzsh\t{id}\t0\t\n",
+                        (p == 0) as u8
+                    ));
+                }
+            }
+            if s % 2 == 1 {
+                out.push_str(&format!("CLIENT\t{name}\t120\t40\t0\t{activity}\n"));
+            }
+        }
+        out
+    }
+
+    fn refresh(state: &mut UIState, stdout: &str) {
+        let sessions = build_sessions(stdout);
+        state.track_session_changes(&sessions);
+        state.update_sessions(sessions);
+    }
+
+    /// Run with `cargo test --release -- --ignored refresh_fits`.
+    #[test]
+    #[ignore = "timing test; depends on the machine"]
+    fn large_server_refresh_fits_the_budget() {
+        // Generous enough for an unoptimised build on a slow CI runner; a
+        // refresh normally takes a small fraction of it.
+        const BUDGET: Duration = Duration::from_millis(500);
+        let stdout = listing(300, 4, 3, 1_000_000);
+        let mut state = UIState::new(Config::default());
+
+        let started = Instant::now();
+        refresh(&mut state, &stdout);
+        refresh(&mut state, &stdout);
+        let elapsed = started.elapsed() / 2;
+
+        assert_eq!(state.sessions.len(), 300);
+        assert_eq!(state.sessions[0].windows[0].panes.len(), 3);
+        assert!(elapsed < BUDGET, "refresh took {elapsed:?} (budget {BUDGET:?})");
+    }
+
+    /// Run with `cargo test --release -- --ignored refresh_soak`.
+    #[test]
+    #[ignore = "soak test; slow"]
+    fn refresh_soak_keeps_state_bounded() {
+        let mut state = UIState::new(Config::default());
+        for round in 0..2_000 {
+            // Sessions s50..s69 come and go. Change badges outlive a session
+            // by a few refreshes, but state keyed by session name must never
+            // grow past the names the server has actually had.
+            let sessions = 50 + round % 20;
+            refresh(&mut state, &listing(sessions, 3, 2, 1_000_000 + round as i64));
+            assert_eq!(state.sessions.len(), sessions);
+            assert!(state.session_changes.len() <= 70, "round {round}");
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use std::io;
+use std::io::{self, Write};
+use std::process::Stdio;
 use std::time::Duration;
 
 use color_eyre::Result;
@@ -11,11 +12,17 @@ use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 use tokio::sync::{mpsc, oneshot};
 
-use crate::actor::messages::{RefreshControl, TmuxCommand, TmuxResponse, UIEvent};
+use crate::actor::messages::{PaneDirection, RefreshControl, TmuxCommand, TmuxResponse, UIEvent};
 use crate::app::{
-    Focus, GroupChoice, InputMode, PopupMode, SESSION_NAME_MAX_LEN, UIState, ViewMode,
+    DeadPane, Focus, GroupChoice, INSPECT_HISTORY_LINES, InputMode, PopupMode,
+    SESSION_NAME_MAX_LEN, SendScope, UIState, ViewMode, display_target,
 };
-use crate::config::Action;
+use crate::clipboard;
+use crate::config::{Action, Config, EnterAction, YankTarget, render_event_command};
+use crate::last_target;
+use crate::preview::SELF_PREVIEW_PLACEHOLDER;
+use crate::resources::ResourceSampler;
+use crate::server::socket_args;
 use crate::ui::render_ui;
 
 // =============================================================================
@@ -62,6 +69,8 @@ pub struct UIActor {
     /// last fetched (to throttle refresh).
     logs_inflight: std::collections::HashSet<String>,
     logs_fetched_at: std::collections::HashMap<String, std::time::Instant>,
+    /// Per-pane CPU / memory figures, when `layout.show_resources` is on.
+    resources: Option<ResourceSampler>,
 }
 
 impl UIActor {
@@ -80,6 +89,7 @@ impl UIActor {
 
         let (agent_summary_tx, agent_summary_rx) = mpsc::channel(8);
         let (agent_logs_tx, agent_logs_rx) = mpsc::channel(8);
+        let resources = state.layout.show_resources.then(ResourceSampler::default);
 
         Self {
             terminal,
@@ -96,11 +106,16 @@ impl UIActor {
             agent_logs_rx,
             logs_inflight: std::collections::HashSet::new(),
             logs_fetched_at: std::collections::HashMap::new(),
+            resources,
         }
     }
 
     pub async fn run(mut self) -> Result<()> {
-        // Request initial data
+        // Request initial data, and pick up where the last run left off
+        // unless `--target` names a pane to start on.
+        if self.state.pending_reveal.is_none() {
+            self.state.pending_restore = last_target::load();
+        }
         let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
 
         // Initial render before entering event loop
@@ -143,7 +158,7 @@ impl UIActor {
 
                 // TmuxActor responses
                 Some(response) = self.tmux_res_rx.recv() => {
-                    self.handle_tmux_response(response);
+                    redraw = self.handle_tmux_response(response) || self.state.force_redraw;
                 }
 
                 // RefreshActor events
@@ -153,17 +168,52 @@ impl UIActor {
                             // Cheap, local: fold the latest Claude hook states
                             // into the tree so markers stay live between full
                             // tmux refreshes.
-                            self.state.refresh_claude_states();
+                            let mut changed = self.state.refresh_claude_states();
+                            if let Some(sampler) = self.resources.as_mut() {
+                                changed |= sampler.annotate(&mut self.state.sessions);
+                            }
 
                             match self.state.view_mode {
-                                // TreeView captures the selected pane for its preview.
-                                ViewMode::TreeView => {
-                                    if let Some((target, start, end)) =
+                                // TreeView captures the selected pane for its
+                                // preview, a zoomed MultiPreview the selected
+                                // window's active pane.
+                                ViewMode::TreeView | ViewMode::MultiPreview => {
+                                    // Capturing our own pane would preview the
+                                    // deck inside itself.
+                                    if self.state.preview_pane_is_self() {
+                                        self.state
+                                            .set_preview_placeholder(SELF_PREVIEW_PLACEHOLDER);
+                                    } else if let Some((target, start, end)) =
                                         self.state.get_selected_pane_target_with_capture_range()
                                     {
+                                        let join = self.state.preview.join_wrapped;
+                                        self.refresh_control.set_capture_pending(true);
                                         let _ = self
                                             .tmux_capture_tx
-                                            .send(TmuxCommand::CapturePane { target, start, end })
+                                            .send(TmuxCommand::CapturePane {
+                                                target,
+                                                start,
+                                                end,
+                                                join,
+                                            })
+                                            .await;
+                                    }
+                                }
+                                // The all-panes grid captures each of its tiles.
+                                ViewMode::AllPanes => {
+                                    let join = self.state.preview.join_wrapped;
+                                    for (target, start, end) in
+                                        self.state.visible_capture_targets()
+                                    {
+                                        self.refresh_control.set_capture_pending(true);
+                                        let _ = self
+                                            .tmux_capture_tx
+                                            .send(TmuxCommand::CapturePane {
+                                                target,
+                                                start,
+                                                end,
+                                                join,
+                                            })
                                             .await;
                                     }
                                 }
@@ -174,8 +224,26 @@ impl UIActor {
                                     self.state.refresh_agents();
                                     self.maybe_fetch_logs();
                                 }
-                                ViewMode::MultiPreview => {}
                             }
+                            // The monitor strip shows in every view. The tmux
+                            // actor queues captures per target, so these run
+                            // after the preview's instead of replacing it.
+                            let join = self.state.preview.join_wrapped;
+                            for (target, start, end) in self.state.monitor_capture_targets() {
+                                self.refresh_control.set_capture_pending(true);
+                                let _ = self
+                                    .tmux_capture_tx
+                                    .send(TmuxCommand::CapturePane { target, start, end, join })
+                                    .await;
+                            }
+                            // The captures redraw when they arrive, if they
+                            // changed anything; the tick itself only does
+                            // when the markers or figures moved, or to age
+                            // the refresh metrics.
+                            redraw = self.state.force_redraw
+                                || self.state.layout.show_metrics
+                                || changed
+                                || self.state.visible_capture_targets().is_empty();
                         }
                         UIEvent::Shutdown => {
                             break;
@@ -186,7 +254,7 @@ impl UIActor {
 
                 // Spinner animation tick: only redraw if a spinner is active.
                 _ = anim.tick() => {
-                    redraw = self.state.has_working_claude();
+                    redraw = self.state.has_working_claude() || self.state.force_redraw;
                 }
             }
 
@@ -196,6 +264,11 @@ impl UIActor {
                 self.attach_agent(&id)?;
                 continue;
             }
+            // Likewise for Enter's `attach` behaviour, with `tmux attach`.
+            if let Some(target) = self.state.pending_tmux_attach.take() {
+                self.attach_tmux(&target)?;
+                continue;
+            }
 
             // Render UI after processing event (event-driven rendering)
             if redraw {
@@ -205,6 +278,9 @@ impl UIActor {
             }
         }
 
+        if let Some(target) = self.state.get_current_target() {
+            last_target::save(&target);
+        }
         Ok(())
     }
 
@@ -213,6 +289,8 @@ impl UIActor {
             if key.kind != KeyEventKind::Press {
                 return Ok(false);
             }
+            // Any key dismisses an informational note.
+            self.state.status_message = None;
 
             // Handle popup mode first
             if let Some(popup_mode) = self.state.popup_mode {
@@ -237,16 +315,20 @@ impl UIActor {
         key: event::KeyEvent,
         popup_mode: PopupMode,
     ) -> Result<bool> {
+        // Esc steps back one layer: a prompt opened from a picker returns to
+        // it, anything else closes (and refreshing picks up again).
+        if key.code == KeyCode::Esc {
+            if self.state.popup_back() {
+                self.refresh_control.resume();
+            }
+            return Ok(false);
+        }
         match popup_mode {
             PopupMode::GroupSession => {
                 // Selecting an existing group (or "ungroup") is handled entirely
                 // tmux-deck-side: no tmux command and no RefreshAll, since
                 // grouping does not change anything tmux knows about.
                 match key.code {
-                    KeyCode::Esc => {
-                        self.state.close_popup();
-                        self.refresh_control.resume();
-                    }
                     KeyCode::Up | KeyCode::Char('k') => self.state.group_choice_up(),
                     KeyCode::Down | KeyCode::Char('j') => self.state.group_choice_down(),
                     KeyCode::Enter => match self.state.selected_group_choice() {
@@ -267,12 +349,199 @@ impl UIActor {
                     _ => {}
                 }
             }
-            PopupMode::NewSession | PopupMode::RenameSession | PopupMode::NewGroup => {
+            PopupMode::TemplatePicker => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.state.template_choice_up(),
+                KeyCode::Down | KeyCode::Char('j') => self.state.template_choice_down(),
+                // Stay in popup (refresh still paused) until the name is confirmed.
+                KeyCode::Enter => self.state.begin_template_session_name(),
+                _ => {}
+            },
+            PopupMode::DeadPanes => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.state.dead_pane_up(),
+                KeyCode::Down | KeyCode::Char('j') => self.state.dead_pane_down(),
+                // Show the pane among its neighbours in the tree.
+                KeyCode::Enter => {
+                    if let Some(pane) = self.state.selected_dead_pane() {
+                        self.state.reveal_pane(&pane.id);
+                    }
+                    self.state.close_popup();
+                    self.refresh_control.resume();
+                }
+                // Lower case acts on the highlighted pane, upper case on all of
+                // them. The list follows the refresh each action triggers.
+                KeyCode::Char(c @ ('x' | 'X' | 'r' | 'R')) => {
+                    if self.refuse_in_read_only("clearing dead panes") {
+                        return Ok(false);
+                    }
+                    // Killing them all can be made to wait for a typed phrase.
+                    if c == 'X' && self.state.behavior.require_typed_confirmation {
+                        if !self.state.dead_panes().is_empty() {
+                            self.state.open_typed_confirm();
+                        }
+                        return Ok(false);
+                    }
+                    let panes = if c.is_ascii_uppercase() {
+                        self.state.dead_panes()
+                    } else {
+                        self.state.selected_dead_pane().into_iter().collect()
+                    };
+                    self.clear_dead_panes(panes, c.eq_ignore_ascii_case(&'x')).await;
+                }
+                _ => {}
+            },
+            // `r` captures again, for a command still printing.
+            PopupMode::CommandOutput => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.state.inspect_scroll_up(),
+                KeyCode::Down | KeyCode::Char('j') => self.state.inspect_scroll_down(),
+                KeyCode::Char('r') => {
+                    if let Some(inspection) = &self.state.inspection {
+                        self.snapshot_after(inspection.target.clone(), 0);
+                    }
+                }
+                KeyCode::Enter | KeyCode::Char('q') => {
+                    self.state.close_popup();
+                    self.refresh_control.resume();
+                }
+                _ => {}
+            },
+            // Enter does nothing until the phrase is typed; Esc (above)
+            // returns to the dead panes list, or closes the prompt.
+            PopupMode::TypedConfirm => match key.code {
+                KeyCode::Enter if self.state.typed_confirmation_matches() => {
+                    if self.state.typed_confirm_origin == Some(PopupMode::DeadPanes) {
+                        let panes = self.state.dead_panes();
+                        self.clear_dead_panes(panes, true).await;
+                        self.state.popup_back();
+                    } else {
+                        let names = self.state.other_session_names();
+                        self.kill_sessions(names).await;
+                        self.state.close_popup();
+                        self.refresh_control.resume();
+                    }
+                }
+                KeyCode::Backspace => self.state.input_backspace(),
+                KeyCode::Delete => self.state.input_delete(),
+                KeyCode::Left => self.state.input_move_left(),
+                KeyCode::Right => self.state.input_move_right(),
+                KeyCode::Home => self.state.input_move_home(),
+                KeyCode::End => self.state.input_move_end(),
+                KeyCode::Char(c) => self.state.input_char_limited(c, SESSION_NAME_MAX_LEN),
+                _ => {}
+            },
+            // Picking an action runs it as its key would have, with the
+            // palette closed first so the action can open its own popup.
+            PopupMode::CommandPalette => {
+                let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
                 match key.code {
-                    KeyCode::Esc => {
+                    KeyCode::Up => self.state.palette_up(),
+                    KeyCode::Down => self.state.palette_down(),
+                    KeyCode::Char('p') if ctrl => self.state.palette_up(),
+                    KeyCode::Char('n') if ctrl => self.state.palette_down(),
+                    KeyCode::Enter => {
+                        let action = self.state.selected_palette_action();
+                        self.state.close_popup();
+                        self.refresh_control.resume();
+                        if let Some(action) = action {
+                            if action != Action::RepeatLast {
+                                self.state.last_command = Some(action);
+                            }
+                            return self.run_action(action, None).await;
+                        }
+                    }
+                    KeyCode::Backspace => {
+                        self.state.input_backspace();
+                        self.state.palette_query_changed();
+                    }
+                    KeyCode::Left => self.state.input_move_left(),
+                    KeyCode::Right => self.state.input_move_right(),
+                    KeyCode::Char(c) => {
+                        self.state.input_char(c);
+                        self.state.palette_query_changed();
+                    }
+                    _ => {}
+                }
+            }
+            PopupMode::MoveWindow => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.state.move_choice_up(),
+                KeyCode::Down | KeyCode::Char('j') => self.state.move_choice_down(),
+                KeyCode::Enter => {
+                    let info = self.state.get_move_window_info();
+                    self.state.close_popup();
+                    self.refresh_control.resume();
+                    if let Some((src_target, dst_session)) = info {
+                        let cmd = TmuxCommand::MoveWindow { src_target, dst_session };
+                        let _ = self.tmux_cmd_tx.send(cmd).await;
+                    }
+                }
+                _ => {}
+            },
+            PopupMode::ServerPicker => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.state.server_choice_up(),
+                KeyCode::Down | KeyCode::Char('j') => self.state.server_choice_down(),
+                KeyCode::Enter => {
+                    let choice = self.state.selected_server_choice();
+                    self.state.close_popup();
+                    self.refresh_control.resume();
+                    if let Some(server) = choice
+                        && server != self.state.tmux_server
+                    {
+                        let _ = self.tmux_cmd_tx.send(TmuxCommand::SwitchServer { server }).await;
+                    }
+                }
+                _ => {}
+            },
+            // Workspaces are deck-side view settings: applying, saving or
+            // deleting one never talks to tmux.
+            PopupMode::WorkspacePicker => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.state.workspace_choice_up(),
+                KeyCode::Down | KeyCode::Char('j') => self.state.workspace_choice_down(),
+                KeyCode::Char('x') => self.state.delete_selected_workspace(),
+                KeyCode::Enter => match self.state.selected_workspace() {
+                    Some(name) => {
+                        self.state.apply_workspace(&name);
                         self.state.close_popup();
                         self.refresh_control.resume();
                     }
+                    // Stay in popup (refresh still paused) until it is named.
+                    None => self.state.begin_new_workspace_entry(),
+                },
+                _ => {}
+            },
+            PopupMode::CaptureRange => match key.code {
+                KeyCode::Enter => {
+                    if let Err(e) = self.state.apply_capture_range_input() {
+                        self.state.set_error(format!("capture range: {e}"));
+                    }
+                    self.state.close_popup();
+                    self.refresh_control.resume();
+                    // Show the new range now rather than on the next tick.
+                    let join = self.state.preview.join_wrapped;
+                    for (target, start, end) in self.state.visible_capture_targets() {
+                        let _ = self
+                            .tmux_cmd_tx
+                            .send(TmuxCommand::CapturePane { target, start, end, join })
+                            .await;
+                    }
+                }
+                KeyCode::Tab => self.state.next_capture_range_preset(),
+                KeyCode::Backspace => self.state.input_backspace(),
+                KeyCode::Delete => self.state.input_delete(),
+                KeyCode::Left => self.state.input_move_left(),
+                KeyCode::Right => self.state.input_move_right(),
+                KeyCode::Home => self.state.input_move_home(),
+                KeyCode::End => self.state.input_move_end(),
+                KeyCode::Char(c) => self.state.input_char(c),
+                _ => {}
+            },
+            PopupMode::NewSession
+            | PopupMode::RenameSession
+            | PopupMode::NewWindow
+            | PopupMode::RenameWindow
+            | PopupMode::NewGroup
+            | PopupMode::NewWorkspace
+            | PopupMode::GotoPane
+            | PopupMode::TemplateSessionName => {
+                match key.code {
                     KeyCode::Enter => {
                         // A new group is handled entirely tmux-deck-side: no
                         // tmux command and no RefreshAll, since grouping does
@@ -284,11 +553,51 @@ impl UIActor {
                             self.refresh_control.resume();
                             return Ok(false);
                         }
+                        if popup_mode == PopupMode::NewWorkspace {
+                            let name = self.state.input_buffer.clone();
+                            self.state.save_workspace(&name);
+                            self.state.close_popup();
+                            self.refresh_control.resume();
+                            return Ok(false);
+                        }
+                        if popup_mode == PopupMode::GotoPane {
+                            if let Err(e) = self.state.goto_pane_input() {
+                                self.state.set_error(e);
+                            }
+                            self.state.close_popup();
+                            self.refresh_control.resume();
+                            return Ok(false);
+                        }
                         if popup_mode == PopupMode::NewSession {
                             let name = self.state.get_new_session_name();
                             if !name.is_empty() {
                                 let _ = self.tmux_cmd_tx.send(TmuxCommand::NewSession { name }).await;
                             }
+                        } else if popup_mode == PopupMode::NewWindow {
+                            if let Some((session, name)) = self.state.get_new_window_info() {
+                                let _ = self
+                                    .tmux_cmd_tx
+                                    .send(TmuxCommand::NewWindow { session, name })
+                                    .await;
+                            }
+                        } else if popup_mode == PopupMode::RenameWindow {
+                            if let Some((target, new_name)) = self.state.get_rename_window_info() {
+                                let _ = self
+                                    .tmux_cmd_tx
+                                    .send(TmuxCommand::RenameWindow { target, new_name })
+                                    .await;
+                            }
+                        } else if popup_mode == PopupMode::TemplateSessionName {
+                            if let Some((name, template)) = self.state.get_template_session_info() {
+                                let item = vec![(name.clone(), name.clone())];
+                                let batch = self.state.begin_batch("created", "session", item);
+                                let cmd = TmuxCommand::NewSessionFromTemplate {
+                                    name,
+                                    template,
+                                    batch: Some(batch),
+                                };
+                                let _ = self.tmux_cmd_tx.send(cmd).await;
+                            }
                         } else if let Some((old_name, new_name)) =
                             self.state.get_rename_session_info()
                         {
@@ -317,9 +626,53 @@ impl UIActor {
                     _ => {}
                 }
             }
-            PopupMode::ConfirmKill => {
+            PopupMode::Search => {
+                match key.code {
+                    KeyCode::Enter => {
+                        if let Err(e) = self.state.accept_search() {
+                            self.state.set_error(e);
+                        }
+                        self.refresh_control.resume();
+                        return Ok(false);
+                    }
+                    KeyCode::Backspace => self.state.input_backspace(),
+                    KeyCode::Delete => self.state.input_delete(),
+                    KeyCode::Left => self.state.input_move_left(),
+                    KeyCode::Right => self.state.input_move_right(),
+                    KeyCode::Home => self.state.input_move_home(),
+                    KeyCode::End => self.state.input_move_end(),
+                    KeyCode::Char(c) => self.state.input_char(c),
+                    _ => {}
+                }
+                self.state.update_search();
+            }
+            PopupMode::ConfirmKill
+            | PopupMode::ConfirmKillWindow
+            | PopupMode::ConfirmKillPane
+            | PopupMode::ConfirmKillOthers => {
                 match key.code {
-                    KeyCode::Esc => {
+                    KeyCode::Enter if popup_mode == PopupMode::ConfirmKillOthers => {
+                        if self.state.confirm_yes_selected {
+                            let names = self.state.other_session_names();
+                            self.kill_sessions(names).await;
+                        }
+                        self.state.close_popup();
+                        self.refresh_control.resume();
+                    }
+                    KeyCode::Enter if popup_mode == PopupMode::ConfirmKillPane => {
+                        if let Some(id) = self.state.get_kill_pane_id() {
+                            let cmd = TmuxCommand::KillPane { id, batch: None };
+                            let _ = self.tmux_cmd_tx.send(cmd).await;
+                            let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
+                        }
+                        self.state.close_popup();
+                        self.refresh_control.resume();
+                    }
+                    KeyCode::Enter if popup_mode == PopupMode::ConfirmKillWindow => {
+                        if let Some(target) = self.state.get_kill_window_target() {
+                            let _ = self.tmux_cmd_tx.send(TmuxCommand::KillWindow { target }).await;
+                            let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
+                        }
                         self.state.close_popup();
                         self.refresh_control.resume();
                     }
@@ -328,7 +681,8 @@ impl UIActor {
                             // Drop the killed session's group assignment so the
                             // store does not keep stale entries around.
                             self.state.groups.forget(&name);
-                            let _ = self.tmux_cmd_tx.send(TmuxCommand::KillSession { name }).await;
+                            let cmd = TmuxCommand::KillSession { name, batch: None };
+                            let _ = self.tmux_cmd_tx.send(cmd).await;
                             // Refresh after operation
                             let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
                         }
@@ -342,6 +696,8 @@ impl UIActor {
                     | KeyCode::Char('l') => {
                         self.state.toggle_confirm_selection();
                     }
+                    KeyCode::Up | KeyCode::Char('k') => self.state.kill_list_scroll_up(),
+                    KeyCode::Down | KeyCode::Char('j') => self.state.kill_list_scroll_down(),
                     KeyCode::Char('y') => {
                         self.state.confirm_yes_selected = true;
                     }
@@ -355,20 +711,72 @@ impl UIActor {
         Ok(false)
     }
 
+    /// Capture `target` for the output inspection once `delay_ms` have passed,
+    /// without holding up the UI meanwhile.
+    fn snapshot_after(&self, target: String, delay_ms: u64) {
+        let tx = self.tmux_cmd_tx.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            let start = -INSPECT_HISTORY_LINES;
+            let _ = tx.send(TmuxCommand::SnapshotPane { target, start }).await;
+        });
+    }
+
+    /// Kill (`kill`) or respawn `panes` as one batch, then refresh so the dead
+    /// panes list follows.
+    async fn clear_dead_panes(&mut self, panes: Vec<DeadPane>, kill: bool) {
+        if panes.is_empty() {
+            return;
+        }
+        let ids: Vec<String> = panes.iter().map(|p| p.id.clone()).collect();
+        let batch = Some(self.state.begin_batch(
+            if kill { "killed" } else { "respawned" },
+            "pane",
+            panes.into_iter().map(|p| (p.id, p.location)).collect(),
+        ));
+        for id in ids {
+            let cmd = if kill {
+                TmuxCommand::KillPane { id, batch }
+            } else {
+                TmuxCommand::RespawnPane { id, batch }
+            };
+            let _ = self.tmux_cmd_tx.send(cmd).await;
+        }
+        let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
+    }
+
+    /// Kill the sessions `names` as one batch, then refresh.
+    async fn kill_sessions(&mut self, names: Vec<String>) {
+        let items = names.iter().map(|n| (n.clone(), n.clone())).collect();
+        let batch = Some(self.state.begin_batch("killed", "session", items));
+        for name in names {
+            self.state.groups.forget(&name);
+            let _ = self.tmux_cmd_tx.send(TmuxCommand::KillSession { name, batch }).await;
+        }
+        let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
+    }
+
     async fn handle_normal_mode_key(&mut self, key: event::KeyEvent) -> Result<bool> {
         let is_ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
         let in_sessions = self.state.view_mode == ViewMode::TreeView
             && self.state.focus == Focus::Sessions;
+        let in_windows = self.state.view_mode == ViewMode::TreeView
+            && self.state.focus == Focus::Windows;
+        let in_panes = self.state.view_mode == ViewMode::TreeView
+            && self.state.focus == Focus::Panes;
 
         // `za` fold chord: a pending `z` followed by `a` toggles the current
         // group's fold. Any other key cancels the chord and is then processed
-        // normally below.
+        // normally below, except Esc, which only cancels it.
         if self.state.pending_z {
             self.state.pending_z = false;
             if !is_ctrl && key.code == KeyCode::Char('a') {
                 self.state.toggle_fold_current_group();
                 return Ok(false);
             }
+            if key.code == KeyCode::Esc {
+                return Ok(false);
+            }
         }
 
         // Fixed (non-remappable) chords handled before config bindings:
@@ -379,10 +787,33 @@ impl UIActor {
                     self.state.pending_z = true;
                     return Ok(false);
                 }
+                KeyCode::Char('z') if self.state.view_mode == ViewMode::MultiPreview => {
+                    self.state.toggle_zoom();
+                    return Ok(false);
+                }
                 KeyCode::Char(' ') if self.state.view_mode != ViewMode::Dashboard => {
                     self.state.handle_space_press();
                     return Ok(false);
                 }
+                KeyCode::Char(c @ ('+' | '-'))
+                    if matches!(self.state.view_mode, ViewMode::MultiPreview | ViewMode::AllPanes)
+                        && !self.state.zoomed =>
+                {
+                    self.state.adjust_multi_columns(if c == '+' { 1 } else { -1 });
+                    return Ok(false);
+                }
+                KeyCode::Char(':') => {
+                    self.state.open_command_palette();
+                    self.pause_for_overlay();
+                    return Ok(false);
+                }
+                // `1`–`9` jump to that session, `0` to the tenth.
+                KeyCode::Char(c @ '0'..='9') if self.state.view_mode != ViewMode::Dashboard => {
+                    self.state.suspend_follow();
+                    let n = c.to_digit(10).unwrap_or(0) as usize;
+                    self.state.jump_to_session(if n == 0 { 9 } else { n - 1 });
+                    return Ok(false);
+                }
                 // Agent-view-only keys: `p` toggles the preview panel, `s`
                 // generates an execution summary for the selected session.
                 KeyCode::Char('p') if self.state.view_mode == ViewMode::Dashboard => {
@@ -398,7 +829,13 @@ impl UIActor {
                     self.request_agent_summary();
                     return Ok(false);
                 }
-                // Esc closes the summary popup before falling through to quit.
+                // Esc steps back one layer, and only quits (as a `quit`
+                // binding) with nothing left to step back from: a slow refresh
+                // is abandoned, the summary popup closed, the agent view left.
+                KeyCode::Esc if self.refresh_control.is_busy() => {
+                    self.refresh_control.cancel();
+                    return Ok(false);
+                }
                 KeyCode::Esc
                     if self.state.view_mode == ViewMode::Dashboard
                         && self.state.agent_summary_open =>
@@ -406,99 +843,265 @@ impl UIActor {
                     self.state.close_agent_summary();
                     return Ok(false);
                 }
+                KeyCode::Esc if self.state.view_mode == ViewMode::Dashboard => {
+                    self.state.toggle_dashboard();
+                    return Ok(false);
+                }
                 _ => {}
             }
         }
 
+        // Actions of one list share keys with global ones (`s` splits in
+        // the Panes list and sorts elsewhere), so they are tried first, and
+        // only in their list.
+        let scoped = [
+            (in_panes, Action::SplitPane),
+            (in_panes, Action::SplitPaneVertical),
+            (in_panes, Action::KillPane),
+            (in_windows, Action::MoveWindow),
+        ];
+        if let Some(&(_, action)) =
+            scoped.iter().find(|&&(here, a)| here && self.state.keybindings.binds(a, &key))
+        {
+            return self.run_action(action, Some(key)).await;
+        }
+
         // Remappable actions, resolved through the user's key bindings.
         if let Some(action) = self.state.keybindings.action_for(&key) {
-            match action {
-                Action::Quit => return Ok(true),
-                Action::Refresh => {
-                    let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
-                }
-                Action::Sort if in_sessions => self.state.cycle_session_sort(),
-                Action::Group if in_sessions => {
-                    self.state.open_group_session_popup();
-                    self.refresh_control.pause();
-                }
-                Action::Input => {
-                    self.state.enter_input_mode();
-                    self.refresh_control.pause();
-                }
-                Action::NewSession => {
-                    self.state.open_new_session_popup();
-                    self.refresh_control.pause();
-                }
-                Action::RenameSession => {
-                    self.state.open_rename_session_popup();
-                    self.refresh_control.pause();
-                }
-                Action::KillSession => {
-                    self.state.open_kill_session_popup();
-                    self.refresh_control.pause();
-                }
-                Action::Enter if self.state.view_mode == ViewMode::Dashboard => {
-                    // Attach to the selected background session. The UI loop
-                    // consumes `pending_attach` to run `claude attach <id>`.
-                    self.state.pending_attach = self.state.selected_agent_id();
-                }
-                Action::Enter => {
-                    if let Some(target) = self.state.get_enter_target() {
-                        let (reply_tx, reply_rx) = oneshot::channel();
-                        let _ = self
-                            .tmux_cmd_tx
-                            .send(TmuxCommand::SwitchClient {
-                                target,
-                                reply: Some(reply_tx),
-                            })
-                            .await;
-                        let _ = reply_rx.await;
-                        // Optionally keep the deck open after switching.
-                        if self.state.behavior.exit_on_switch {
-                            return Ok(true);
-                        }
-                    }
-                }
-                Action::Dashboard => self.state.toggle_dashboard(),
-                // Context-gated actions whose gate is not satisfied fall through
-                // to navigation so the key is not swallowed.
-                Action::Sort | Action::Group => {
-                    if !is_ctrl {
-                        self.handle_navigation_key(key.code);
-                    }
-                }
-            }
-            return Ok(false);
+            return self.run_action(action, Some(key)).await;
         }
 
-        // Unbound keys: view-specific navigation (only without Ctrl).
+        // Unbound keys: view-specific navigation, or with Ctrl the TreeView
+        // preview's vertical scroll.
         if !is_ctrl {
             self.handle_navigation_key(key.code);
+        } else if self.state.view_mode == ViewMode::TreeView {
+            let half = self.state.preview_half_page();
+            let lines = match key.code {
+                KeyCode::Char('u') => half,
+                KeyCode::Char('d') => -half,
+                KeyCode::Char('k') => 1,
+                KeyCode::Char('j') => -1,
+                _ => return Ok(false),
+            };
+            self.state.preview_scroll_vertical(lines);
+            // The capture follows the scroll into history; fetch it now
+            // rather than on the next tick.
+            let join = self.state.preview.join_wrapped;
+            for (target, start, end) in self.state.visible_capture_targets() {
+                let _ = self
+                    .tmux_cmd_tx
+                    .send(TmuxCommand::CapturePane { target, start, end, join })
+                    .await;
+            }
         }
         Ok(false)
     }
 
+    /// Run the window adjustment `action` (rotate / even layout / resize) on
+    /// the selected window, then refresh. Returns the tmux command it ran, or
+    /// `None` when nothing is selected or there is no other pane to resize
+    /// against.
+    async fn run_window_command(&mut self, action: Action) -> Option<String> {
+        let target = self.state.get_current_window_target()?;
+        let direction = match action {
+            Action::ResizePaneLeft => Some(PaneDirection::Left),
+            Action::ResizePaneRight => Some(PaneDirection::Right),
+            Action::ResizePaneUp => Some(PaneDirection::Up),
+            Action::ResizePaneDown => Some(PaneDirection::Down),
+            _ => None,
+        };
+        let (command, shown) = if let Some(direction) = direction {
+            if self.state.current_window().is_none_or(|w| w.panes.len() < 2) {
+                self.state.status_message =
+                    Some("Only one pane in this window; nothing to resize".to_string());
+                return None;
+            }
+            // The TreeView and the all-panes grid resize the selected pane,
+            // the MultiPreview the window's active one.
+            let target = match self.state.view_mode {
+                ViewMode::TreeView => self.state.get_selected_pane_target()?,
+                ViewMode::AllPanes => self.state.get_all_panes_target()?,
+                _ => target,
+            };
+            let amount = self.state.behavior.resize_step.max(1);
+            let shown = format!("resize-pane -t {target} {} {amount}", direction.flag());
+            (TmuxCommand::ResizePane { target, direction, amount }, shown)
+        } else if action == Action::EvenLayout {
+            let layout = self.state.behavior.even_layout.clone();
+            let shown = format!("select-layout -t {target} {layout}");
+            (TmuxCommand::SelectLayout { target, layout }, shown)
+        } else {
+            // Follow the selected pane to its new position.
+            self.state.pending_pane_select = self.state.selected_pane_id();
+            let reverse = action == Action::RotatePanesReverse;
+            let flag = if reverse { "-D" } else { "-U" };
+            let shown = format!("rotate-window {flag} -t {target}");
+            (TmuxCommand::RotateWindow { target, reverse }, shown)
+        };
+        let _ = self.tmux_cmd_tx.send(command).await;
+        let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
+        Some(shown)
+    }
+
+    /// `switch-client` to `target`, waiting until tmux has done it.
+    async fn switch_client(&mut self, target: String) {
+        if let Some(current) = self.state.get_current_target() {
+            last_target::save(&current);
+        }
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let _ = self
+            .tmux_cmd_tx
+            .send(TmuxCommand::SwitchClient {
+                target,
+                reply: Some(reply_tx),
+            })
+            .await;
+        let _ = reply_rx.await;
+    }
+
+    /// Launch the configured terminal emulator, detached from the deck. Only
+    /// a failure to start it is reported; the window then lives on its own.
+    fn spawn_terminal(&mut self, argv: &[String]) {
+        let spawned = tokio::process::Command::new(&argv[0])
+            .args(&argv[1..])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        if let Err(e) = spawned {
+            self.state.set_error(format!("{} failed to start: {e}", argv[0]));
+        }
+    }
+
+    /// Spawn the user's `[events]` command, if set, with `vars` filled in. It
+    /// runs detached: the UI never waits on it or its exit status.
+    fn run_event(&mut self, template: Option<String>, vars: &[(&str, &str)]) {
+        let Some(template) = template else {
+            return;
+        };
+        let command = render_event_command(&template, vars);
+        let spawned = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        if let Err(e) = spawned {
+            self.state.set_error(format!("event command failed to start: {e}"));
+        }
+    }
+
+    /// Pause the preview refresh while a popup or the input popup is open,
+    /// unless `behavior.pause_on_input` is off. Refresh ticks only recapture
+    /// the preview, so a live one never touches the selection or the input.
+    fn pause_for_overlay(&self) {
+        if self.state.behavior.pause_on_input {
+            self.refresh_control.pause();
+        }
+    }
+
+    /// In `--read-only` mode, refuse `what` with a status note (cleared by the
+    /// next key). Returns whether it was refused.
+    fn refuse_in_read_only(&mut self, what: &str) -> bool {
+        if self.state.read_only {
+            self.state.status_message = Some(format!("Read-only mode: {what} is disabled"));
+        }
+        self.state.read_only
+    }
+
     async fn handle_input_mode_key(&mut self, key: event::KeyEvent) -> Result<()> {
+        // Compose: the key after C-v goes straight to the pane as a tmux key
+        // name, so Escape, control chords and function keys can be sent.
+        if self.state.compose_pending {
+            self.state.compose_pending = false;
+            if let (Some(target), Some(name)) =
+                (self.state.get_current_target(), tmux_key_name(&key))
+            {
+                let _ = self
+                    .tmux_cmd_tx
+                    .send(TmuxCommand::SendKeys {
+                        target,
+                        keys: name,
+                        literal: false,
+                        submit_keys: Vec::new(),
+                        batch: None,
+                        reply: None,
+                    })
+                    .await;
+            }
+            return Ok(());
+        }
+        if key.code == KeyCode::Char(COMPOSE_KEY)
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            self.state.compose_pending = true;
+            return Ok(());
+        }
+        // Anything but the confirming Enter calls off a pending
+        // session-wide send.
+        if key.code != KeyCode::Enter {
+            self.state.send_confirm_pending = false;
+        }
         match key.code {
             KeyCode::Esc => {
                 self.state.exit_input_mode();
                 self.refresh_control.resume();
             }
+            KeyCode::Tab => self.state.cycle_send_scope(),
             KeyCode::Enter => {
-                if let Some(target) = self.state.get_current_target() {
-                    let keys = self.state.input_buffer.clone();
+                let targets = self.state.send_targets();
+                if self.state.send_scope == SendScope::Session
+                    && targets.len() > 1
+                    && !self.state.send_confirm_pending
+                {
+                    self.state.send_confirm_pending = true;
+                    return Ok(());
+                }
+                let keys = self.state.input_buffer.clone();
+                // M-Enter also shows what the command printed: the pane is
+                // captured once before the keys go in and again shortly after.
+                // Both captures share the command channel with the send, so
+                // they are taken in order.
+                let inspected = match targets.as_slice() {
+                    [target] if key.modifiers.contains(KeyModifiers::ALT) => Some(target.clone()),
+                    _ => None,
+                };
+                if let Some(target) = &inspected {
+                    self.state.begin_inspection(target, &keys);
+                    let _ = self
+                        .tmux_cmd_tx
+                        .send(TmuxCommand::SnapshotPane {
+                            target: target.clone(),
+                            start: -INSPECT_HISTORY_LINES,
+                        })
+                        .await;
+                }
+                // A send to several panes is summarised once every pane has
+                // answered.
+                let batch = (targets.len() > 1).then(|| {
+                    let items = targets.iter().map(|t| (t.clone(), t.clone())).collect();
+                    self.state.begin_batch("sent to", "pane", items)
+                });
+                for target in targets {
                     let (reply_tx, reply_rx) = oneshot::channel();
                     let _ = self
                         .tmux_cmd_tx
                         .send(TmuxCommand::SendKeys {
                             target,
-                            keys,
+                            keys: keys.clone(),
+                            literal: true,
+                            submit_keys: self.state.behavior.submit_keys.clone(),
+                            batch,
                             reply: Some(reply_tx),
                         })
                         .await;
                     let _ = reply_rx.await;
                 }
+                if let Some(target) = inspected {
+                    self.snapshot_after(target, self.state.behavior.inspect_delay_ms);
+                }
                 self.state.exit_input_mode();
                 self.refresh_control.resume();
             }
@@ -518,27 +1121,49 @@ impl UIActor {
     /// then restore the TUI. Mirrors the agent view's attach/detach: when the
     /// user detaches (or the session ends) we come back to the list.
     fn attach_agent(&mut self, id: &str) -> Result<()> {
-        // Tear down our TUI so claude owns a clean terminal.
+        let status =
+            self.run_with_terminal(std::process::Command::new("claude").arg("attach").arg(id))?;
+        if let Err(e) = status {
+            self.state.set_error(format!("claude attach failed: {e}"));
+        }
+        self.state.refresh_agents();
+        Ok(())
+    }
+
+    /// Suspend the TUI and `tmux attach` to `target`; detaching comes back to
+    /// the deck.
+    fn attach_tmux(&mut self, target: &str) -> Result<()> {
+        let mut command = std::process::Command::new("tmux");
+        command.args(socket_args(self.state.tmux_server.as_deref()));
+        command.args(["attach", "-t", target]);
+        match self.run_with_terminal(&mut command)? {
+            Ok(s) if s.success() => {}
+            Ok(s) => {
+                self.state.set_error(format!("tmux attach -t {target} failed: {s}"));
+            }
+            Err(e) => self.state.set_error(format!("tmux attach failed: {e}")),
+        }
+        let _ = self.tmux_cmd_tx.try_send(TmuxCommand::RefreshAll);
+        Ok(())
+    }
+
+    /// Tear down the TUI so `command` owns a clean terminal, run it to
+    /// completion, then restore the TUI regardless of how it exited.
+    fn run_with_terminal(
+        &mut self,
+        command: &mut std::process::Command,
+    ) -> Result<io::Result<std::process::ExitStatus>> {
         self.refresh_control.pause();
         disable_raw_mode()?;
         io::stdout().execute(LeaveAlternateScreen)?;
 
-        let status = std::process::Command::new("claude")
-            .arg("attach")
-            .arg(id)
-            .status();
+        let status = command.status();
 
-        // Restore the TUI regardless of how claude exited.
         enable_raw_mode()?;
         io::stdout().execute(EnterAlternateScreen)?;
         self.terminal.clear()?;
         self.refresh_control.resume();
-
-        if let Err(e) = status {
-            self.state.set_error(format!("claude attach failed: {e}"));
-        }
-        self.state.refresh_agents();
-        Ok(())
+        Ok(status)
     }
 
     /// In screen-preview mode, fetch the selected session's `claude logs`
@@ -611,7 +1236,369 @@ impl UIActor {
         });
     }
 
+    /// Perform `action`, pressed as `key` or picked from the command palette
+    /// (`None`). Returns whether the deck should exit. An action whose view
+    /// or focus does not apply falls through to navigation with its key.
+    async fn run_action(&mut self, action: Action, key: Option<event::KeyEvent>) -> Result<bool> {
+        let in_sessions = self.state.view_mode == ViewMode::TreeView
+            && self.state.focus == Focus::Sessions;
+        let in_windows = self.state.view_mode == ViewMode::TreeView
+            && self.state.focus == Focus::Windows;
+        let in_panes = self.state.view_mode == ViewMode::TreeView
+            && self.state.focus == Focus::Panes;
+        match action {
+            Action::Quit => return Ok(true),
+            Action::SplitPane | Action::SplitPaneVertical if in_panes => {
+                if !self.refuse_in_read_only("splitting panes")
+                    && let Some(target) = self.state.get_selected_pane_target()
+                {
+                    let vertical = action == Action::SplitPaneVertical;
+                    let cmd = TmuxCommand::SplitPane { target, vertical };
+                    let _ = self.tmux_cmd_tx.send(cmd).await;
+                    let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
+                }
+            }
+            Action::KillPane if in_panes => {
+                if !self.refuse_in_read_only("killing panes") {
+                    self.state.open_kill_pane_popup();
+                    if self.state.popup_mode.is_some() {
+                        self.pause_for_overlay();
+                    }
+                }
+            }
+            Action::MoveWindow if in_windows => {
+                if !self.refuse_in_read_only("moving windows") {
+                    self.state.open_move_window_picker();
+                    if self.state.popup_mode.is_some() {
+                        self.pause_for_overlay();
+                    }
+                }
+            }
+            Action::Refresh => {
+                let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
+            }
+            // Content-only refresh: re-capture what is on screen via the
+            // high-priority channel, leaving the tree as it is.
+            Action::Recapture => {
+                self.state.refresh_claude_states();
+                let join = self.state.preview.join_wrapped;
+                for (target, start, end) in self.state.visible_capture_targets() {
+                    let _ = self
+                        .tmux_cmd_tx
+                        .send(TmuxCommand::CapturePane { target, start, end, join })
+                        .await;
+                }
+            }
+            Action::Sort if in_sessions => self.state.cycle_session_sort(),
+            Action::Sort if in_windows => self.state.cycle_window_sort(),
+            Action::ToggleInactive
+                if matches!(self.state.view_mode, ViewMode::MultiPreview | ViewMode::AllPanes) =>
+            {
+                self.state.toggle_inactive_sessions();
+            }
+            Action::FocusParent if self.state.view_mode == ViewMode::TreeView => {
+                self.state.tree_focus_parent();
+            }
+            Action::Monitor if self.state.view_mode == ViewMode::TreeView => {
+                self.state.toggle_monitor_selected();
+            }
+            Action::CollapseList if self.state.view_mode == ViewMode::TreeView => {
+                self.state.toggle_collapse_focused_list();
+            }
+            Action::SyncPanes if self.state.view_mode == ViewMode::TreeView => {
+                if !self.refuse_in_read_only("synchronizing panes")
+                    && let Some(target) = self.state.get_current_window_target()
+                {
+                    let cmd = TmuxCommand::ToggleSyncPanes { target };
+                    let _ = self.tmux_cmd_tx.send(cmd).await;
+                }
+            }
+            Action::Yank if self.state.view_mode == ViewMode::TreeView => {
+                let Some(content) = self.state.preview_plain_text() else {
+                    self.state.status_message = Some("Nothing to copy".to_string());
+                    return Ok(false);
+                };
+                match self.state.behavior.yank_to {
+                    YankTarget::Clipboard => {
+                        let lines = content.lines().count();
+                        let mut stdout = io::stdout();
+                        stdout.write_all(clipboard::osc52(&content).as_bytes())?;
+                        stdout.flush()?;
+                        self.state.status_message = Some(format!("Copied {lines} lines"));
+                    }
+                    YankTarget::Tmux => {
+                        let cmd = TmuxCommand::LoadBuffer { content };
+                        let _ = self.tmux_cmd_tx.send(cmd).await;
+                    }
+                }
+            }
+            Action::Search if self.state.view_mode == ViewMode::TreeView => {
+                self.state.open_search();
+                self.pause_for_overlay();
+            }
+            Action::Follow if self.state.view_mode != ViewMode::Dashboard => {
+                self.state.toggle_follow();
+            }
+            Action::ToggleBorders if self.state.view_mode != ViewMode::Dashboard => {
+                self.state.preview.borders = !self.state.preview.borders;
+            }
+            Action::WrapPreview if self.state.view_mode != ViewMode::Dashboard => {
+                self.state.toggle_preview_wrap();
+            }
+            Action::AlertsOnly if self.state.view_mode != ViewMode::Dashboard => {
+                self.state.toggle_alerts_only();
+                if !self.state.alerts_only {
+                    let _ = self.tmux_cmd_tx.send(TmuxCommand::RefreshAll).await;
+                }
+            }
+            Action::ReloadConfig => {
+                self.state.reload_config(Config::load(self.state.config_path.as_deref()));
+                self.refresh_control.set_interval(self.state.interval);
+                let fields = self.state.behavior.skip_fields.clone();
+                let _ = self.tmux_cmd_tx.send(TmuxCommand::SetSkipFields { fields }).await;
+            }
+            Action::SwitchServer => {
+                self.state.open_server_picker();
+                self.pause_for_overlay();
+            }
+            Action::Workspaces if self.state.view_mode != ViewMode::Dashboard => {
+                self.state.open_workspace_picker();
+                self.pause_for_overlay();
+            }
+            Action::GotoPane => {
+                self.state.open_goto_pane_popup();
+                self.pause_for_overlay();
+            }
+            Action::CaptureRange if self.state.view_mode == ViewMode::TreeView => {
+                self.state.open_capture_range_popup();
+                self.pause_for_overlay();
+            }
+            Action::Group if in_sessions => {
+                self.state.open_group_session_popup();
+                self.pause_for_overlay();
+            }
+            // The mutating actions below are refused up front in
+            // read-only mode, so their popups / input mode never open.
+            Action::Input => {
+                if self.refuse_in_read_only("send-keys") {
+                    return Ok(false);
+                }
+                self.state.enter_input_mode();
+                self.pause_for_overlay();
+            }
+            // In the TreeView's windows list the session keys act on
+            // windows instead.
+            Action::NewSession if in_windows => {
+                if self.refuse_in_read_only("creating windows") {
+                    return Ok(false);
+                }
+                self.state.open_new_window_popup();
+                self.pause_for_overlay();
+            }
+            Action::RenameSession if in_windows => {
+                if self.refuse_in_read_only("renaming windows") {
+                    return Ok(false);
+                }
+                self.state.open_rename_window_popup();
+                self.pause_for_overlay();
+            }
+            Action::KillSession if in_windows => {
+                if self.refuse_in_read_only("killing windows") {
+                    return Ok(false);
+                }
+                self.state.open_kill_window_popup();
+                self.pause_for_overlay();
+            }
+            Action::NewSession => {
+                if self.refuse_in_read_only("creating sessions") {
+                    return Ok(false);
+                }
+                self.state.open_new_session_popup();
+                self.pause_for_overlay();
+            }
+            Action::NewFromTemplate => {
+                if self.refuse_in_read_only("creating sessions") {
+                    return Ok(false);
+                }
+                if self.state.open_template_picker() {
+                    self.pause_for_overlay();
+                }
+            }
+            Action::RenameSession => {
+                if self.refuse_in_read_only("renaming sessions") {
+                    return Ok(false);
+                }
+                self.state.open_rename_session_popup();
+                self.pause_for_overlay();
+            }
+            Action::KillSession => {
+                if self.refuse_in_read_only("killing sessions") {
+                    return Ok(false);
+                }
+                self.state.open_kill_session_popup();
+                self.pause_for_overlay();
+            }
+            Action::KillOtherSessions => {
+                if self.refuse_in_read_only("killing sessions") {
+                    return Ok(false);
+                }
+                if self.state.open_kill_others_popup() {
+                    self.pause_for_overlay();
+                }
+            }
+            Action::Enter if self.state.view_mode == ViewMode::Dashboard => {
+                if self.refuse_in_read_only("attaching") {
+                    return Ok(false);
+                }
+                // Attach to the selected background session. The UI loop
+                // consumes `pending_attach` to run `claude attach <id>`.
+                self.state.pending_attach = self.state.selected_agent_id();
+            }
+            Action::Enter => {
+                let mut enter = self.state.behavior.enter_action(self.state.view_mode);
+                if enter == EnterAction::Expand && self.state.expand_selection() {
+                    return Ok(false);
+                }
+                if self.refuse_in_read_only("switching") {
+                    return Ok(false);
+                }
+                // Attaching from inside tmux would nest clients, and
+                // outside it there is no client to switch.
+                if !self.state.inside_tmux {
+                    enter = EnterAction::Attach;
+                } else if enter == EnterAction::Attach {
+                    enter = EnterAction::SwitchStay;
+                }
+                let Some(target) = self.state.get_enter_target() else {
+                    self.state.set_error("No session selected".to_string());
+                    return Ok(false);
+                };
+                if enter == EnterAction::Attach {
+                    self.state.pending_tmux_attach = Some(target);
+                    return Ok(false);
+                }
+                self.switch_client(target).await;
+                let exit = match enter {
+                    EnterAction::SwitchExit => true,
+                    EnterAction::SwitchStay => false,
+                    // Expanding past a pane switches like the default.
+                    _ => self.state.behavior.exit_on_switch,
+                };
+                if exit {
+                    return Ok(true);
+                }
+            }
+            Action::OpenInTerminal => {
+                if self.refuse_in_read_only("attaching") {
+                    return Ok(false);
+                }
+                let Some(target) = self.state.get_enter_target() else {
+                    return Ok(false);
+                };
+                match self.state.behavior.terminal_argv(display_target(&target)) {
+                    Some(argv) => self.spawn_terminal(&argv),
+                    // No terminal configured: switch (or, outside tmux,
+                    // attach) in place instead.
+                    None if !self.state.inside_tmux => {
+                        self.state.pending_tmux_attach = Some(target);
+                    }
+                    None => {
+                        self.switch_client(target).await;
+                        if self.state.behavior.exit_on_switch {
+                            return Ok(true);
+                        }
+                    }
+                }
+            }
+            Action::DeadPanes => {
+                if self.state.open_dead_panes_popup() {
+                    self.pause_for_overlay();
+                }
+            }
+            Action::Dashboard => self.state.toggle_dashboard(),
+            Action::RotatePanes
+            | Action::RotatePanesReverse
+            | Action::EvenLayout
+            | Action::ResizePaneLeft
+            | Action::ResizePaneRight
+            | Action::ResizePaneUp
+            | Action::ResizePaneDown => {
+                let what = match action {
+                    Action::EvenLayout => "changing layouts",
+                    Action::RotatePanes | Action::RotatePanesReverse => "rotating panes",
+                    _ => "resizing panes",
+                };
+                if self.refuse_in_read_only(what) {
+                    return Ok(false);
+                }
+                if self.run_window_command(action).await.is_some() {
+                    self.state.last_command = Some(action);
+                }
+            }
+            // A window command re-runs as the tmux command it was, anything
+            // picked from the palette as if picked again.
+            Action::RepeatLast => match self.state.last_command {
+                None => {}
+                Some(
+                    last @ (Action::RotatePanes
+                    | Action::RotatePanesReverse
+                    | Action::EvenLayout
+                    | Action::ResizePaneLeft
+                    | Action::ResizePaneRight
+                    | Action::ResizePaneUp
+                    | Action::ResizePaneDown),
+                ) => {
+                    if !self.refuse_in_read_only("repeating commands")
+                        && let Some(command) = self.run_window_command(last).await
+                    {
+                        self.state.status_message = Some(format!("repeated: {command}"));
+                    }
+                }
+                Some(last) => {
+                    let quit = Box::pin(self.run_action(last, None)).await?;
+                    if self.state.status_message.is_none() {
+                        self.state.status_message = Some(format!("repeated: {}", last.name()));
+                    }
+                    return Ok(quit);
+                }
+            },
+            // Context-gated actions whose gate is not satisfied fall through
+            // to navigation so the key is not swallowed. Picked from the
+            // palette, they say why nothing happened.
+            Action::SplitPane
+            | Action::SplitPaneVertical
+            | Action::KillPane
+            | Action::MoveWindow
+            | Action::Sort
+            | Action::Group
+            | Action::ToggleInactive
+            | Action::ToggleBorders
+            | Action::WrapPreview
+            | Action::AlertsOnly
+            | Action::Monitor
+            | Action::CollapseList
+            | Action::Follow
+            | Action::Search
+            | Action::Yank
+            | Action::SyncPanes
+            | Action::FocusParent
+            | Action::CaptureRange
+            | Action::Workspaces => match key {
+                Some(key) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.handle_navigation_key(key.code);
+                }
+                Some(_) => {}
+                None => {
+                    self.state.status_message =
+                        Some(format!("{} does not apply here", action.name()));
+                }
+            },
+        }
+        Ok(false)
+    }
+
     fn handle_navigation_key(&mut self, code: KeyCode) {
+        self.state.suspend_follow();
         match self.state.view_mode {
             ViewMode::TreeView => match code {
                 KeyCode::Up | KeyCode::Char('k') => self.state.tree_move_up(),
@@ -620,15 +1607,56 @@ impl UIActor {
                 KeyCode::BackTab => self.state.tree_prev_focus(),
                 KeyCode::Left | KeyCode::Char('h') => self.state.tree_prev_focus(),
                 KeyCode::Right | KeyCode::Char('l') => self.state.tree_next_focus(),
+                KeyCode::Char('H') => self.state.preview_scroll_left(),
+                KeyCode::Char('L') => self.state.preview_scroll_right(),
+                KeyCode::Home | KeyCode::Char('g') => self.state.tree_move_top(),
+                KeyCode::End | KeyCode::Char('G') => self.state.tree_move_bottom(),
                 _ => {}
             },
+            // Zoomed in, j/k scroll back through the window's capture.
+            ViewMode::MultiPreview if self.state.zoomed => match code {
+                KeyCode::Up | KeyCode::Char('k') => self.state.preview_scroll_vertical(1),
+                KeyCode::Down | KeyCode::Char('j') => self.state.preview_scroll_vertical(-1),
+                KeyCode::Left | KeyCode::Char('h') => self.state.multi_move_left(),
+                KeyCode::Right | KeyCode::Char('l') => self.state.multi_move_right(),
+                _ => {}
+            },
+            // The grid reads left to right, top to bottom.
+            ViewMode::MultiPreview if self.state.layout.multi_columns > 0 => {
+                let row = self.state.layout.multi_columns as isize;
+                match code {
+                    KeyCode::Up | KeyCode::Char('k') => self.state.multi_grid_move(-row),
+                    KeyCode::Down | KeyCode::Char('j') => self.state.multi_grid_move(row),
+                    KeyCode::Left | KeyCode::Char('h') => self.state.multi_grid_move(-1),
+                    KeyCode::Right | KeyCode::Char('l') => self.state.multi_grid_move(1),
+                    KeyCode::Home | KeyCode::Char('g') => self.state.multi_grid_edge(false),
+                    KeyCode::End | KeyCode::Char('G') => self.state.multi_grid_edge(true),
+                    KeyCode::Char('$') => self.state.multi_move_last(),
+                    _ => {}
+                }
+            }
             ViewMode::MultiPreview => match code {
                 KeyCode::Up | KeyCode::Char('k') => self.state.multi_move_up(),
                 KeyCode::Down | KeyCode::Char('j') => self.state.multi_move_down(),
                 KeyCode::Left | KeyCode::Char('h') => self.state.multi_move_left(),
                 KeyCode::Right | KeyCode::Char('l') => self.state.multi_move_right(),
+                KeyCode::Home | KeyCode::Char('g') => self.state.multi_move_top(),
+                KeyCode::End | KeyCode::Char('G') => self.state.multi_move_bottom(),
+                KeyCode::Char('$') => self.state.multi_move_last(),
                 _ => {}
             },
+            ViewMode::AllPanes => {
+                let row = self.state.all_panes_columns() as isize;
+                match code {
+                    KeyCode::Up | KeyCode::Char('k') => self.state.all_panes_move(-row),
+                    KeyCode::Down | KeyCode::Char('j') => self.state.all_panes_move(row),
+                    KeyCode::Left | KeyCode::Char('h') => self.state.all_panes_move(-1),
+                    KeyCode::Right | KeyCode::Char('l') => self.state.all_panes_move(1),
+                    KeyCode::Home | KeyCode::Char('g') => self.state.all_panes_edge(false),
+                    KeyCode::End | KeyCode::Char('G') => self.state.all_panes_edge(true),
+                    _ => {}
+                }
+            }
             ViewMode::Dashboard => match code {
                 KeyCode::Down | KeyCode::Tab | KeyCode::Char('j') => {
                     self.state.agent_select_next()
@@ -641,26 +1669,62 @@ impl UIActor {
         }
     }
 
-    fn handle_tmux_response(&mut self, response: TmuxResponse) {
+    /// Apply `response` to the state. Returns whether the screen needs a
+    /// redraw, which only a capture identical to the last one does not.
+    fn handle_tmux_response(&mut self, response: TmuxResponse) -> bool {
         match response {
-            TmuxResponse::SessionsRefreshed { sessions } => {
+            TmuxResponse::SessionsRefreshed { sessions, elapsed_ms } => {
+                self.state.last_refresh = Some((elapsed_ms, std::time::Instant::now()));
+                self.state.track_session_changes(&sessions);
                 self.state.update_sessions(sessions);
+                if let Some(sampler) = self.resources.as_mut() {
+                    sampler.annotate(&mut self.state.sessions);
+                }
+                if let Some(target) = self.state.pending_reveal.take()
+                    && !self.state.select_target(&target)
+                {
+                    self.state.set_error(format!("--target: no pane {target}"));
+                }
+                if let Some(target) = self.state.pending_restore.take() {
+                    self.state.restore_selection(&target);
+                }
+                if let Some(pane_id) = self.state.pending_moved_window.take() {
+                    self.state.select_moved_window(&pane_id);
+                }
             }
-            TmuxResponse::PaneCaptured { target: _, content } => {
-                self.state.update_pane_content(content);
+            TmuxResponse::PaneCaptured { target, content, printable } => {
+                return match self.state.update_monitor_content(&target, &content, printable) {
+                    Some(changed) => changed,
+                    None => self.state.update_pane_content(content, printable),
+                };
+            }
+            TmuxResponse::PaneSnapshot { target, content } => {
+                if self.state.record_snapshot(&target, content) {
+                    self.pause_for_overlay();
+                }
             }
             TmuxResponse::SessionCreated {
                 name,
+                batch,
                 success,
                 error,
             } => {
+                if let Some(batch) = batch {
+                    self.state.record_batch_result(batch, &name, error.clone());
+                }
                 if success {
+                    self.run_event(
+                        self.state.events.on_session_created.clone(),
+                        &[("session", &name)],
+                    );
                     // Select the new session
                     if let Some(idx) = self.state.sessions.iter().position(|s| s.name == name) {
                         self.state.selected_session = idx;
                         self.state.session_list_state.select(Some(idx));
                     }
-                } else if let Some(err) = error {
+                } else if batch.is_none()
+                    && let Some(err) = error
+                {
                     self.state.set_error(err);
                 }
             }
@@ -669,8 +1733,20 @@ impl UIActor {
                     self.state.set_error(err);
                 }
             }
-            TmuxResponse::SessionKilled { success, error } => {
+            TmuxResponse::SessionKilled {
+                name,
+                batch,
+                success,
+                error,
+            } => {
+                if let Some(batch) = batch {
+                    self.state.record_batch_result(batch, &name, error.clone());
+                }
                 if success {
+                    self.run_event(
+                        self.state.events.on_session_killed.clone(),
+                        &[("session", &name)],
+                    );
                     // Adjust selection if needed
                     if !self.state.sessions.is_empty() {
                         self.state.selected_session = self
@@ -681,11 +1757,71 @@ impl UIActor {
                             .session_list_state
                             .select(Some(self.state.selected_session));
                     }
+                } else if batch.is_none()
+                    && let Some(err) = error
+                {
+                    self.state.set_error(err);
+                }
+            }
+            TmuxResponse::PaneKilled { id, batch, success, error } => {
+                // Drop the pane now rather than at the next refresh, so the
+                // selection never points past the end of its window meanwhile.
+                if success {
+                    self.state.forget_pane(&id);
+                }
+                match batch {
+                    Some(batch) => self.state.record_batch_result(batch, &id, error),
+                    None => {
+                        if !success && let Some(err) = error {
+                            self.state.set_error(err);
+                        }
+                    }
+                }
+            }
+            TmuxResponse::PaneRespawned { id, batch: Some(batch), error, .. } => {
+                self.state.record_batch_result(batch, &id, error);
+            }
+            TmuxResponse::PanesSynchronized { on, success, error } => {
+                if success {
+                    let state = if on { "on" } else { "off" };
+                    self.state.status_message = Some(format!("Pane synchronization {state}"));
+                    let _ = self.tmux_cmd_tx.try_send(TmuxCommand::RefreshAll);
+                } else if let Some(err) = error {
+                    self.state.set_error(err);
+                }
+            }
+            TmuxResponse::WindowMoved { src_target, success, error } => {
+                if success {
+                    self.state.pending_moved_window = Some(src_target);
+                    let _ = self.tmux_cmd_tx.try_send(TmuxCommand::RefreshAll);
                 } else if let Some(err) = error {
                     self.state.set_error(err);
                 }
             }
-            TmuxResponse::KeysSent { success: _, error } => {
+            TmuxResponse::WindowCreated { success, error }
+            | TmuxResponse::PaneSplit { success, error }
+            | TmuxResponse::WindowRenamed { success, error }
+            | TmuxResponse::WindowKilled { success, error }
+            | TmuxResponse::WindowRotated { success, error }
+            | TmuxResponse::LayoutSelected { success, error }
+            | TmuxResponse::PaneResized { success, error }
+            | TmuxResponse::PaneRespawned { success, error, .. } => {
+                if !success && let Some(err) = error {
+                    self.state.set_error(err);
+                }
+            }
+            TmuxResponse::BufferLoaded { lines, success, error } => {
+                if success {
+                    self.state.status_message =
+                        Some(format!("Copied {lines} lines to a tmux buffer"));
+                } else if let Some(err) = error {
+                    self.state.set_error(err);
+                }
+            }
+            TmuxResponse::KeysSent { target, batch: Some(batch), error, .. } => {
+                self.state.record_batch_result(batch, &target, error);
+            }
+            TmuxResponse::KeysSent { error, .. } => {
                 if let Some(err) = error {
                     self.state.set_error(err);
                 }
@@ -695,7 +1831,10 @@ impl UIActor {
                 success,
                 error,
             } => {
-                if !success {
+                let target = display_target(&target);
+                if success {
+                    self.run_event(self.state.events.on_switch.clone(), &[("target", target)]);
+                } else {
                     let message = match error {
                         Some(err) if !err.trim().is_empty() => {
                             format!("Failed to switch to {}: {}", target, err)
@@ -705,10 +1844,19 @@ impl UIActor {
                     self.state.set_error(message);
                 }
             }
+            TmuxResponse::ServerSwitched { server } => {
+                self.state.server_switched(server);
+                let _ = self.tmux_cmd_tx.try_send(TmuxCommand::RefreshAll);
+            }
+            TmuxResponse::ServerRestarted => self.state.server_restarted(),
+            TmuxResponse::RefreshCancelled => {
+                self.state.status_message = Some("Refresh cancelled".to_string());
+            }
             TmuxResponse::Error { message } => {
                 self.state.set_error(message);
             }
         }
+        true
     }
 }
 
@@ -748,8 +1896,66 @@ async fn generate_summary(transcript_path: &str, model: &str) -> Result<String,
     }
 }
 
+/// Input-mode compose key (with Ctrl): the next key is sent as a special key.
+/// Same chord as the terminal's own "insert next key literally".
+const COMPOSE_KEY: char = 'v';
+
+/// tmux `send-keys` name for `key`: `Escape`, `C-c`, `M-x`, `F5`, `S-Up`, …
+/// `None` for keys tmux has no name for.
+fn tmux_key_name(key: &event::KeyEvent) -> Option<String> {
+    let base = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Esc => "Escape".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "BTab".to_string(),
+        KeyCode::Backspace => "BSpace".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PPage".to_string(),
+        KeyCode::PageDown => "NPage".to_string(),
+        KeyCode::Insert => "IC".to_string(),
+        KeyCode::Delete => "DC".to_string(),
+        KeyCode::F(n @ 1..=12) => format!("F{n}"),
+        _ => return None,
+    };
+    let mut name = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        name.push_str("C-");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("M-");
+    }
+    // Shift is already folded into the character for printable keys.
+    if key.modifiers.contains(KeyModifiers::SHIFT) && !matches!(key.code, KeyCode::Char(_)) {
+        name.push_str("S-");
+    }
+    name.push_str(&base);
+    Some(name)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
     #[test]
     fn test_handle_key_event() {}
+
+    #[test]
+    fn special_keys_map_to_tmux_names() {
+        let name = |code, modifiers| tmux_key_name(&KeyEvent::new(code, modifiers));
+        assert_eq!(name(KeyCode::Esc, KeyModifiers::NONE).as_deref(), Some("Escape"));
+        assert_eq!(name(KeyCode::Char('c'), KeyModifiers::CONTROL).as_deref(), Some("C-c"));
+        assert_eq!(name(KeyCode::Char('X'), KeyModifiers::SHIFT).as_deref(), Some("X"));
+        assert_eq!(name(KeyCode::Up, KeyModifiers::SHIFT).as_deref(), Some("S-Up"));
+        assert_eq!(name(KeyCode::F(5), KeyModifiers::ALT).as_deref(), Some("M-F5"));
+        assert_eq!(name(KeyCode::PageDown, KeyModifiers::NONE).as_deref(), Some("NPage"));
+        assert_eq!(name(KeyCode::F(20), KeyModifiers::NONE), None);
+    }
 }
@@ -1,15 +1,22 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
-use ansi_to_tui::IntoText;
+use ratatui::style::Color;
 use ratatui::text::Text;
 use ratatui::widgets::ListState;
+use serde::Serialize;
 
 use crate::agents::{self, AgentSession};
 use crate::config::{
-    AgentsConfig, BehaviorConfig, Config, HooksConfig, KeyBindings, LayoutConfig, Theme,
+    Action, AgentsConfig, BehaviorConfig, ColorDepth, Config, EventsConfig, HooksConfig,
+    KeyBindings, LayoutConfig, PreviewConfig, SessionTemplate, Theme, WindowSort,
 };
 use crate::group::GroupStore;
+use crate::preview::{self, BINARY_PREVIEW_PLACEHOLDER};
+use crate::workspace::{Workspace, WorkspaceStore};
 
 /// How the agent-view preview panel renders the selected session.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -55,6 +62,34 @@ pub const UNGROUPED_LABEL: &str = "Ungrouped";
 /// input popups. Keeps names short enough to render in the narrow list panes.
 pub const SESSION_NAME_MAX_LEN: usize = 30;
 
+/// Rows of the "what will be destroyed" list shown at once in the ConfirmKill
+/// popup; longer lists scroll.
+pub const KILL_LIST_MAX_ROWS: usize = 8;
+
+/// Phrase the TypedConfirm popup wants typed before a bulk kill goes ahead.
+pub const TYPED_CONFIRMATION: &str = "yes";
+
+/// History lines the captures around an inspected send reach back, so output
+/// that scrolls the screen still lines up with what was there before.
+pub const INSPECT_HISTORY_LINES: i32 = 2000;
+
+/// Columns `H` / `L` scroll the TreeView preview by.
+pub const PREVIEW_HSCROLL_STEP: usize = 8;
+
+/// Most columns `+` widens the MultiPreview grid to.
+pub const MAX_MULTI_COLUMNS: u16 = 6;
+
+/// Most scrollback lines `preview.history_lines` makes a capture reach back.
+pub const MAX_HISTORY_LINES: usize = 10_000;
+
+/// Ranges `Tab` cycles through in the capture-range popup; the empty entry
+/// returns to the visible screen.
+pub const CAPTURE_RANGE_PRESETS: &[&str] = &["-100", "-500", "-2000", "-1000..-500", ""];
+
+/// One-time note shown when tmux-deck starts inside a tmux client.
+pub const NESTED_TMUX_NOTE: &str =
+    "Running inside tmux: switching moves this client to the target session";
+
 // =============================================================================
 // Data Structures
 // =============================================================================
@@ -65,7 +100,8 @@ pub const SESSION_NAME_MAX_LEN: usize = 30;
 /// these states tell us *what claude is doing*, sourced from Claude Code's
 /// hook events (see [`crate::hook`]). Variants are ordered loosely by how much
 /// they want the user's attention — see [`ClaudeState::priority`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ClaudeState {
     /// Claude is actively working (prompt submitted / tool running).
     Working,
@@ -137,17 +173,24 @@ impl ClaudeState {
 }
 
 /// Represents a tmux pane
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct TmuxPane {
     pub id: String,
     pub index: u32,
-    #[allow(dead_code)]
     pub width: u32,
-    #[allow(dead_code)]
     pub height: u32,
     pub active: bool,
     pub current_command: String,
     pub pid: u32,
+    /// The pane's program has exited and the pane lingers (`remain-on-exit`).
+    pub dead: bool,
+    /// Exit status of a dead pane's program, when it exited normally.
+    pub dead_status: Option<i32>,
+    /// CPU use of the pane's process tree, in percent of one core, when
+    /// `layout.show_resources` is on (see [`crate::resources`]).
+    pub cpu_percent: Option<f32>,
+    /// Resident memory of the pane's process tree, in kB.
+    pub rss_kb: Option<u64>,
     /// True if a claude process is running in this pane (detected via descendant process scan).
     pub has_claude: bool,
     /// Latest state reported by Claude Code hooks for this pane, if any.
@@ -176,10 +219,19 @@ impl TmuxPane {
 }
 
 /// Represents a tmux window with captured content
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct TmuxWindow {
     pub index: u32,
     pub name: String,
+    /// The session's current window in tmux.
+    pub active: bool,
+    /// `synchronize-panes` is on: input to one pane goes to all of them.
+    pub synchronized: bool,
+    /// The window's pending alert flags from `#{window_flags}`: `!` bell,
+    /// `#` activity, `~` silence. Empty when nothing is pending.
+    pub alerts: String,
+    /// `#{window_activity}`, epoch seconds, for [`WindowSort::Activity`].
+    pub activity: i64,
     pub panes: Vec<TmuxPane>,
     /// True if any pane in this window has claude running.
     pub has_claude: bool,
@@ -194,7 +246,7 @@ impl TmuxWindow {
 }
 
 /// Represents a tmux session
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct TmuxSession {
     pub name: String,
     pub windows: Vec<TmuxWindow>,
@@ -206,16 +258,220 @@ pub struct TmuxSession {
     /// the list without re-querying tmux.
     pub last_attached: i64,
     pub activity: i64,
+    /// Whether at least one client is attached to this session.
+    pub attached: bool,
+    /// Terminal size (columns, rows) of each client attached to this session.
+    pub client_sizes: Vec<(u32, u32)>,
+    /// Epoch seconds of the latest input from a client attached to this
+    /// session; 0 when none is attached.
+    pub client_activity: i64,
     /// tmux-deck-side group label this session belongs to, if any. This is a
     /// purely organisational tag managed by the deck (see [`crate::group`]),
     /// independent of tmux's native session groups. `None` means ungrouped.
     pub group: Option<String>,
 }
 
+impl TmuxSession {
+    /// Exact-match tmux target for this session (see [`session_target`]).
+    pub fn target(&self) -> String {
+        session_target(&self.name)
+    }
+}
+
+/// tmux target for the session named `name`. The `=` prefix makes tmux match
+/// the name exactly: a bare `2` would otherwise also prefix-match `20`, and
+/// fnmatch patterns in names would be expanded.
+pub fn session_target(name: &str) -> String {
+    format!("={name}")
+}
+
+/// `target` without the exact-match `=` added by [`session_target`], for
+/// titles, the status bar and `{target}` placeholders. Only `-t` arguments
+/// keep the prefix.
+pub fn display_target(target: &str) -> &str {
+    target.strip_prefix('=').unwrap_or(target)
+}
+
+/// Whether the characters of `query` appear in `text` in order, ignoring
+/// case, so `dpy` matches `deploy`. An empty query matches everything.
+pub fn fuzzy_match(query: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| text.any(|t| t == q))
+}
+
+/// `err` on one status-bar line: the lines of its message joined, leaving
+/// out the source excerpt (`3 | key = …` and its `^` marker) TOML errors carry.
+fn one_line(err: &color_eyre::Report) -> String {
+    err.to_string()
+        .lines()
+        .map(str::trim)
+        .filter(|l| {
+            let gutter = l.trim_start_matches(|c: char| c.is_ascii_digit()).trim_start();
+            !l.is_empty() && !gutter.starts_with('|')
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 // =============================================================================
 // Enums
 // =============================================================================
 
+/// Lines of a pane the TreeView preview captures when not just the visible
+/// screen, as `capture-pane -S start -E end` line numbers: 0 is the top of the
+/// screen and negative lines are scrollback. The preview shows the bottom of
+/// the range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureRange {
+    pub start: i32,
+    /// Last line; `None` is the bottom of the visible screen.
+    pub end: Option<i32>,
+}
+
+impl CaptureRange {
+    /// Parse `-500` (the last 500 history lines and the screen), `-1000..-500`
+    /// or `-1000..`. Blank means the visible screen only.
+    pub fn parse(input: &str) -> Result<Option<Self>, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+        let line = |s: &str| -> Result<i32, String> {
+            s.trim()
+                .parse()
+                .map_err(|_| format!("not a line number: '{}'", s.trim()))
+        };
+        let range = match input.split_once("..") {
+            Some((start, end)) if end.trim().is_empty() => Self { start: line(start)?, end: None },
+            Some((start, end)) => Self { start: line(start)?, end: Some(line(end)?) },
+            None => Self { start: line(input)?, end: None },
+        };
+        if let Some(end) = range.end
+            && range.start > end
+        {
+            return Err(format!("range starts after it ends: {}..{end}", range.start));
+        }
+        Ok(Some(range))
+    }
+
+    /// `(start, end)` lines for a pane `height` lines tall.
+    pub fn lines(self, height: i32) -> (i32, i32) {
+        (self.start, self.end.unwrap_or(height))
+    }
+
+    /// e.g. `-500..` or `-1000..-500`.
+    pub fn label(self) -> String {
+        match self.end {
+            Some(end) => format!("{}..{}", self.start, end),
+            None => format!("{}..", self.start),
+        }
+    }
+}
+
+/// Results of a multi-item operation, collected as its responses arrive and
+/// summarised once the last one is in (see [`UIState::begin_batch`]).
+#[derive(Debug, Clone)]
+pub struct Batch {
+    /// Past-tense verb for the summary, e.g. `killed`.
+    verb: &'static str,
+    /// Singular noun for the items, e.g. `pane`.
+    noun: &'static str,
+    /// Display label of each item still awaiting its response, keyed by the
+    /// id the response echoes.
+    pending: HashMap<String, String>,
+    succeeded: usize,
+    /// `(label, error)` of each failed item, in arrival order.
+    failed: Vec<(String, String)>,
+}
+
+impl Batch {
+    /// e.g. `killed 4 panes (1 failed: dev:0.1 — can't find pane %3)`.
+    fn summary(&self) -> String {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        let mut out = format!(
+            "{} {} {}{}",
+            self.verb,
+            self.succeeded,
+            self.noun,
+            plural(self.succeeded)
+        );
+        if !self.failed.is_empty() {
+            let failures: Vec<String> = self
+                .failed
+                .iter()
+                .map(|(label, err)| format!("{label} — {err}"))
+                .collect();
+            out.push_str(&format!(
+                " ({} failed: {})",
+                self.failed.len(),
+                failures.join("; ")
+            ));
+        }
+        out
+    }
+}
+
+/// How a session changed in recent refreshes, badged in the sessions list
+/// (see [`UIState::track_session_changes`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionChange {
+    /// Windows gained (positive) or lost (negative).
+    pub windows: i64,
+    /// Whether the session saw activity.
+    pub activity: bool,
+    /// Refreshes left before the badge is dropped.
+    ttl: u8,
+}
+
+impl SessionChange {
+    /// e.g. `+1w`, `-2w~`, or `~` for activity alone.
+    pub fn badge(&self) -> String {
+        let mut out = String::new();
+        if self.windows != 0 {
+            out.push_str(&format!("{:+}w", self.windows));
+        }
+        if self.activity {
+            out.push('~');
+        }
+        out
+    }
+}
+
+/// Which panes the send-keys popup sends to, cycled with Tab.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SendScope {
+    /// The selection only.
+    #[default]
+    Pane,
+    /// Every pane of the selected window.
+    Window,
+    /// Every pane of every window of the selected session.
+    Session,
+}
+
+impl SendScope {
+    pub fn next(self) -> Self {
+        match self {
+            SendScope::Pane => SendScope::Window,
+            SendScope::Window => SendScope::Session,
+            SendScope::Session => SendScope::Pane,
+        }
+    }
+}
+
+/// How the MultiPreview shows sessions no client is attached to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InactiveSessions {
+    /// Shown, with dimmed borders and titles.
+    #[default]
+    Dim,
+    /// Left out of the grid, so the attached sessions get the space.
+    Hide,
+}
+
 /// Main view mode
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ViewMode {
@@ -224,10 +480,13 @@ pub enum ViewMode {
     /// Full-screen fleet view of Claude Code background sessions (the
     /// `claude agents` agent view), grouped by working directory.
     Dashboard,
+    /// Every pane of the MultiPreview's sessions as its own tile, each
+    /// captured on its own.
+    AllPanes,
 }
 
 /// Focus area in TreeView mode
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Focus {
     Sessions,
     Windows,
@@ -258,6 +517,9 @@ pub enum SessionSortKey {
     LastAttached,
     /// Case-insensitive session name.
     Alphabet,
+    /// Whether a client is attached (then `last_attached`), so attached
+    /// sessions come first in the `Desc` direction.
+    Attached,
 }
 
 impl SessionSortKey {
@@ -266,6 +528,7 @@ impl SessionSortKey {
         match self {
             SessionSortKey::LastAttached => "recent",
             SessionSortKey::Alphabet => "abc",
+            SessionSortKey::Attached => "attached",
         }
     }
 
@@ -282,6 +545,10 @@ impl SessionSortKey {
                 .name
                 .to_lowercase()
                 .cmp(&b.name.to_lowercase()),
+            SessionSortKey::Attached => a
+                .attached
+                .cmp(&b.attached)
+                .then_with(|| a.last_attached.cmp(&b.last_attached)),
         }
     }
 }
@@ -333,6 +600,14 @@ impl SessionSort {
             key: SessionSortKey::Alphabet,
             direction: SortDirection::Asc,
         },
+        SessionSort {
+            key: SessionSortKey::Attached,
+            direction: SortDirection::Desc,
+        },
+        SessionSort {
+            key: SessionSortKey::Attached,
+            direction: SortDirection::Asc,
+        },
     ];
 
     /// Label shown in the Sessions list title, e.g. "recent↓" / "abc↑".
@@ -340,6 +615,33 @@ impl SessionSort {
         format!("{}{}", self.key.label(), self.direction.arrow())
     }
 
+    /// Name used for this mode in the config (`behavior.default_sort`).
+    pub fn name(self) -> &'static str {
+        match (self.key, self.direction) {
+            (SessionSortKey::LastAttached, SortDirection::Desc) => "recent",
+            (SessionSortKey::LastAttached, SortDirection::Asc) => "recent_asc",
+            (SessionSortKey::Alphabet, SortDirection::Desc) => "abc",
+            (SessionSortKey::Alphabet, SortDirection::Asc) => "abc_asc",
+            (SessionSortKey::Attached, SortDirection::Desc) => "attached",
+            (SessionSortKey::Attached, SortDirection::Asc) => "attached_asc",
+        }
+    }
+
+    /// Parse a config name (see [`Self::name`]), accepting the `oldest` /
+    /// `alphabet` aliases. Unknown names fall back to the default.
+    pub fn from_name(name: &str) -> Self {
+        let (key, direction) = match name.to_ascii_lowercase().as_str() {
+            "recent_asc" | "oldest" => (SessionSortKey::LastAttached, SortDirection::Asc),
+            "abc" | "alphabet" => (SessionSortKey::Alphabet, SortDirection::Desc),
+            "abc_asc" | "alphabet_asc" => (SessionSortKey::Alphabet, SortDirection::Asc),
+            "attached" => (SessionSortKey::Attached, SortDirection::Desc),
+            "attached_asc" => (SessionSortKey::Attached, SortDirection::Asc),
+            // "recent" / unknown -> the historical default (most recent first).
+            _ => (SessionSortKey::LastAttached, SortDirection::Desc),
+        };
+        Self { key, direction }
+    }
+
     /// Next mode in [`Self::ALL`], wrapping around.
     pub fn next(self) -> Self {
         let idx = Self::ALL.iter().position(|s| *s == self).unwrap_or(0);
@@ -376,12 +678,74 @@ pub enum PopupMode {
     RenameSession,
     /// Confirming session kill
     ConfirmKill,
+    /// Naming a window to create in the selected session; blank lets tmux
+    /// name it.
+    NewWindow,
+    /// Renaming the selected window
+    RenameWindow,
+    /// Confirming window kill
+    ConfirmKillWindow,
+    /// Confirming pane kill
+    ConfirmKillPane,
+    /// Confirming the kill of every session but the selected one
+    ConfirmKillOthers,
+    /// Typing a fuzzy query that dims the TreeView rows it does not match.
+    Search,
     /// Choosing a group for the selected session from a list of existing
     /// groups (plus "ungroup" and "create new" entries).
     GroupSession,
     /// Typing the name of a brand-new group, reached from the GroupSession
     /// list via the "New group" entry.
     NewGroup,
+    /// Choosing a session template from `[[templates]]`.
+    TemplatePicker,
+    /// Naming the session to create from the picked template.
+    TemplateSessionName,
+    /// Listing every dead (`remain-on-exit`) pane on the server, to respawn
+    /// or kill them.
+    DeadPanes,
+    /// Typing the scrollback range the TreeView preview captures.
+    CaptureRange,
+    /// Choosing the tmux server the deck talks to.
+    ServerPicker,
+    /// Choosing the session the TreeView's selected window moves to.
+    MoveWindow,
+    /// Choosing a saved workspace to switch to, or saving the current one.
+    WorkspacePicker,
+    /// Naming the workspace the current view is saved as, reached from the
+    /// WorkspacePicker via its "Save current" entry.
+    NewWorkspace,
+    /// Typing a pane id (`%N`) to jump to.
+    GotoPane,
+    /// Typing [`TYPED_CONFIRMATION`] before a bulk kill (every dead pane, or
+    /// every other session), with `behavior.require_typed_confirmation` set.
+    TypedConfirm,
+    /// The lines a command sent with `M-Enter` added to its pane.
+    CommandOutput,
+    /// Typing a fuzzy query over every action, to run the one picked.
+    CommandPalette,
+}
+
+/// A row of the [`PopupMode::DeadPanes`] list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadPane {
+    /// Pane id (`%N`), the target for kill / respawn.
+    pub id: String,
+    /// `session:window.pane`, for display.
+    pub location: String,
+    pub command: String,
+    pub status: Option<i32>,
+}
+
+/// A send-keys command whose output is being inspected (`M-Enter`).
+#[derive(Debug, Clone)]
+pub struct Inspection {
+    /// The pane the keys went to.
+    pub target: String,
+    /// What was sent, for the popup title.
+    pub command: String,
+    /// The pane's capture from just before the send, once it has arrived.
+    before: Option<String>,
 }
 
 /// The entry highlighted in the [`PopupMode::GroupSession`] selection list.
@@ -437,6 +801,12 @@ pub struct UIState {
     pub window_list_state: ListState,
     pub pane_list_state: ListState,
     pub session_sort: SessionSort,
+    /// Order of each session's windows in the Windows list.
+    pub window_sort: WindowSort,
+    /// Pane id to re-select once the next refresh lands. Set by operations
+    /// that reshuffle pane indices (e.g. rotate) so the selection follows the
+    /// pane rather than staying on a position now holding a different pane.
+    pub pending_pane_select: Option<String>,
 
     /// Persisted tmux-deck-side session grouping (session name -> group).
     pub groups: GroupStore,
@@ -444,12 +814,23 @@ pub struct UIState {
     /// the implicit "Ungrouped" bucket. Fold state is session-runtime only and
     /// is not persisted.
     pub collapsed_groups: HashSet<Option<String>>,
+    /// TreeView lists shrunk to the selected row, leaving their height to the
+    /// others. Runtime only, like the folds.
+    pub collapsed_lists: HashSet<Focus>,
     /// True after `z` is pressed, awaiting the `a` of the `za` fold chord.
     pub pending_z: bool,
 
     // MultiPreview state (session_idx, window_idx)
     pub multi_session: usize,
     pub multi_window: usize,
+    /// The all-panes grid's selected pane, an index into `multi_window`'s
+    /// panes.
+    pub multi_pane: usize,
+    /// The MultiPreview shows only the selected window, full screen, with a
+    /// capture of its active pane (`z`).
+    pub zoomed: bool,
+    /// Whether unattached sessions are dimmed or hidden in the MultiPreview.
+    pub inactive_sessions: InactiveSessions,
 
     /// Claude Code background sessions shown in the agent view, refreshed from
     /// `~/.claude/jobs` while the dashboard is open. Order matches the rendered
@@ -460,6 +841,9 @@ pub struct UIState {
     /// Set to a session id when the user asks to attach; the UI loop consumes it
     /// to run `claude attach <id>` and clears it.
     pub pending_attach: Option<String>,
+    /// Set to a tmux target when Enter is configured to `attach`; the UI loop
+    /// consumes it to run `tmux attach -t <target>` and clears it.
+    pub pending_tmux_attach: Option<String>,
     /// Whether the agent-view preview panel is shown (`p`).
     pub agent_preview: bool,
     /// How the preview renders (transcript vs screen); toggled with `v`.
@@ -478,12 +862,99 @@ pub struct UIState {
     pub pane_content: String,
     pub pane_content_parsed: Option<Text<'static>>,
     pub last_error: Option<String>,
-    #[allow(dead_code)]
+    /// Informational note shown in the status bar (below errors) until the
+    /// next key press dismisses it.
+    pub status_message: Option<String>,
+    /// How long the last full refresh took (ms) and when it landed, for the
+    /// status bar's `layout.show_metrics`.
+    pub last_refresh: Option<(u64, Instant)>,
+    /// Id of the pane tmux-deck itself runs in (`$TMUX_PANE`) when launched
+    /// inside tmux. Never previewed, so the deck does not capture itself.
+    pub self_pane: Option<String>,
+    /// Launched inside tmux (`$TMUX` set). Outside, there is no client to
+    /// `switch-client`, so switching attaches instead.
+    pub inside_tmux: bool,
+    /// Scrollback slice the TreeView preview captures; `None` is the visible
+    /// screen.
+    pub capture_range: Option<CaptureRange>,
+    /// Next entry of [`CAPTURE_RANGE_PRESETS`] `Tab` fills in.
+    pub capture_range_preset: usize,
+    /// Ids of the panes pinned to the monitor strip, in the order marked.
+    pub monitored: Vec<String>,
+    /// Latest capture of each monitored pane, without its trailing blank rows.
+    pub monitor_content: HashMap<String, Text<'static>>,
+    /// Hash of the raw capture behind each entry of `monitor_content`, so an
+    /// unchanged capture is neither re-parsed nor redrawn.
+    monitor_hashes: HashMap<String, u64>,
+    /// Columns the TreeView preview is scrolled right by, for lines wider
+    /// than the panel.
+    pub preview_hscroll: usize,
+    /// Lines the TreeView preview is scrolled back from the bottom of the
+    /// capture.
+    pub preview_vscroll: usize,
+    /// Preview target the scroll offsets were set for; once the preview shows
+    /// another pane they start over.
+    preview_scroll_target: Option<String>,
+    /// Content rows the TreeView preview had on the last frame, for clamping
+    /// `preview_vscroll` and sizing half-page scrolls.
+    pub preview_rows: usize,
+    /// Content columns of the TreeView preview on the last frame, the width
+    /// `preview.wrap` wraps at.
+    pub preview_cols: usize,
+    /// tmux server the deck is showing (see [`crate::server`]); `None` is the
+    /// one it started on.
+    pub tmux_server: Option<String>,
+    /// Servers listed by the [`PopupMode::ServerPicker`] popup.
+    pub server_choices: Vec<Option<String>>,
+    pub server_choice_index: usize,
+    /// Sessions listed by the [`PopupMode::MoveWindow`] popup: every session
+    /// but the selected window's own.
+    pub move_choices: Vec<String>,
+    pub move_choice_index: usize,
+    /// Highlighted row of the [`PopupMode::CommandPalette`] matches.
+    pub palette_index: usize,
+    /// Pane id of a window just moved to another session, selected (with the
+    /// Windows list focused) once a refresh lists it there.
+    pub pending_moved_window: Option<String>,
+    /// `--read-only`: every tmux-mutating action is refused with a note.
+    pub read_only: bool,
+    /// `--force-redraw`: draw a frame after every event, even a capture or
+    /// tick that changed nothing on screen.
+    pub force_redraw: bool,
+    /// The selection tracks the window the most recently active tmux client
+    /// shows, on every refresh, until navigation takes it back.
+    pub follow_active: bool,
+    /// `--session NAME`: every refresh is filtered down to this one session,
+    /// and the TreeView hides its sessions list.
+    pub scoped_session: Option<String>,
+    /// `alerts_only`: every refresh is filtered down to the windows with a
+    /// pending alert, and the sessions holding them.
+    pub alerts_only: bool,
+    /// `--target`: pane to select (see [`Self::select_target`]) once the
+    /// first refresh has listed it.
+    pub pending_reveal: Option<String>,
+    /// The previous run's last target, selected once the first refresh has
+    /// listed the tree (see [`crate::last_target`]).
+    pub pending_restore: Option<String>,
+    /// The next listing comes from a restarted server, so comparing it with
+    /// the tree would badge every session as changed.
+    skip_change_tracking: bool,
+    /// `preview.color_depth`, detected when not configured.
+    pub color_depth: ColorDepth,
+    /// The preview refresh interval.
     pub interval: Duration,
+    /// `--interval` set the interval, so a reloaded config does not change it.
+    pub interval_from_cli: bool,
+    /// `--config`, the file a config reload reads again (the XDG one if unset).
+    pub config_path: Option<PathBuf>,
 
     // Resolved user configuration.
+    /// Preview capture/rendering options.
+    pub preview: PreviewConfig,
     /// Semantic UI colour palette.
     pub theme: Theme,
+    /// Colour of each pane command in the Panes list (`[theme.commands]`).
+    pub command_colors: HashMap<String, Color>,
     /// Per-state hook markers (claude / codex).
     pub hooks: HooksConfig,
     /// Remappable key bindings.
@@ -492,25 +963,71 @@ pub struct UIState {
     pub layout: LayoutConfig,
     /// Behavioural toggles (double-space window, exit-on-switch, …).
     pub behavior: BehaviorConfig,
+    /// Session templates offered by the template picker.
+    pub templates: Vec<SessionTemplate>,
+    /// User commands run on deck events.
+    pub events: EventsConfig,
 
     pub input_mode: InputMode,
     pub input_buffer: String,
     pub input_cursor: usize,
+    /// Set by the compose key in input mode: the next key is sent to the pane
+    /// as a special key instead of being typed into the buffer.
+    pub compose_pending: bool,
+    /// Panes the send-keys popup sends to.
+    pub send_scope: SendScope,
+    /// A session-wide send is waiting for a second Enter to confirm it.
+    pub send_confirm_pending: bool,
+    /// The send whose output the CommandOutput popup shows, while its
+    /// captures are under way or the popup is open.
+    pub inspection: Option<Inspection>,
+    /// What the inspected command added to its pane, and the first line shown.
+    pub inspect_lines: Vec<String>,
+    pub inspect_scroll: usize,
+    /// The TreeView search's query, empty when no search is running.
+    pub search_query: String,
+    /// Selection (session, window, pane and focus) to return to when the
+    /// search is cancelled.
+    search_origin: Option<(usize, usize, usize, Focus)>,
 
     // Popup state
     pub popup_mode: Option<PopupMode>,
     pub confirm_yes_selected: bool,
+    /// Saved workspaces (view, sort and fold snapshots) offered by the
+    /// WorkspacePicker.
+    pub workspaces: WorkspaceStore,
+    /// Index of the highlighted WorkspacePicker entry: a saved workspace, or
+    /// the "Save current" entry just past them.
+    pub workspace_choice_index: usize,
     /// Existing group names offered in the GroupSession selection list,
     /// snapshotted when the popup opens so navigation stays stable.
     pub group_choices: Vec<String>,
     /// Index of the highlighted entry in the GroupSession list. Entries are
     /// `group_choices` followed by the "Ungrouped" and "New group" entries.
     pub group_choice_index: usize,
+    /// Index into `templates` highlighted in the TemplatePicker list; kept
+    /// while the TemplateSessionName prompt is open so Enter knows which one.
+    pub template_choice_index: usize,
+    /// Highlighted row of the dead panes list.
+    pub dead_pane_index: usize,
+    /// Popup the TypedConfirm prompt was opened from and returns to (the dead
+    /// panes list); `None` when it confirms killing the other sessions.
+    pub typed_confirm_origin: Option<PopupMode>,
+    /// Bulk operations still collecting results, by batch id.
+    pub batches: HashMap<u64, Batch>,
+    next_batch_id: u64,
+    /// Last window command run (rotate, even layout, resize) or action picked
+    /// from the palette, for `RepeatLast`.
+    pub last_command: Option<Action>,
+    /// Per-session change since recent refreshes, by session name.
+    pub session_changes: HashMap<String, SessionChange>,
+    /// First visible row of the window list in the ConfirmKill popup.
+    pub kill_list_scroll: usize,
 }
 
 impl UIState {
     pub fn new(config: Config) -> Self {
-        let interval_ms = config.preview.interval.unwrap_or(300);
+        let interval_ms = config.preview.interval.unwrap_or(crate::DEFAULT_INTERVAL_MS);
         let theme = config.theme.resolve();
         let view_mode = config.behavior.view_mode();
         let session_sort = config.behavior.session_sort();
@@ -527,17 +1044,24 @@ impl UIState {
             window_list_state: ListState::default(),
             pane_list_state: ListState::default(),
             session_sort,
+            window_sort: config.behavior.window_sort,
+            pending_pane_select: None,
 
             groups: GroupStore::load(),
             collapsed_groups: HashSet::new(),
+            collapsed_lists: HashSet::new(),
             pending_z: false,
 
             multi_session: 0,
+            inactive_sessions: InactiveSessions::default(),
             multi_window: 0,
+            multi_pane: 0,
+            zoomed: false,
 
             agent_sessions: Vec::new(),
             agent_selected: 0,
             pending_attach: None,
+            pending_tmux_attach: None,
             agent_preview: false,
             agent_preview_mode: PreviewMode::from_str(&config.agents.preview_mode),
             agent_summary_open: false,
@@ -548,21 +1072,78 @@ impl UIState {
             pane_content: String::new(),
             pane_content_parsed: None,
             last_error: None,
+            status_message: None,
+            last_refresh: None,
+            self_pane: None,
+            inside_tmux: false,
+            capture_range: None,
+            capture_range_preset: 0,
+            monitored: Vec::new(),
+            monitor_content: HashMap::new(),
+            monitor_hashes: HashMap::new(),
+            preview_hscroll: 0,
+            preview_vscroll: 0,
+            preview_scroll_target: None,
+            preview_rows: 0,
+            preview_cols: 0,
+            tmux_server: None,
+            server_choices: Vec::new(),
+            server_choice_index: 0,
+            move_choices: Vec::new(),
+            move_choice_index: 0,
+            palette_index: 0,
+            pending_moved_window: None,
+            read_only: false,
+            force_redraw: false,
+            follow_active: false,
+            scoped_session: None,
+            pending_reveal: None,
+            pending_restore: None,
+            skip_change_tracking: false,
+            alerts_only: false,
             interval: Duration::from_millis(interval_ms),
+            interval_from_cli: false,
+            config_path: None,
+            color_depth: config.preview.color_depth.unwrap_or_else(ColorDepth::detect),
 
+            preview: config.preview,
             theme,
+            command_colors: config.theme.command_colors(),
             hooks: config.hooks,
             keybindings: config.keybindings,
-            layout: config.layout,
+            layout: LayoutConfig {
+                multi_columns: config.layout.multi_columns.min(MAX_MULTI_COLUMNS),
+                ..config.layout
+            },
             behavior: config.behavior,
+            templates: config.templates,
+            events: config.events,
 
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
             input_cursor: 0,
+            compose_pending: false,
+            send_scope: SendScope::Pane,
+            send_confirm_pending: false,
+            inspection: None,
+            inspect_lines: Vec::new(),
+            inspect_scroll: 0,
+            search_query: String::new(),
+            search_origin: None,
 
             popup_mode: None,
             group_choices: Vec::new(),
+            workspaces: WorkspaceStore::load(),
+            workspace_choice_index: 0,
             group_choice_index: 0,
+            template_choice_index: 0,
+            dead_pane_index: 0,
+            typed_confirm_origin: None,
+            batches: HashMap::new(),
+            session_changes: HashMap::new(),
+            last_command: None,
+            next_batch_id: 0,
+            kill_list_scroll: 0,
             confirm_yes_selected: false,
         };
         state.session_list_state.select(Some(0));
@@ -571,6 +1152,42 @@ impl UIState {
         state
     }
 
+    /// Take a re-read config file: on success its theme, keys, hooks, layout,
+    /// behaviour, templates and preview options replace the current ones; on
+    /// error everything stays as it was. Either way the status bar says so.
+    ///
+    /// What the user has since changed at runtime (view mode, sort orders) is
+    /// kept, and the interval only follows the file when `--interval` did not
+    /// set it.
+    pub fn reload_config(&mut self, loaded: color_eyre::Result<Config>) {
+        let config = match loaded {
+            Ok(config) => config,
+            Err(e) => {
+                self.status_message = Some(format!("Config not reloaded: {}", one_line(&e)));
+                return;
+            }
+        };
+        if !self.interval_from_cli {
+            let interval_ms = config.preview.interval.unwrap_or(crate::DEFAULT_INTERVAL_MS);
+            self.interval = Duration::from_millis(interval_ms);
+        }
+        self.color_depth = config.preview.color_depth.unwrap_or_else(ColorDepth::detect);
+        self.preview = config.preview;
+        self.theme = config.theme.resolve();
+        self.command_colors = config.theme.command_colors();
+        self.hooks = config.hooks;
+        self.keybindings = config.keybindings;
+        self.layout = LayoutConfig {
+            multi_columns: config.layout.multi_columns.min(MAX_MULTI_COLUMNS),
+            ..config.layout
+        };
+        self.behavior = config.behavior;
+        self.templates = config.templates;
+        self.events = config.events;
+        self.agents_config = config.agents;
+        self.status_message = Some("Config reloaded".to_string());
+    }
+
     // =========================================================================
     // View Mode Switching
     // =========================================================================
@@ -579,8 +1196,9 @@ impl UIState {
     ///
     /// Cheap enough to call on every refresh tick: it only reads a small local
     /// state directory. This keeps markers live without a full tmux refresh.
-    pub fn refresh_claude_states(&mut self) {
-        crate::hook::apply_states(&mut self.sessions);
+    /// Returns whether any marker changed.
+    pub fn refresh_claude_states(&mut self) -> bool {
+        crate::hook::apply_states(&mut self.sessions)
     }
 
     /// True if any session currently has a `Working` Claude marker, used to
@@ -606,27 +1224,46 @@ impl UIState {
     }
 
     pub fn toggle_view_mode(&mut self) {
-        self.view_mode = match self.view_mode {
+        match self.view_mode {
             ViewMode::TreeView => {
                 // Sync multi selection with tree selection
                 self.multi_session = self.selected_session;
                 self.multi_window = self.selected_window;
-                ViewMode::MultiPreview
+                self.clamp_multi_to_visible();
+                self.view_mode = ViewMode::MultiPreview;
             }
             ViewMode::MultiPreview => {
-                // Sync tree selection with multi selection
-                self.selected_session = self.multi_session;
-                self.selected_window = self.multi_window;
-                self.selected_pane = 0;
-                self.session_list_state.select(Some(self.selected_session));
-                self.window_list_state.select(Some(self.selected_window));
-                self.pane_list_state.select(Some(0));
-                ViewMode::TreeView
+                self.zoomed = false;
+                // The grid starts on the selected window's active pane.
+                self.multi_pane = self
+                    .sessions
+                    .get(self.multi_session)
+                    .and_then(|s| s.windows.get(self.multi_window))
+                    .and_then(|w| w.panes.iter().position(|p| p.active))
+                    .unwrap_or(0);
+                self.view_mode = ViewMode::AllPanes;
             }
-            // Double-space cycles only Tree <-> Multi; leaving the dashboard
-            // returns to the tree.
-            ViewMode::Dashboard => ViewMode::TreeView,
-        };
+            ViewMode::AllPanes => self.multi_to_tree(self.multi_pane),
+            // Double-space cycles Tree -> Multi -> AllPanes; leaving the
+            // dashboard returns to the tree.
+            ViewMode::Dashboard => self.view_mode = ViewMode::TreeView,
+        }
+    }
+
+    /// Back to the TreeView on the MultiPreview's selected window and its
+    /// pane `pane`. The all-panes grid's captures are dropped; the monitor
+    /// strip keeps its own.
+    fn multi_to_tree(&mut self, pane: usize) {
+        self.zoomed = false;
+        self.selected_session = self.multi_session;
+        self.selected_window = self.multi_window;
+        self.selected_pane = pane;
+        self.session_list_state.select(Some(self.selected_session));
+        self.window_list_state.select(Some(self.selected_window));
+        self.pane_list_state.select(Some(pane));
+        let monitored = &self.monitored;
+        self.monitor_content.retain(|id, _| monitored.contains(id));
+        self.view_mode = ViewMode::TreeView;
     }
 
     // =========================================================================
@@ -753,18 +1390,80 @@ impl UIState {
         self.input_mode = InputMode::Input;
         self.input_buffer.clear();
         self.input_cursor = 0;
+        self.compose_pending = false;
+        // Every popup starts on the selection alone; a wider fan-out is
+        // always chosen on purpose.
+        self.send_scope = SendScope::Pane;
+        self.send_confirm_pending = false;
     }
 
     pub fn exit_input_mode(&mut self) {
         self.input_mode = InputMode::Normal;
         self.input_buffer.clear();
         self.input_cursor = 0;
+        self.compose_pending = false;
+        self.send_confirm_pending = false;
+    }
+
+    pub fn cycle_send_scope(&mut self) {
+        self.send_scope = self.send_scope.next();
+        self.send_confirm_pending = false;
+    }
+
+    /// The session and window the send-keys popup widens its scope from.
+    fn send_window(&self) -> Option<(&TmuxSession, &TmuxWindow)> {
+        let (session, window) = match self.view_mode {
+            ViewMode::TreeView => (self.selected_session, self.selected_window),
+            ViewMode::MultiPreview | ViewMode::AllPanes
+                if self.multi_is_visible(self.multi_session) =>
+            {
+                (self.multi_session, self.multi_window)
+            }
+            _ => return None,
+        };
+        let session = self.sessions.get(session)?;
+        Some((session, session.windows.get(window)?))
+    }
+
+    /// Targets the send-keys popup sends to under [`UIState::send_scope`].
+    /// Window and session scopes list pane ids, leaving out the deck's own
+    /// pane so it never types into itself.
+    pub fn send_targets(&self) -> Vec<String> {
+        let Some((session, window)) = self.send_window() else {
+            return Vec::new();
+        };
+        let windows = match self.send_scope {
+            SendScope::Pane => return self.get_current_target().into_iter().collect(),
+            SendScope::Window => std::slice::from_ref(window),
+            SendScope::Session => session.windows.as_slice(),
+        };
+        let own = self.self_pane.as_ref().filter(|_| self.tmux_server.is_none());
+        windows
+            .iter()
+            .flat_map(|w| &w.panes)
+            .filter(|p| Some(&p.id) != own)
+            .map(|p| p.id.clone())
+            .collect()
+    }
+
+    /// What the send-keys popup sends to, for its title.
+    pub fn send_scope_label(&self) -> Option<String> {
+        let (session, window) = self.send_window()?;
+        let count = self.send_targets().len();
+        Some(match self.send_scope {
+            SendScope::Pane => self.get_current_target()?,
+            SendScope::Window => {
+                format!("window {}:{} ({count} panes)", session.name, window.index)
+            }
+            SendScope::Session => format!("session {} ({count} panes)", session.name),
+        })
     }
 
     pub fn get_current_target(&self) -> Option<String> {
         match self.view_mode {
             ViewMode::TreeView => self.get_selected_pane_target(),
             ViewMode::MultiPreview => self.get_multi_selected_target(),
+            ViewMode::AllPanes => self.get_all_panes_target(),
             // Agent-view sessions are not tmux panes; they have no send-keys target.
             ViewMode::Dashboard => None,
         }
@@ -776,20 +1475,47 @@ impl UIState {
                 Focus::Sessions => self
                     .sessions
                     .get(self.selected_session)
-                    .map(|s| s.name.clone()),
+                    .map(|s| s.target()),
                 Focus::Windows => {
                     let session = self.sessions.get(self.selected_session)?;
                     let window = session.windows.get(self.selected_window)?;
-                    Some(format!("{}:{}", session.name, window.index))
+                    Some(format!("{}:{}", session.target(), window.index))
                 }
                 Focus::Panes => self.get_selected_pane_target(),
             },
             ViewMode::MultiPreview => self.get_multi_selected_target(),
+            ViewMode::AllPanes => self.get_all_panes_target(),
             // The agent view attaches via `claude attach`, not a tmux target.
             ViewMode::Dashboard => None,
         }
     }
 
+    /// Enter's `expand` behaviour: move one level deeper into the selection.
+    /// Returns false at the innermost level (a pane), where the caller
+    /// switches instead.
+    pub fn expand_selection(&mut self) -> bool {
+        match self.view_mode {
+            ViewMode::TreeView => match self.focus {
+                Focus::Sessions => {
+                    self.focus = Focus::Windows;
+                    true
+                }
+                Focus::Windows => {
+                    self.focus = Focus::Panes;
+                    true
+                }
+                Focus::Panes => false,
+            },
+            ViewMode::MultiPreview => {
+                self.multi_to_tree(0);
+                self.focus = Focus::Windows;
+                true
+            }
+            // A tile is already a pane.
+            ViewMode::AllPanes | ViewMode::Dashboard => false,
+        }
+    }
+
     /// `input_cursor`（char 単位）を `input_buffer` 内のバイトオフセットへ変換する。
     fn input_cursor_byte_offset(&self) -> usize {
         self.input_buffer
@@ -872,6 +1598,90 @@ impl UIState {
         }
     }
 
+    pub fn open_new_window_popup(&mut self) {
+        if self.sessions.get(self.selected_session).is_some() {
+            self.popup_mode = Some(PopupMode::NewWindow);
+            self.input_buffer.clear();
+            self.input_cursor = 0;
+        }
+    }
+
+    pub fn open_rename_window_popup(&mut self) {
+        if let Some(window) = self.selected_window() {
+            self.input_buffer = window.name.clone();
+            self.popup_mode = Some(PopupMode::RenameWindow);
+            self.input_cursor = self.input_char_count();
+        }
+    }
+
+    pub fn open_kill_window_popup(&mut self) {
+        if self.selected_window().is_some() {
+            self.popup_mode = Some(PopupMode::ConfirmKillWindow);
+            self.confirm_yes_selected = false; // Default to No
+            self.kill_list_scroll = 0;
+        }
+    }
+
+    /// Ask to kill the selected pane, unless it is the server's last: tmux
+    /// would exit with it, so that is refused with an error instead.
+    pub fn open_kill_pane_popup(&mut self) {
+        if self.selected_pane_id().is_none() {
+            return;
+        }
+        let panes: usize = self
+            .sessions
+            .iter()
+            .flat_map(|s| &s.windows)
+            .map(|w| w.panes.len())
+            .sum();
+        if panes <= 1 {
+            self.set_error("Refusing to kill the last pane: tmux would exit".to_string());
+            return;
+        }
+        self.popup_mode = Some(PopupMode::ConfirmKillPane);
+        self.confirm_yes_selected = false; // Default to No
+        self.kill_list_scroll = 0;
+    }
+
+    /// The pane to kill once confirmed (for ConfirmKillPane).
+    pub fn get_kill_pane_id(&self) -> Option<String> {
+        self.selected_pane_id().filter(|_| self.confirm_yes_selected)
+    }
+
+    /// The TreeView's selected window.
+    fn selected_window(&self) -> Option<&TmuxWindow> {
+        self.sessions.get(self.selected_session)?.windows.get(self.selected_window)
+    }
+
+    /// `session:index` of the TreeView's selected window.
+    fn selected_window_target(&self) -> Option<String> {
+        let session = self.sessions.get(self.selected_session)?;
+        let window = session.windows.get(self.selected_window)?;
+        Some(format!("{}:{}", session.target(), window.index))
+    }
+
+    /// The session to create a window in and the typed name, if any (for the
+    /// NewWindow popup).
+    pub fn get_new_window_info(&self) -> Option<(String, Option<String>)> {
+        let session = self.sessions.get(self.selected_session)?;
+        let name = Some(self.input_buffer.trim()).filter(|n| !n.is_empty());
+        Some((session.target(), name.map(str::to_string)))
+    }
+
+    /// The selected window's target and its new name (for RenameWindow).
+    pub fn get_rename_window_info(&self) -> Option<(String, String)> {
+        let new_name = self.input_buffer.trim();
+        if new_name.is_empty() {
+            return None;
+        }
+        Some((self.selected_window_target()?, new_name.to_string()))
+    }
+
+    /// The window to kill once confirmed (for ConfirmKillWindow).
+    pub fn get_kill_window_target(&self) -> Option<String> {
+        self.selected_window_target().filter(|_| self.confirm_yes_selected)
+    }
+
     pub fn open_group_session_popup(&mut self) {
         let Some(session) = self.sessions.get(self.selected_session) else {
             return;
@@ -929,726 +1739,4041 @@ impl UIState {
         self.input_cursor = 0;
     }
 
-    pub fn open_kill_session_popup(&mut self) {
-        if !self.sessions.is_empty() {
-            self.popup_mode = Some(PopupMode::ConfirmKill);
-            self.confirm_yes_selected = false; // Default to No
+    /// Open the template picker. With no templates configured there is
+    /// nothing to pick, so an error pointing at the config is shown instead.
+    pub fn open_template_picker(&mut self) -> bool {
+        if self.templates.is_empty() {
+            self.set_error(
+                "No session templates configured (add [[templates]] to the config)".to_string(),
+            );
+            return false;
         }
+        self.popup_mode = Some(PopupMode::TemplatePicker);
+        self.template_choice_index = 0;
+        true
     }
 
-    pub fn close_popup(&mut self) {
-        self.popup_mode = None;
-        self.input_buffer.clear();
-        self.input_cursor = 0;
-        self.confirm_yes_selected = false;
-        self.group_choices.clear();
-        self.group_choice_index = 0;
+    pub fn template_choice_up(&mut self) {
+        let n = self.templates.len().max(1);
+        self.template_choice_index = (self.template_choice_index + n - 1) % n;
     }
 
-    pub fn toggle_confirm_selection(&mut self) {
-        self.confirm_yes_selected = !self.confirm_yes_selected;
+    pub fn template_choice_down(&mut self) {
+        let n = self.templates.len().max(1);
+        self.template_choice_index = (self.template_choice_index + 1) % n;
     }
 
-    /// Get the session name to create (for NewSession popup)
-    pub fn get_new_session_name(&self) -> String {
-        self.input_buffer.trim().to_string()
+    /// Open the server picker with the current server highlighted.
+    pub fn open_server_picker(&mut self) {
+        self.server_choices = crate::server::known_servers(&self.behavior.tmux_sockets);
+        self.server_choice_index = self
+            .server_choices
+            .iter()
+            .position(|s| *s == self.tmux_server)
+            .unwrap_or(0);
+        self.popup_mode = Some(PopupMode::ServerPicker);
     }
 
-    /// Get the current session name and new name (for RenameSession popup)
-    pub fn get_rename_session_info(&self) -> Option<(String, String)> {
-        let new_name = self.input_buffer.trim().to_string();
-        if new_name.is_empty() {
-            return None;
-        }
-        self.sessions
-            .get(self.selected_session)
-            .map(|s| (s.name.clone(), new_name))
+    pub fn server_choice_up(&mut self) {
+        let n = self.server_choices.len().max(1);
+        self.server_choice_index = (self.server_choice_index + n - 1) % n;
     }
 
-    /// Get the group name typed in the GroupSession popup. An empty/whitespace
-    /// entry means "remove from any group" and is returned as `None`.
-    pub fn get_group_session_input(&self) -> Option<String> {
-        let trimmed = self.input_buffer.trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed.to_string())
-        }
+    pub fn server_choice_down(&mut self) {
+        let n = self.server_choices.len().max(1);
+        self.server_choice_index = (self.server_choice_index + 1) % n;
     }
 
-    /// Get the session name to kill (for ConfirmKill popup)
-    pub fn get_kill_session_name(&self) -> Option<String> {
-        if self.confirm_yes_selected {
-            self.sessions
-                .get(self.selected_session)
-                .map(|s| s.name.clone())
-        } else {
-            None
-        }
+    /// The highlighted server, or `None` when the picker is empty.
+    pub fn selected_server_choice(&self) -> Option<Option<String>> {
+        self.server_choices.get(self.server_choice_index).cloned()
     }
 
-    // =========================================================================
-    // Data Update (called when TmuxResponse is received)
-    // =========================================================================
+    pub fn open_command_palette(&mut self) {
+        self.popup_mode = Some(PopupMode::CommandPalette);
+        self.input_buffer.clear();
+        self.input_cursor = 0;
+        self.palette_index = 0;
+    }
 
-    pub fn update_sessions(&mut self, sessions: Vec<TmuxSession>) {
-        // Preserve the user's currently-highlighted session across the refresh:
-        // it may move to a new index once the new order is applied (e.g. when
-        // sort is Alphabet and a session was renamed).
-        let current_name = self
-            .sessions
-            .get(self.selected_session)
-            .map(|s| s.name.clone());
+    /// The actions whose name (words split on `_`) fuzzy-matches the typed
+    /// query, with their key labels, alphabetically.
+    pub fn palette_matches(&self) -> Vec<(Action, String)> {
+        let query = self.input_buffer.trim();
+        self.keybindings
+            .actions()
+            .into_iter()
+            .filter(|(action, _)| fuzzy_match(query, &action.name().replace('_', " ")))
+            .collect()
+    }
 
-        self.sessions = sessions;
-        self.apply_group_labels();
-        self.order_sessions();
+    /// The query changed: start again from the first match.
+    pub fn palette_query_changed(&mut self) {
+        self.palette_index = 0;
+    }
 
-        if let Some(name) = current_name
-            && let Some(idx) = self.sessions.iter().position(|s| s.name == name)
-        {
-            self.selected_session = idx;
-        }
+    pub fn palette_up(&mut self) {
+        let n = self.palette_matches().len().max(1);
+        self.palette_index = (self.palette_index + n - 1) % n;
+    }
 
-        self.validate_selections();
-        self.last_error = None;
+    pub fn palette_down(&mut self) {
+        let n = self.palette_matches().len().max(1);
+        self.palette_index = (self.palette_index + 1) % n;
     }
 
-    /// Stamp each session with its persisted group label. Called whenever fresh
-    /// session data arrives from tmux, since the tmux layer is group-agnostic.
-    fn apply_group_labels(&mut self) {
-        for session in &mut self.sessions {
-            session.group = self.groups.group_of(&session.name);
-        }
+    /// The highlighted action, or `None` when nothing matches.
+    pub fn selected_palette_action(&self) -> Option<Action> {
+        self.palette_matches().get(self.palette_index).map(|(action, _)| *action)
     }
 
-    /// Order the session list: first by the active [`SessionSort`], then cluster
-    /// sessions of the same group together. Because the clustering pass is a
-    /// *stable* sort keyed only on the group, sessions keep their sort order
-    /// within each group, and ungrouped sessions fall to the bottom.
-    fn order_sessions(&mut self) {
-        self.session_sort.apply(&mut self.sessions);
-        self.sessions.sort_by(|a, b| match (&a.group, &b.group) {
-            (Some(x), Some(y)) => x.to_lowercase().cmp(&y.to_lowercase()),
-            (Some(_), None) => std::cmp::Ordering::Less,
-            (None, Some(_)) => std::cmp::Ordering::Greater,
-            (None, None) => std::cmp::Ordering::Equal,
-        });
-    }
-
-    /// Assign the currently-selected session to `group` (or remove it from any
-    /// group when `group` is `None`/empty), persist the change, and re-order the
-    /// list in place keeping that session highlighted. No tmux round-trip is
-    /// needed — grouping is entirely tmux-deck-side.
-    pub fn assign_selected_group(&mut self, group: Option<String>) {
+    /// Open the session picker for moving the selected window, or leave a
+    /// note when there is no other session to move it to.
+    pub fn open_move_window_picker(&mut self) {
         let Some(session) = self.sessions.get(self.selected_session) else {
             return;
         };
-        let name = session.name.clone();
-        self.groups.set(&name, group.as_deref());
-        // Reveal the destination group so the user sees the session land, even
-        // if that group was folded.
-        self.collapsed_groups.remove(&group);
-        self.apply_group_labels();
-        self.resort_sessions_preserve_selection();
+        if session.windows.get(self.selected_window).is_none() {
+            return;
+        }
+        self.move_choices = self
+            .sessions
+            .iter()
+            .filter(|s| s.name != session.name)
+            .map(|s| s.name.clone())
+            .collect();
+        if self.move_choices.is_empty() {
+            self.status_message = Some("No other session to move the window to".to_string());
+            return;
+        }
+        self.move_choice_index = 0;
+        self.popup_mode = Some(PopupMode::MoveWindow);
     }
 
-    /// Whether any session carries a group label. When false there are no
-    /// headers and folding is a no-op (there is nothing to organise yet).
-    fn any_grouped(&self) -> bool {
-        self.sessions.iter().any(|s| s.group.is_some())
+    pub fn move_choice_up(&mut self) {
+        let n = self.move_choices.len().max(1);
+        self.move_choice_index = (self.move_choice_index + n - 1) % n;
     }
 
-    /// Whether `group` is currently folded. Folding only takes effect once
-    /// real groups exist, so a stray collapsed entry never hides a flat list.
-    fn is_collapsed(&self, group: &Option<String>) -> bool {
-        self.any_grouped() && self.collapsed_groups.contains(group)
+    pub fn move_choice_down(&mut self) {
+        let n = self.move_choices.len().max(1);
+        self.move_choice_index = (self.move_choice_index + 1) % n;
     }
 
-    /// Whether the session at `index` is the first of its group in the current
-    /// ordering — the row a folded group collapses onto.
-    fn is_group_head(&self, index: usize) -> bool {
-        match self.sessions.get(index) {
-            None => false,
-            Some(s) => index == 0 || self.sessions[index - 1].group != s.group,
-        }
+    /// The selected window's active pane id, which names the window across
+    /// the move, and the highlighted destination session's target (for the
+    /// MoveWindow popup).
+    pub fn get_move_window_info(&self) -> Option<(String, String)> {
+        let pane = self.selected_window()?.get_active_pane()?;
+        let dst = self.move_choices.get(self.move_choice_index)?;
+        Some((pane.id.clone(), session_target(dst)))
     }
 
-    /// Whether the cursor may rest on the session at `index`. A session is a
-    /// stop when it is visible, or when it is the head of a folded group — in
-    /// which case the cursor visually sits on that group's (collapsed) header,
-    /// so the group can be re-expanded with `za`.
-    fn is_cursor_stop(&self, index: usize) -> bool {
-        match self.sessions.get(index) {
-            None => false,
-            Some(s) => !self.is_collapsed(&s.group) || self.is_group_head(index),
+    /// Select the window moved with [`Self::get_move_window_info`] in its
+    /// new session, keeping the Windows list focused.
+    pub fn select_moved_window(&mut self, pane_id: &str) {
+        if self.reveal_pane(pane_id) {
+            self.focus = Focus::Windows;
         }
     }
 
-    /// Whether the selection currently sits on a folded group's header rather
-    /// than a visible session. Used by the renderer to highlight the header.
-    pub fn selection_on_folded_header(&self) -> bool {
-        self.sessions
-            .get(self.selected_session)
-            .map(|s| self.is_collapsed(&s.group))
-            .unwrap_or(false)
+    /// The tmux server restarted under the deck: every pane id it holds is
+    /// stale. Drop what is keyed by pane id, and the change badges a listing
+    /// of a fresh server would trip, but keep the tree itself so the coming
+    /// refresh reselects the same session (by name) and window (by index).
+    pub fn server_restarted(&mut self) {
+        self.status_message = Some("tmux server restarted — reloaded".to_string());
+        self.session_changes.clear();
+        self.skip_change_tracking = true;
+        self.monitored.clear();
+        self.monitor_content.clear();
+        self.pending_pane_select = None;
+        // Results of kills / respawns sent to the old server never arrive.
+        self.batches.clear();
+        self.inspection = None;
+        self.pane_content.clear();
+        self.pane_content_parsed = None;
     }
 
-    /// Toggle the fold state of the group containing the selected session.
-    /// When folding, the selection collapses onto the group's head so the
-    /// cursor stays on the (now folded) header and the group can be reopened
-    /// with another `za`.
-    pub fn toggle_fold_current_group(&mut self) {
-        if !self.any_grouped() {
+    /// The deck now talks to `server`: forget the previous server's tree so
+    /// nothing from it lingers until the first refresh lands.
+    pub fn server_switched(&mut self, server: Option<String>) {
+        self.status_message = Some(format!(
+            "tmux server: {}",
+            crate::server::label(server.as_deref())
+        ));
+        self.tmux_server = server;
+        self.sessions.clear();
+        self.session_changes.clear();
+        // Pane ids are per server.
+        self.monitored.clear();
+        self.monitor_content.clear();
+        self.inspection = None;
+        self.selected_session = 0;
+        self.selected_window = 0;
+        self.selected_pane = 0;
+        self.multi_session = 0;
+        self.multi_window = 0;
+        self.multi_pane = 0;
+        self.pane_content.clear();
+        self.pane_content_parsed = None;
+        self.validate_selections();
+    }
+
+    pub fn open_workspace_picker(&mut self) {
+        self.popup_mode = Some(PopupMode::WorkspacePicker);
+        self.workspace_choice_index = 0;
+    }
+
+    pub fn workspace_choice_up(&mut self) {
+        let n = self.workspaces.all().len() + 1;
+        self.workspace_choice_index = (self.workspace_choice_index + n - 1) % n;
+    }
+
+    pub fn workspace_choice_down(&mut self) {
+        let n = self.workspaces.all().len() + 1;
+        self.workspace_choice_index = (self.workspace_choice_index + 1) % n;
+    }
+
+    /// Name of the highlighted saved workspace; `None` on the "Save current"
+    /// entry.
+    pub fn selected_workspace(&self) -> Option<String> {
+        self.workspaces
+            .all()
+            .get(self.workspace_choice_index)
+            .map(|w| w.name.clone())
+    }
+
+    /// Switch the open WorkspacePicker into text entry for the name to save
+    /// the current view under.
+    pub fn begin_new_workspace_entry(&mut self) {
+        self.popup_mode = Some(PopupMode::NewWorkspace);
+        self.input_buffer.clear();
+        self.input_cursor = 0;
+    }
+
+    /// Save the current view mode, sort, inactive-session handling, folded
+    /// groups and selected session as workspace `name`, replacing any
+    /// workspace already saved under it.
+    pub fn save_workspace(&mut self, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
             return;
         }
-        let Some(session) = self.sessions.get(self.selected_session) else {
+        let session = match self.view_mode {
+            ViewMode::MultiPreview | ViewMode::AllPanes => self.sessions.get(self.multi_session),
+            _ => self.sessions.get(self.selected_session),
+        };
+        let mut folded: Vec<Option<String>> = self.collapsed_groups.iter().cloned().collect();
+        folded.sort();
+        self.workspaces.save(Workspace {
+            name: name.to_string(),
+            view: self.view_mode,
+            sort: self.session_sort,
+            inactive: self.inactive_sessions,
+            session: session.map(|s| s.name.clone()),
+            folded,
+        });
+        self.status_message = Some(format!("Workspace saved: {name}"));
+    }
+
+    /// Re-apply workspace `name`. The saved session is selected when it still
+    /// exists (its folded group's header, if its group is folded); otherwise
+    /// the selection stays where it is.
+    pub fn apply_workspace(&mut self, name: &str) {
+        let Some(ws) = self.workspaces.get(name).cloned() else {
             return;
         };
-        let group = session.group.clone();
-        if self.collapsed_groups.contains(&group) {
-            self.collapsed_groups.remove(&group);
-        } else {
-            self.collapsed_groups.insert(group.clone());
-            // Park the selection on the group's head so it remains a valid
-            // cursor stop (the folded header) instead of a hidden member.
-            if let Some(head) = self.sessions.iter().position(|s| s.group == group) {
-                self.selected_session = head;
+        self.session_sort = ws.sort;
+        self.resort_sessions_preserve_selection();
+        self.inactive_sessions = ws.inactive;
+        self.collapsed_groups = ws.folded.into_iter().collect();
+        if let Some(idx) = ws
+            .session
+            .and_then(|s| self.sessions.iter().position(|session| session.name == s))
+        {
+            let group = self.sessions[idx].group.clone();
+            let idx = if self.is_cursor_stop(idx) {
+                idx
+            } else {
+                self.sessions.iter().position(|s| s.group == group).unwrap_or(idx)
+            };
+            if idx != self.selected_session {
+                self.selected_session = idx;
                 self.selected_window = 0;
                 self.selected_pane = 0;
                 self.window_list_state.select(Some(0));
                 self.pane_list_state.select(Some(0));
             }
+            self.session_list_state.select(Some(idx));
+            self.multi_session = idx;
+            self.multi_window = 0;
+            self.multi_pane = 0;
+        }
+        // Switching views goes through the double-Space toggle so the tree
+        // and MultiPreview selections are carried across the same way.
+        if self.view_mode == ws.view {
+            self.clamp_multi_to_visible();
+        }
+        for _ in 0..3 {
+            if self.view_mode == ws.view {
+                break;
+            }
+            self.toggle_view_mode();
         }
+        self.status_message = Some(format!("Workspace: {}", ws.name));
     }
 
-    fn next_cursor_stop(&self, from: usize) -> Option<usize> {
-        ((from + 1)..self.sessions.len()).find(|&i| self.is_cursor_stop(i))
+    /// Delete the highlighted saved workspace.
+    pub fn delete_selected_workspace(&mut self) {
+        if let Some(name) = self.selected_workspace() {
+            self.workspaces.remove(&name);
+            let n = self.workspaces.all().len() + 1;
+            self.workspace_choice_index = self.workspace_choice_index.min(n - 1);
+        }
     }
 
-    fn prev_cursor_stop(&self, from: usize) -> Option<usize> {
-        (0..from).rev().find(|&i| self.is_cursor_stop(i))
+    /// Every dead pane on the server, in tree order.
+    pub fn dead_panes(&self) -> Vec<DeadPane> {
+        self.sessions
+            .iter()
+            .flat_map(|s| {
+                s.windows.iter().flat_map(move |w| {
+                    w.panes.iter().filter(|p| p.dead).map(move |p| DeadPane {
+                        id: p.id.clone(),
+                        location: format!("{}:{}.{}", s.name, w.index, p.index),
+                        command: p.current_command.clone(),
+                        status: p.dead_status,
+                    })
+                })
+            })
+            .collect()
     }
 
-    /// Build the rendered Sessions rows, inserting group headers and dropping
-    /// the members of folded groups. When no session is grouped the result is a
-    /// flat list of [`SessionRow::Session`] rows (no headers), matching the
-    /// pre-grouping behaviour exactly.
-    pub fn session_rows(&self) -> Vec<SessionRow> {
-        let any_grouped = self.any_grouped();
-        let mut rows = Vec::with_capacity(self.sessions.len());
-        let mut current: Option<&Option<String>> = None;
-        for (index, session) in self.sessions.iter().enumerate() {
-            let collapsed = any_grouped && self.collapsed_groups.contains(&session.group);
-            if any_grouped && current != Some(&session.group) {
-                let count = self
-                    .sessions
-                    .iter()
-                    .filter(|s| s.group == session.group)
-                    .count();
-                rows.push(SessionRow::Header {
-                    group: session.group.clone(),
-                    count,
-                    collapsed,
-                });
-                current = Some(&session.group);
-            }
-            if !collapsed {
-                rows.push(SessionRow::Session { index });
-            }
+    /// Open the dead panes list, or note that there are none.
+    pub fn open_dead_panes_popup(&mut self) -> bool {
+        if self.dead_panes().is_empty() {
+            self.status_message = Some("No dead panes".to_string());
+            return false;
         }
-        rows
+        self.popup_mode = Some(PopupMode::DeadPanes);
+        self.dead_pane_index = 0;
+        true
     }
 
-    /// Advance to the next [`SessionSort`] and re-sort the list in place,
-    /// keeping the currently-highlighted session highlighted.
-    pub fn cycle_session_sort(&mut self) {
-        self.session_sort = self.session_sort.next();
-        self.resort_sessions_preserve_selection();
+    /// Ask for [`TYPED_CONFIRMATION`] before the bulk kill the open popup (or,
+    /// with none open, `kill_other_sessions`) leads to.
+    pub fn open_typed_confirm(&mut self) {
+        self.typed_confirm_origin = self.popup_mode;
+        self.popup_mode = Some(PopupMode::TypedConfirm);
+        self.input_buffer.clear();
+        self.input_cursor = 0;
     }
 
-    fn resort_sessions_preserve_selection(&mut self) {
-        let current_name = self
-            .sessions
-            .get(self.selected_session)
-            .map(|s| s.name.clone());
+    /// Whether the TypedConfirm input holds the phrase, so the kill may run.
+    pub fn typed_confirmation_matches(&self) -> bool {
+        self.input_buffer.trim() == TYPED_CONFIRMATION
+    }
 
-        self.order_sessions();
+    pub fn dead_pane_up(&mut self) {
+        self.dead_pane_index = self.dead_pane_index.saturating_sub(1);
+    }
 
-        if let Some(name) = current_name
-            && let Some(idx) = self.sessions.iter().position(|s| s.name == name)
+    pub fn dead_pane_down(&mut self) {
+        let last = self.dead_panes().len().saturating_sub(1);
+        self.dead_pane_index = (self.dead_pane_index + 1).min(last);
+    }
+
+    /// The highlighted dead pane. The list shrinks as panes are cleared, so
+    /// the index is clamped to it.
+    pub fn selected_dead_pane(&self) -> Option<DeadPane> {
+        let panes = self.dead_panes();
+        let idx = self.dead_pane_index.min(panes.len().checked_sub(1)?);
+        panes.into_iter().nth(idx)
+    }
+
+    // =========================================================================
+    // Bulk Operations
+    // =========================================================================
+
+    /// Start collecting results for `items` (`(id, label)` pairs), one
+    /// response per id. Returns the batch id to tag their commands with.
+    pub fn begin_batch(
+        &mut self,
+        verb: &'static str,
+        noun: &'static str,
+        items: Vec<(String, String)>,
+    ) -> u64 {
+        self.next_batch_id += 1;
+        self.batches.insert(
+            self.next_batch_id,
+            Batch {
+                verb,
+                noun,
+                pending: items.into_iter().collect(),
+                succeeded: 0,
+                failed: Vec::new(),
+            },
+        );
+        self.next_batch_id
+    }
+
+    /// Record item `id` of `batch` (`error` is `None` on success). The last
+    /// result posts the batch's summary as a status note.
+    pub fn record_batch_result(&mut self, batch: u64, id: &str, error: Option<String>) {
+        let Some(entry) = self.batches.get_mut(&batch) else {
+            return;
+        };
+        let Some(label) = entry.pending.remove(id) else {
+            return;
+        };
+        match error {
+            None => entry.succeeded += 1,
+            Some(err) => entry.failed.push((label, err.trim().to_string())),
+        }
+        if entry.pending.is_empty()
+            && let Some(done) = self.batches.remove(&batch)
         {
-            self.selected_session = idx;
-            self.multi_session = self.multi_session.min(self.sessions.len().saturating_sub(1));
-            self.session_list_state.select(Some(idx));
+            self.status_message = Some(done.summary());
         }
     }
 
-    pub fn update_pane_content(&mut self, content: String) {
-        self.pane_content_parsed = content.as_bytes().into_text().ok();
-        self.pane_content = content;
+    /// Move from the picker to the name prompt, pre-filled with the template's
+    /// name as a starting point.
+    pub fn begin_template_session_name(&mut self) {
+        let Some(template) = self.templates.get(self.template_choice_index) else {
+            return;
+        };
+        self.input_buffer = template.name.clone();
+        self.input_cursor = self.input_char_count();
+        self.popup_mode = Some(PopupMode::TemplateSessionName);
     }
 
-    pub fn set_error(&mut self, message: String) {
-        self.last_error = Some(message);
+    /// The picked template and the typed session name (TemplateSessionName).
+    pub fn get_template_session_info(&self) -> Option<(String, SessionTemplate)> {
+        let name = self.get_new_session_name();
+        if name.is_empty() {
+            return None;
+        }
+        self.templates
+            .get(self.template_choice_index)
+            .map(|t| (name, t.clone()))
     }
 
-    pub fn validate_selections(&mut self) {
+    pub fn open_kill_session_popup(&mut self) {
         if !self.sessions.is_empty() {
-            self.selected_session = self.selected_session.min(self.sessions.len() - 1);
-            self.multi_session = self.multi_session.min(self.sessions.len() - 1);
-
-            if let Some(session) = self.sessions.get(self.selected_session)
-                && !session.windows.is_empty()
-            {
-                self.selected_window = self.selected_window.min(session.windows.len() - 1);
-                if let Some(window) = session.windows.get(self.selected_window)
-                    && !window.panes.is_empty()
-                {
-                    self.selected_pane = self.selected_pane.min(window.panes.len() - 1);
-                }
-            }
+            self.popup_mode = Some(PopupMode::ConfirmKill);
+            self.confirm_yes_selected = false; // Default to No
+            self.kill_list_scroll = 0;
+        }
+    }
 
-            if let Some(session) = self.sessions.get(self.multi_session)
-                && !session.windows.is_empty()
-            {
-                self.multi_window = self.multi_window.min(session.windows.len() - 1);
-            }
+    /// Names of the sessions `kill_other_sessions` takes down: all but the
+    /// selected one and the one tmux-deck itself runs in.
+    pub fn other_session_names(&self) -> Vec<String> {
+        let own = self.self_pane.as_ref().filter(|_| self.tmux_server.is_none());
+        self.sessions
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != self.selected_session)
+            .filter(|(_, s)| {
+                !s.windows.iter().flat_map(|w| &w.panes).any(|p| Some(&p.id) == own)
+            })
+            .map(|(_, s)| s.name.clone())
+            .collect()
+    }
 
-            self.session_list_state.select(Some(self.selected_session));
-            self.window_list_state.select(Some(self.selected_window));
-            self.pane_list_state.select(Some(self.selected_pane));
+    /// Ask before killing every other session: with a typed phrase when
+    /// `behavior.require_typed_confirmation` is set, Yes/No otherwise. Notes
+    /// instead when there is nothing to kill.
+    pub fn open_kill_others_popup(&mut self) -> bool {
+        if self.other_session_names().is_empty() {
+            self.status_message = Some("No other sessions".to_string());
+            return false;
+        }
+        if self.behavior.require_typed_confirmation {
+            self.close_popup();
+            self.open_typed_confirm();
         } else {
-            self.session_list_state.select(None);
-            self.window_list_state.select(None);
-            self.pane_list_state.select(None);
+            self.popup_mode = Some(PopupMode::ConfirmKillOthers);
+            self.confirm_yes_selected = false;
+            self.kill_list_scroll = 0;
         }
+        true
     }
 
-    // =========================================================================
-    // TreeView Navigation
-    // =========================================================================
+    /// One line per window of the session about to be killed, with the
+    /// commands running in its panes, e.g. `1:editor  nvim, cargo`. Built from
+    /// the loaded session tree, so it costs no tmux round-trip.
+    pub fn kill_session_contents(&self) -> Vec<String> {
+        let Some(session) = self.sessions.get(self.selected_session) else {
+            return Vec::new();
+        };
+        session
+            .windows
+            .iter()
+            .map(|w| {
+                let commands: Vec<&str> =
+                    w.panes.iter().map(|p| p.current_command.as_str()).collect();
+                format!("{}:{}  {}", w.index, w.name, commands.join(", "))
+            })
+            .collect()
+    }
 
-    pub fn get_selected_pane_target(&self) -> Option<String> {
-        let session = self.sessions.get(self.selected_session)?;
-        let window = session.windows.get(self.selected_window)?;
-        let pane = window.panes.get(self.selected_pane)?;
-        Some(format!("{}:{}.{}", session.name, window.index, pane.index))
+    /// One line per pane of the window about to be killed, e.g. `1  cargo`.
+    pub fn kill_window_contents(&self) -> Vec<String> {
+        let Some(window) = self.selected_window() else {
+            return Vec::new();
+        };
+        window
+            .panes
+            .iter()
+            .map(|p| format!("{}  {}", p.index, p.current_command))
+            .collect()
     }
 
-    pub fn get_selected_pane_target_with_capture_range(&self) -> Option<(String, i32, i32)> {
-        let session = self.sessions.get(self.selected_session)?;
-        let window = session.windows.get(self.selected_window)?;
-        let pane = window.panes.get(self.selected_pane)?;
-        let target = format!("{}:{}.{}", session.name, window.index, pane.index);
-        let height = i32::try_from(pane.height).unwrap_or(i32::MAX);
-        let start = 0;
-        let end = height;
-        Some((target, start, end))
+    /// What the open kill confirmation takes down with it.
+    pub fn kill_contents(&self) -> Vec<String> {
+        match self.popup_mode {
+            Some(PopupMode::ConfirmKillWindow) => self.kill_window_contents(),
+            Some(PopupMode::ConfirmKillPane) => Vec::new(),
+            Some(PopupMode::ConfirmKillOthers) => self.other_session_names(),
+            _ => self.kill_session_contents(),
+        }
     }
 
-    pub fn tree_move_up(&mut self) {
-        match self.focus {
-            Focus::Sessions => {
-                if let Some(prev) = self.prev_cursor_stop(self.selected_session) {
-                    self.selected_session = prev;
-                    self.selected_window = 0;
-                    self.selected_pane = 0;
-                    self.window_list_state.select(Some(0));
-                    self.pane_list_state.select(Some(0));
-                }
-                self.session_list_state.select(Some(self.selected_session));
-            }
-            Focus::Windows => {
-                if self.selected_window > 0 {
-                    self.selected_window -= 1;
-                    self.selected_pane = 0;
-                    self.pane_list_state.select(Some(0));
-                }
-                self.window_list_state.select(Some(self.selected_window));
+    pub fn kill_list_scroll_up(&mut self) {
+        self.kill_list_scroll = self.kill_list_scroll.saturating_sub(1);
+    }
+
+    pub fn kill_list_scroll_down(&mut self) {
+        let max = self
+            .kill_contents()
+            .len()
+            .saturating_sub(KILL_LIST_MAX_ROWS);
+        self.kill_list_scroll = (self.kill_list_scroll + 1).min(max);
+    }
+
+    /// Open the capture-range prompt, prefilled with the active range.
+    pub fn open_capture_range_popup(&mut self) {
+        self.popup_mode = Some(PopupMode::CaptureRange);
+        self.input_buffer = self.capture_range.map(CaptureRange::label).unwrap_or_default();
+        self.input_cursor = self.input_char_count();
+        self.capture_range_preset = 0;
+    }
+
+    /// Replace the prompt's text with the next preset.
+    pub fn next_capture_range_preset(&mut self) {
+        let preset = CAPTURE_RANGE_PRESETS[self.capture_range_preset % CAPTURE_RANGE_PRESETS.len()];
+        self.capture_range_preset += 1;
+        self.input_buffer = preset.to_string();
+        self.input_cursor = self.input_char_count();
+    }
+
+    /// Set the capture range from the prompt's text. A bad range leaves the
+    /// current one in place and is returned as the error message.
+    pub fn apply_capture_range_input(&mut self) -> Result<(), String> {
+        self.capture_range = CaptureRange::parse(&self.input_buffer)?;
+        Ok(())
+    }
+
+    /// Scroll the TreeView preview right, stopping while the widest line's
+    /// last column is still in view.
+    pub fn preview_scroll_right(&mut self) {
+        self.sync_preview_scroll();
+        if self.preview.wrap {
+            return;
+        }
+        let widest = self
+            .pane_content_parsed
+            .as_ref()
+            .map_or(0, |text| text.lines.iter().map(|l| l.width()).max().unwrap_or(0));
+        self.preview_hscroll =
+            (self.preview_hscroll + PREVIEW_HSCROLL_STEP).min(widest.saturating_sub(1));
+    }
+
+    pub fn preview_scroll_left(&mut self) {
+        self.sync_preview_scroll();
+        self.preview_hscroll = self.preview_hscroll.saturating_sub(PREVIEW_HSCROLL_STEP);
+    }
+
+    /// Scroll the TreeView preview `lines` back into the capture (negative:
+    /// forward), stopping once its first line is at the top.
+    /// Without a set capture range the capture only holds history once
+    /// scrolled back, so scrolling up may go a step past it; the recapture
+    /// for the new offset fetches the lines, and [`Self::update_pane_content`]
+    /// clamps the offset to what it got.
+    pub fn preview_scroll_vertical(&mut self, lines: isize) {
+        self.sync_preview_scroll();
+        let mut max = self.preview_vscroll_max();
+        if self.capture_range.is_none() {
+            max = max.max(self.preview_vscroll.saturating_add(self.preview_rows));
+        }
+        self.preview_vscroll = self.preview_vscroll.saturating_add_signed(lines).min(max);
+    }
+
+    /// Furthest the TreeView preview can scroll back: its first line at the
+    /// top. Wrapped, a line counts for each row it takes.
+    fn preview_vscroll_max(&self) -> usize {
+        let total = match self.pane_content_parsed.as_ref() {
+            Some(text) if self.preview.wrap => preview::wrapped_height(text, self.preview_cols),
+            Some(text) => text.lines.len(),
+            None => self.pane_content.lines().count(),
+        };
+        total.saturating_sub(self.preview_rows)
+    }
+
+    /// Turn wrapping of long preview lines on or off. Wrapped, nothing is
+    /// off to the side, and the rows above may number differently.
+    pub fn toggle_preview_wrap(&mut self) {
+        self.preview.wrap = !self.preview.wrap;
+        self.preview_hscroll = 0;
+        self.preview_vscroll = self.preview_vscroll.min(self.preview_vscroll_max());
+    }
+
+    /// Half the TreeView preview's height, the step of `C-d` / `C-u`.
+    pub fn preview_half_page(&self) -> isize {
+        (self.preview_rows / 2).max(1) as isize
+    }
+
+    /// Drop the preview scroll once the selection has moved to another pane.
+    /// Called before every render, so no selection change is missed.
+    pub fn sync_preview_scroll(&mut self) {
+        let target = self.get_preview_pane_target();
+        if target != self.preview_scroll_target {
+            self.preview_hscroll = 0;
+            self.preview_vscroll = 0;
+            self.preview_scroll_target = target;
+        }
+    }
+
+    /// Esc in a popup: a prompt reached from a picker (new group, template
+    /// session name, workspace name) goes back to that picker, any other popup
+    /// closes. Returns true once no popup is open.
+    pub fn popup_back(&mut self) -> bool {
+        match self.popup_mode {
+            Some(PopupMode::NewGroup) => self.open_group_session_popup(),
+            Some(PopupMode::TemplateSessionName) => {
+                self.popup_mode = Some(PopupMode::TemplatePicker);
+                self.input_buffer.clear();
+                self.input_cursor = 0;
             }
-            Focus::Panes => {
-                if self.selected_pane > 0 {
-                    self.selected_pane -= 1;
-                }
-                self.pane_list_state.select(Some(self.selected_pane));
+            Some(PopupMode::NewWorkspace) => {
+                self.popup_mode = Some(PopupMode::WorkspacePicker);
+                self.input_buffer.clear();
+                self.input_cursor = 0;
+            }
+            Some(PopupMode::TypedConfirm) if self.typed_confirm_origin.is_some() => {
+                self.popup_mode = self.typed_confirm_origin;
+                self.input_buffer.clear();
+                self.input_cursor = 0;
             }
+            _ => self.close_popup(),
         }
+        self.popup_mode.is_none()
     }
 
-    pub fn tree_move_down(&mut self) {
-        match self.focus {
-            Focus::Sessions => {
-                if let Some(next) = self.next_cursor_stop(self.selected_session) {
-                    self.selected_session = next;
-                    self.selected_window = 0;
-                    self.selected_pane = 0;
-                    self.window_list_state.select(Some(0));
-                    self.pane_list_state.select(Some(0));
-                }
-                self.session_list_state.select(Some(self.selected_session));
+    pub fn close_popup(&mut self) {
+        if self.popup_mode == Some(PopupMode::Search) {
+            self.cancel_search();
+        }
+        if self.popup_mode == Some(PopupMode::CommandOutput) {
+            self.inspection = None;
+            self.inspect_lines.clear();
+            self.inspect_scroll = 0;
+        }
+        self.popup_mode = None;
+        self.input_buffer.clear();
+        self.input_cursor = 0;
+        self.confirm_yes_selected = false;
+        self.group_choices.clear();
+        self.group_choice_index = 0;
+        self.template_choice_index = 0;
+        self.dead_pane_index = 0;
+        self.kill_list_scroll = 0;
+        self.server_choices.clear();
+        self.server_choice_index = 0;
+        self.move_choices.clear();
+        self.move_choice_index = 0;
+        self.palette_index = 0;
+    }
+
+    /// Start inspecting the output of `command`, about to be sent to
+    /// `target`. The captures taken around the send come back through
+    /// [`Self::record_snapshot`].
+    pub fn begin_inspection(&mut self, target: &str, command: &str) {
+        self.inspection = Some(Inspection {
+            target: target.to_string(),
+            command: command.to_string(),
+            before: None,
+        });
+    }
+
+    /// Take a capture of the inspected pane: the first is the "before", each
+    /// later one (the delayed capture, or a re-capture from the popup) shows
+    /// what the command has added since in the CommandOutput popup. Returns
+    /// whether the popup was opened. When another popup or the send-keys
+    /// input is up by then, the inspection is dropped rather than cover it.
+    pub fn record_snapshot(&mut self, target: &str, content: String) -> bool {
+        let Some(inspection) = self.inspection.as_mut().filter(|i| i.target == target) else {
+            return false;
+        };
+        let Some(before) = inspection.before.as_deref() else {
+            inspection.before = Some(content);
+            return false;
+        };
+        let lines = crate::preview::appended_lines(before, &content);
+        match self.popup_mode {
+            Some(PopupMode::CommandOutput) => {
+                self.inspect_lines = lines;
+                self.inspect_scroll = self.inspect_scroll.min(self.inspect_last_line());
+                false
             }
-            Focus::Windows => {
-                if let Some(session) = self.sessions.get(self.selected_session)
-                    && self.selected_window < session.windows.len().saturating_sub(1)
-                {
-                    self.selected_window += 1;
-                    self.selected_pane = 0;
-                    self.pane_list_state.select(Some(0));
-                }
-                self.window_list_state.select(Some(self.selected_window));
+            None if self.input_mode == InputMode::Normal => {
+                self.inspect_lines = lines;
+                self.inspect_scroll = 0;
+                self.popup_mode = Some(PopupMode::CommandOutput);
+                true
             }
-            Focus::Panes => {
-                if let Some(session) = self.sessions.get(self.selected_session)
-                    && let Some(window) = session.windows.get(self.selected_window)
-                    && self.selected_pane < window.panes.len().saturating_sub(1)
-                {
-                    self.selected_pane += 1;
-                }
-                self.pane_list_state.select(Some(self.selected_pane));
+            _ => {
+                self.inspection = None;
+                false
             }
         }
     }
 
-    pub fn tree_next_focus(&mut self) {
-        self.focus = match self.focus {
-            Focus::Sessions => Focus::Windows,
-            Focus::Windows => Focus::Panes,
-            Focus::Panes => Focus::Sessions,
-        };
+    fn inspect_last_line(&self) -> usize {
+        self.inspect_lines.len().saturating_sub(1)
     }
 
-    pub fn tree_prev_focus(&mut self) {
-        self.focus = match self.focus {
-            Focus::Sessions => Focus::Panes,
-            Focus::Windows => Focus::Sessions,
-            Focus::Panes => Focus::Windows,
-        };
+    pub fn inspect_scroll_up(&mut self) {
+        self.inspect_scroll = self.inspect_scroll.saturating_sub(1);
     }
 
-    // =========================================================================
-    // MultiPreview Navigation
-    // =========================================================================
+    pub fn inspect_scroll_down(&mut self) {
+        self.inspect_scroll = (self.inspect_scroll + 1).min(self.inspect_last_line());
+    }
 
-    pub fn get_multi_selected_target(&self) -> Option<String> {
-        let session = self.sessions.get(self.multi_session)?;
-        let window = session.windows.get(self.multi_window)?;
-        // Use window-level target (tmux will switch to the active pane)
-        Some(format!("{}:{}", session.name, window.index))
+    pub fn toggle_confirm_selection(&mut self) {
+        self.confirm_yes_selected = !self.confirm_yes_selected;
     }
 
-    pub fn multi_move_left(&mut self) {
-        if self.multi_session > 0 {
-            self.multi_session -= 1;
-            // Reset window selection for new session
-            self.multi_window = 0;
+    /// Get the session name to create (for NewSession popup)
+    pub fn get_new_session_name(&self) -> String {
+        self.input_buffer.trim().to_string()
+    }
+
+    /// Get the current session name and new name (for RenameSession popup)
+    pub fn get_rename_session_info(&self) -> Option<(String, String)> {
+        let new_name = self.input_buffer.trim().to_string();
+        if new_name.is_empty() {
+            return None;
         }
+        self.sessions
+            .get(self.selected_session)
+            .map(|s| (s.name.clone(), new_name))
     }
 
-    pub fn multi_move_right(&mut self) {
-        if self.multi_session < self.sessions.len().saturating_sub(1) {
-            self.multi_session += 1;
-            // Reset window selection for new session
-            self.multi_window = 0;
+    /// Get the group name typed in the GroupSession popup. An empty/whitespace
+    /// entry means "remove from any group" and is returned as `None`.
+    pub fn get_group_session_input(&self) -> Option<String> {
+        let trimmed = self.input_buffer.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed.to_string())
         }
     }
 
-    pub fn multi_move_up(&mut self) {
-        if self.multi_window > 0 {
-            self.multi_window -= 1;
+    /// Get the session name to kill (for ConfirmKill popup)
+    pub fn get_kill_session_name(&self) -> Option<String> {
+        if self.confirm_yes_selected {
+            self.sessions
+                .get(self.selected_session)
+                .map(|s| s.name.clone())
+        } else {
+            None
         }
     }
 
-    pub fn multi_move_down(&mut self) {
-        if let Some(session) = self.sessions.get(self.multi_session)
-            && self.multi_window < session.windows.len().saturating_sub(1)
+    // =========================================================================
+    // Data Update (called when TmuxResponse is received)
+    // =========================================================================
+
+    pub fn update_sessions(&mut self, sessions: Vec<TmuxSession>) {
+        // Preserve the user's currently-highlighted session across the refresh:
+        // it may move to a new index once the new order is applied (e.g. when
+        // sort is Alphabet and a session was renamed).
+        let current_name = self
+            .sessions
+            .get(self.selected_session)
+            .map(|s| s.name.clone());
+        // Likewise the selected window, by its tmux index, so a window closed
+        // before it does not shift the selection onto a neighbour.
+        let current_window = self
+            .sessions
+            .get(self.selected_session)
+            .and_then(|s| s.windows.get(self.selected_window))
+            .map(|w| w.index);
+
+        self.sessions = sessions;
+        if let Some(scope) = &self.scoped_session {
+            self.sessions.retain(|s| &s.name == scope);
+        }
+        if self.alerts_only {
+            for session in &mut self.sessions {
+                session.windows.retain(|w| !w.alerts.is_empty());
+            }
+            self.sessions.retain(|s| !s.windows.is_empty());
+        }
+        self.apply_group_labels();
+        self.order_sessions();
+
+        if let Some(name) = current_name
+            && let Some(idx) = self.sessions.iter().position(|s| s.name == name)
+        {
+            self.selected_session = idx;
+            if let Some(window) = current_window {
+                self.reselect_window(window);
+            }
+        }
+
+        // Monitored panes that closed leave the strip.
+        let sessions = &self.sessions;
+        self.monitored.retain(|id| {
+            sessions
+                .iter()
+                .flat_map(|s| &s.windows)
+                .any(|w| w.panes.iter().any(|p| &p.id == id))
+        });
+        // So do the all-panes grid's.
+        let kept: Vec<String> = self
+            .monitor_content
+            .keys()
+            .filter(|id| self.captured_by_id(id))
+            .cloned()
+            .collect();
+        self.monitor_content.retain(|id, _| kept.contains(id));
+
+        self.validate_selections();
+        if let Some(pane_id) = self.pending_pane_select.take() {
+            self.select_pane_by_id(&pane_id);
+        }
+        if self.follow_active {
+            self.follow_active_client();
+        }
+        self.last_error = None;
+        if let Some(scope) = &self.scoped_session
+            && self.sessions.is_empty()
         {
-            self.multi_window += 1;
+            self.last_error = Some(format!("no session named '{scope}'"));
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Select the window with tmux index `window` in the selected session. If
+    /// it is gone, fall back to the window before it (else the first), so a
+    /// killed window leaves the selection where the user was working.
+    fn reselect_window(&mut self, window: u32) {
+        let Some(session) = self.sessions.get(self.selected_session) else {
+            return;
+        };
+        if let Some(pos) = session.windows.iter().position(|w| w.index == window) {
+            self.selected_window = pos;
+            return;
+        }
+        self.selected_window = session
+            .windows
+            .iter()
+            .rposition(|w| w.index < window)
+            .unwrap_or(0);
+        self.selected_pane = 0;
+    }
 
-    fn session(name: &str) -> TmuxSession {
-        TmuxSession {
-            name: name.to_string(),
-            windows: Vec::new(),
-            has_claude: false,
-            claude_state: None,
-            last_attached: 0,
-            activity: 0,
-            group: None,
+    /// Limit the deck to the session `name` (`--session`). The sessions list
+    /// goes away, so focus starts on the windows list and never returns to it.
+    pub fn scope_to_session(&mut self, name: String) {
+        self.scoped_session = Some(name);
+        self.focus = Focus::Windows;
+    }
+
+    /// Compare the refreshed `sessions` against the current snapshot and badge
+    /// the sessions that gained or lost windows or saw activity (at least
+    /// `behavior.activity_min_secs` of it). Existing badges age by one refresh
+    /// and drop after `behavior.change_badge_refreshes`. The first snapshot is
+    /// not compared, so startup does not badge everything.
+    pub fn track_session_changes(&mut self, sessions: &[TmuxSession]) {
+        self.session_changes.retain(|_, c| {
+            c.ttl -= 1;
+            c.ttl > 0
+        });
+        // A tree filtered down to its alerts has nothing to compare against.
+        if self.sessions.is_empty()
+            || self.alerts_only
+            || std::mem::take(&mut self.skip_change_tracking)
+        {
+            return;
+        }
+        for session in sessions {
+            let (windows, activity) = match self.sessions.iter().find(|s| s.name == session.name)
+            {
+                Some(old) => (
+                    session.windows.len() as i64 - old.windows.len() as i64,
+                    session.activity - old.activity >= self.behavior.activity_min_secs.max(1),
+                ),
+                None => (session.windows.len() as i64, false),
+            };
+            if windows == 0 && !activity {
+                continue;
+            }
+            let change = self
+                .session_changes
+                .entry(session.name.clone())
+                .or_insert(SessionChange {
+                    windows: 0,
+                    activity: false,
+                    ttl: 0,
+                });
+            change.windows += windows;
+            change.activity |= activity;
+            change.ttl = self.behavior.change_badge_refreshes.max(1);
         }
     }
 
-    /// Build a UIState with an in-memory (no-disk) group store and the given
-    /// assignments, then load `names` as the session list.
-    fn state_with(names: &[&str], groups: &[(&str, &str)]) -> UIState {
-        let mut state = UIState::new(Config::default());
-        state.groups = GroupStore::default();
-        for (sess, grp) in groups {
-            state.groups.set(sess, Some(grp));
+    /// Select the pane `pane_id` wherever it lives on the server, showing it
+    /// in the TreeView with the Panes list focused (its group unfolded).
+    /// Returns false if no such pane exists.
+    pub fn reveal_pane(&mut self, pane_id: &str) -> bool {
+        let found = self.sessions.iter().enumerate().find_map(|(s, session)| {
+            session.windows.iter().enumerate().find_map(|(w, window)| {
+                let p = window.panes.iter().position(|p| p.id == pane_id)?;
+                Some((s, w, p))
+            })
+        });
+        let Some((s, w, p)) = found else {
+            return false;
+        };
+        let group = self.sessions[s].group.clone();
+        self.collapsed_groups.remove(&group);
+        self.selected_session = s;
+        self.selected_window = w;
+        self.selected_pane = p;
+        self.session_list_state.select(Some(s));
+        self.window_list_state.select(Some(w));
+        self.pane_list_state.select(Some(p));
+        self.view_mode = ViewMode::TreeView;
+        self.focus = Focus::Panes;
+        true
+    }
+
+    /// Select the pane `target` names, as [`Self::reveal_pane`] does: a pane
+    /// id (`%5`), or `session:window.pane` by tmux indices (`main:1.2`), where
+    /// a missing pane or window means the active one. Returns false, leaving
+    /// the selection alone, when nothing matches.
+    pub fn select_target(&mut self, target: &str) -> bool {
+        if target.starts_with('%') {
+            return self.reveal_pane(target);
         }
-        state.update_sessions(names.iter().map(|n| session(n)).collect());
-        state
+        let target = target.strip_prefix('=').unwrap_or(target);
+        // Session names may contain colons, so the window is after the last.
+        let (name, rest) = target.rsplit_once(':').unwrap_or((target, ""));
+        let (window, pane) = rest.split_once('.').unwrap_or((rest, ""));
+        let Some(session) = self.sessions.iter().find(|s| s.name == name) else {
+            return false;
+        };
+        let window = if window.is_empty() {
+            session.windows.iter().find(|w| w.active).or(session.windows.first())
+        } else {
+            let Ok(index) = window.parse::<u32>() else {
+                return false;
+            };
+            session.windows.iter().find(|w| w.index == index)
+        };
+        let Some(window) = window else {
+            return false;
+        };
+        let pane = if pane.is_empty() {
+            window.get_active_pane()
+        } else {
+            let Ok(index) = pane.parse::<u32>() else {
+                return false;
+            };
+            window.panes.iter().find(|p| p.index == index)
+        };
+        match pane.map(|p| p.id.clone()) {
+            Some(id) => self.reveal_pane(&id),
+            None => false,
+        }
+    }
+
+    /// Select the session and window (and pane, if given) of a saved
+    /// `=session:window[.pane]` target. Whatever no longer exists falls back
+    /// to the first entry.
+    pub fn restore_selection(&mut self, target: &str) {
+        let target = target.strip_prefix('=').unwrap_or(target);
+        // Session names may contain colons, so the window is after the last.
+        let (name, rest) = target.rsplit_once(':').unwrap_or((target, ""));
+        let (window, pane) = rest.split_once('.').unwrap_or((rest, ""));
+        let Some(s) = self.sessions.iter().position(|s| s.name == name) else {
+            self.selected_session = 0;
+            self.selected_window = 0;
+            self.selected_pane = 0;
+            self.validate_selections();
+            return;
+        };
+        let session = &self.sessions[s];
+        let w = window
+            .parse::<u32>()
+            .ok()
+            .and_then(|i| session.windows.iter().position(|w| w.index == i))
+            .unwrap_or(0);
+        let p = pane
+            .parse::<u32>()
+            .ok()
+            .and_then(|i| session.windows.get(w)?.panes.iter().position(|p| p.index == i))
+            .unwrap_or(0);
+        let group = session.group.clone();
+        self.collapsed_groups.remove(&group);
+        self.selected_session = s;
+        self.selected_window = w;
+        self.selected_pane = p;
+        self.multi_session = s;
+        self.multi_window = w;
+        self.validate_selections();
+    }
+
+    /// Open the go-to-pane prompt, prefilled with the `%` every id starts with.
+    pub fn open_goto_pane_popup(&mut self) {
+        self.popup_mode = Some(PopupMode::GotoPane);
+        self.input_buffer = "%".to_string();
+        self.input_cursor = 1;
+    }
+
+    /// Reveal the pane whose id the prompt holds (the leading `%` may be
+    /// left off). An unknown id is returned as the error message.
+    pub fn goto_pane_input(&mut self) -> Result<(), String> {
+        let id = self.input_buffer.trim();
+        let id = format!("%{}", id.strip_prefix('%').unwrap_or(id));
+        if self.reveal_pane(&id) {
+            Ok(())
+        } else {
+            Err(format!("no pane {id}"))
+        }
+    }
+
+    /// Start a TreeView search, remembering the selection to go back to.
+    pub fn open_search(&mut self) {
+        self.search_origin =
+            Some((self.selected_session, self.selected_window, self.selected_pane, self.focus));
+        self.search_query.clear();
+        self.popup_mode = Some(PopupMode::Search);
+        self.input_buffer.clear();
+        self.input_cursor = 0;
+    }
+
+    /// Match against what the search prompt holds now.
+    pub fn update_search(&mut self) {
+        self.search_query = self.input_buffer.trim().to_string();
+    }
+
+    pub fn search_matches_pane(&self, pane: &TmuxPane) -> bool {
+        fuzzy_match(&self.search_query, &pane.current_command)
+    }
+
+    /// A window matches by name, or through one of its panes.
+    pub fn search_matches_window(&self, window: &TmuxWindow) -> bool {
+        fuzzy_match(&self.search_query, &window.name)
+            || window.panes.iter().any(|p| self.search_matches_pane(p))
+    }
+
+    /// A session matches by name, or through one of its windows.
+    pub fn search_matches_session(&self, session: &TmuxSession) -> bool {
+        fuzzy_match(&self.search_query, &session.name)
+            || session.windows.iter().any(|w| self.search_matches_window(w))
+    }
+
+    /// Sessions the search matches, for the search bar's count.
+    pub fn search_match_count(&self) -> usize {
+        self.sessions.iter().filter(|s| self.search_matches_session(s)).count()
+    }
+
+    /// End the search on its first match, in list order, focusing the list
+    /// the match was made in. Without a match the search is cancelled and
+    /// the query returned as the error message.
+    pub fn accept_search(&mut self) -> Result<(), String> {
+        let query = self.search_query.clone();
+        let found = self.sessions.iter().enumerate().find_map(|(s, session)| {
+            if fuzzy_match(&query, &session.name) {
+                return Some((s, 0, 0, Focus::Sessions));
+            }
+            session.windows.iter().enumerate().find_map(|(w, window)| {
+                if fuzzy_match(&query, &window.name) {
+                    return Some((s, w, 0, Focus::Windows));
+                }
+                let p = window.panes.iter().position(|p| self.search_matches_pane(p))?;
+                Some((s, w, p, Focus::Panes))
+            })
+        });
+        let Some((s, w, p, focus)) = found.filter(|_| !query.is_empty()) else {
+            self.close_popup();
+            return if query.is_empty() { Ok(()) } else { Err(format!("no match for '{query}'")) };
+        };
+        self.search_origin = None;
+        self.close_popup();
+        let group = self.sessions[s].group.clone();
+        self.collapsed_groups.remove(&group);
+        self.selected_session = s;
+        self.selected_window = w;
+        self.selected_pane = p;
+        self.focus = focus;
+        self.validate_selections();
+        Ok(())
+    }
+
+    /// Drop the query and go back to the selection the search started from.
+    fn cancel_search(&mut self) {
+        self.search_query.clear();
+        if let Some((s, w, p, focus)) = self.search_origin.take() {
+            self.selected_session = s;
+            self.selected_window = w;
+            self.selected_pane = p;
+            self.focus = focus;
+            self.validate_selections();
+        }
+    }
+
+    /// Move the pane selection onto `pane_id` if it lives in the selected
+    /// window. Returns false (leaving the selection alone) otherwise.
+    pub fn select_pane_by_id(&mut self, pane_id: &str) -> bool {
+        let Some(idx) = self
+            .sessions
+            .get(self.selected_session)
+            .and_then(|s| s.windows.get(self.selected_window))
+            .and_then(|w| w.panes.iter().position(|p| p.id == pane_id))
+        else {
+            return false;
+        };
+        self.selected_pane = idx;
+        self.pane_list_state.select(Some(idx));
+        true
+    }
+
+    /// Stamp each session with its persisted group label. Called whenever fresh
+    /// session data arrives from tmux, since the tmux layer is group-agnostic.
+    fn apply_group_labels(&mut self) {
+        for session in &mut self.sessions {
+            session.group = self.groups.group_of(&session.name);
+        }
+    }
+
+    /// Order the session list: first by the active [`SessionSort`], then cluster
+    /// sessions of the same group together. Because the clustering pass is a
+    /// *stable* sort keyed only on the group, sessions keep their sort order
+    /// within each group, and ungrouped sessions fall to the bottom.
+    fn order_sessions(&mut self) {
+        self.session_sort.apply(&mut self.sessions);
+        for session in &mut self.sessions {
+            session.windows.sort_by(|a, b| self.window_sort.cmp(a, b));
+        }
+        self.sessions.sort_by(|a, b| match (&a.group, &b.group) {
+            (Some(x), Some(y)) => x.to_lowercase().cmp(&y.to_lowercase()),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+    }
+
+    /// Assign the currently-selected session to `group` (or remove it from any
+    /// group when `group` is `None`/empty), persist the change, and re-order the
+    /// list in place keeping that session highlighted. No tmux round-trip is
+    /// needed — grouping is entirely tmux-deck-side.
+    pub fn assign_selected_group(&mut self, group: Option<String>) {
+        let Some(session) = self.sessions.get(self.selected_session) else {
+            return;
+        };
+        let name = session.name.clone();
+        self.groups.set(&name, group.as_deref());
+        // Reveal the destination group so the user sees the session land, even
+        // if that group was folded.
+        self.collapsed_groups.remove(&group);
+        self.apply_group_labels();
+        self.resort_sessions_preserve_selection();
+    }
+
+    /// Whether any session carries a group label. When false there are no
+    /// headers and folding is a no-op (there is nothing to organise yet).
+    fn any_grouped(&self) -> bool {
+        self.sessions.iter().any(|s| s.group.is_some())
+    }
+
+    /// Whether `group` is currently folded. Folding only takes effect once
+    /// real groups exist, so a stray collapsed entry never hides a flat list.
+    fn is_collapsed(&self, group: &Option<String>) -> bool {
+        self.any_grouped() && self.collapsed_groups.contains(group)
+    }
+
+    /// Whether the session at `index` is the first of its group in the current
+    /// ordering — the row a folded group collapses onto.
+    fn is_group_head(&self, index: usize) -> bool {
+        match self.sessions.get(index) {
+            None => false,
+            Some(s) => index == 0 || self.sessions[index - 1].group != s.group,
+        }
+    }
+
+    /// Whether the cursor may rest on the session at `index`. A session is a
+    /// stop when it is visible, or when it is the head of a folded group — in
+    /// which case the cursor visually sits on that group's (collapsed) header,
+    /// so the group can be re-expanded with `za`.
+    fn is_cursor_stop(&self, index: usize) -> bool {
+        match self.sessions.get(index) {
+            None => false,
+            Some(s) => !self.is_collapsed(&s.group) || self.is_group_head(index),
+        }
+    }
+
+    /// Whether the selection currently sits on a folded group's header rather
+    /// than a visible session. Used by the renderer to highlight the header.
+    pub fn selection_on_folded_header(&self) -> bool {
+        self.sessions
+            .get(self.selected_session)
+            .map(|s| self.is_collapsed(&s.group))
+            .unwrap_or(false)
+    }
+
+    /// Toggle the fold state of the group containing the selected session.
+    /// When folding, the selection collapses onto the group's head so the
+    /// cursor stays on the (now folded) header and the group can be reopened
+    /// with another `za`.
+    pub fn toggle_fold_current_group(&mut self) {
+        if !self.any_grouped() {
+            return;
+        }
+        let Some(session) = self.sessions.get(self.selected_session) else {
+            return;
+        };
+        let group = session.group.clone();
+        if self.collapsed_groups.contains(&group) {
+            self.collapsed_groups.remove(&group);
+        } else {
+            self.collapsed_groups.insert(group.clone());
+            // Park the selection on the group's head so it remains a valid
+            // cursor stop (the folded header) instead of a hidden member.
+            if let Some(head) = self.sessions.iter().position(|s| s.group == group) {
+                self.selected_session = head;
+                self.selected_window = 0;
+                self.selected_pane = 0;
+                self.window_list_state.select(Some(0));
+                self.pane_list_state.select(Some(0));
+            }
+        }
+    }
+
+    fn next_cursor_stop(&self, from: usize) -> Option<usize> {
+        ((from + 1)..self.sessions.len()).find(|&i| self.is_cursor_stop(i))
+    }
+
+    fn prev_cursor_stop(&self, from: usize) -> Option<usize> {
+        (0..from).rev().find(|&i| self.is_cursor_stop(i))
+    }
+
+    /// Build the rendered Sessions rows, inserting group headers and dropping
+    /// the members of folded groups. When no session is grouped the result is a
+    /// flat list of [`SessionRow::Session`] rows (no headers), matching the
+    /// pre-grouping behaviour exactly.
+    pub fn session_rows(&self) -> Vec<SessionRow> {
+        let any_grouped = self.any_grouped();
+        let mut rows = Vec::with_capacity(self.sessions.len());
+        let mut current: Option<&Option<String>> = None;
+        for (index, session) in self.sessions.iter().enumerate() {
+            let collapsed = any_grouped && self.collapsed_groups.contains(&session.group);
+            if any_grouped && current != Some(&session.group) {
+                let count = self
+                    .sessions
+                    .iter()
+                    .filter(|s| s.group == session.group)
+                    .count();
+                rows.push(SessionRow::Header {
+                    group: session.group.clone(),
+                    count,
+                    collapsed,
+                });
+                current = Some(&session.group);
+            }
+            if !collapsed {
+                rows.push(SessionRow::Session { index });
+            }
+        }
+        rows
+    }
+
+    /// Advance to the next [`SessionSort`] and re-sort the list in place,
+    /// keeping the currently-highlighted session highlighted.
+    pub fn cycle_session_sort(&mut self) {
+        self.session_sort = self.session_sort.next();
+        self.resort_sessions_preserve_selection();
+    }
+
+    /// Cycle the Windows list's order, keeping the selected windows (tree
+    /// and MultiPreview) selected.
+    pub fn cycle_window_sort(&mut self) {
+        let index_of = |s: usize, w: usize| {
+            self.sessions.get(s).and_then(|s| s.windows.get(w)).map(|w| w.index)
+        };
+        let tree = index_of(self.selected_session, self.selected_window);
+        let multi = index_of(self.multi_session, self.multi_window);
+        self.window_sort = self.window_sort.next();
+        self.order_sessions();
+        let position = |s: usize, index: Option<u32>| {
+            let windows = &self.sessions.get(s)?.windows;
+            windows.iter().position(|w| Some(w.index) == index)
+        };
+        if let Some(w) = position(self.selected_session, tree) {
+            self.selected_window = w;
+            self.window_list_state.select(Some(w));
+        }
+        if let Some(w) = position(self.multi_session, multi) {
+            self.multi_window = w;
+        }
+    }
+
+    fn resort_sessions_preserve_selection(&mut self) {
+        let current_name = self
+            .sessions
+            .get(self.selected_session)
+            .map(|s| s.name.clone());
+
+        self.order_sessions();
+
+        if let Some(name) = current_name
+            && let Some(idx) = self.sessions.iter().position(|s| s.name == name)
+        {
+            self.selected_session = idx;
+            self.multi_session = self.multi_session.min(self.sessions.len().saturating_sub(1));
+            self.session_list_state.select(Some(idx));
+        }
+    }
+
+    /// Store a fresh capture and parse it once for every frame that draws it.
+    /// Most ticks re-capture a pane whose output has not moved, so an
+    /// unchanged capture keeps the text already parsed. `printable` is the
+    /// capture's [`preview::printable_ratio`]. Returns whether the preview
+    /// changed, i.e. whether it needs a redraw.
+    pub fn update_pane_content(&mut self, content: String, printable: f64) -> bool {
+        if !content.is_empty()
+            && content == self.pane_content
+            && self.pane_content_parsed.is_some()
+        {
+            return false;
+        }
+        if printable < self.preview.binary_threshold {
+            self.pane_content_parsed = Some(Text::raw(BINARY_PREVIEW_PLACEHOLDER));
+        } else {
+            // Unparsable escapes fall back to the stripped text, computed
+            // here rather than on every draw.
+            self.pane_content_parsed = preview::to_styled(&content)
+                .map(|text| preview::reduce_colors(text, self.color_depth))
+                .or_else(|| Some(Text::from(preview::to_plain_text(&content))));
+        }
+        self.pane_content = content;
+        self.preview_vscroll = self.preview_vscroll.min(self.preview_vscroll_max());
+        true
+    }
+
+    /// The TreeView preview's capture as plain text, without trailing blank
+    /// lines, for `yank`. `None` when there is nothing to copy.
+    pub fn preview_plain_text(&self) -> Option<String> {
+        let text = preview::to_plain_text(&self.pane_content);
+        let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+        let end = lines.iter().rposition(|l| !l.is_empty())?;
+        Some(lines[..=end].join("\n"))
+    }
+
+    /// Record that tmux-deck runs inside tmux, in pane `self_pane`, and queue
+    /// the one-time note explaining what switching will do.
+    pub fn note_nested_tmux(&mut self, self_pane: Option<String>) {
+        self.inside_tmux = true;
+        self.self_pane = self_pane;
+        self.status_message = Some(NESTED_TMUX_NOTE.to_string());
+    }
+
+    /// Whether the TreeView preview shows the pane tmux-deck itself runs in.
+    /// Pane ids are per server, so only the startup server can hold it.
+    pub fn preview_pane_is_self(&self) -> bool {
+        self.self_pane.is_some()
+            && self.tmux_server.is_none()
+            && self.preview_pane().map(|(_, _, p)| &p.id) == self.self_pane.as_ref()
+    }
+
+    /// Show a fixed placeholder in the preview instead of a capture.
+    pub fn set_preview_placeholder(&mut self, text: &'static str) {
+        self.pane_content.clear();
+        self.pane_content_parsed = Some(Text::raw(text));
+    }
+
+    pub fn set_error(&mut self, message: String) {
+        self.last_error = Some(message);
+    }
+
+    /// Remove the pane `pane_id` from the tree ahead of the next refresh
+    /// (e.g. once tmux confirmed killing it), along with a window or session
+    /// it leaves empty, as tmux closes those too. The selection stays on the
+    /// same pane where it can, and is clamped to what is left otherwise.
+    pub fn forget_pane(&mut self, pane_id: &str) {
+        let Some((s, w, p)) = self.sessions.iter().enumerate().find_map(|(s, session)| {
+            session.windows.iter().enumerate().find_map(|(w, window)| {
+                let p = window.panes.iter().position(|p| p.id == pane_id)?;
+                Some((s, w, p))
+            })
+        }) else {
+            return;
+        };
+        let in_selected_window = s == self.selected_session && w == self.selected_window;
+        let session = &mut self.sessions[s];
+        session.windows[w].panes.remove(p);
+        if in_selected_window && p < self.selected_pane {
+            self.selected_pane -= 1;
+        }
+        if session.windows[w].panes.is_empty() {
+            session.windows.remove(w);
+            if s == self.selected_session && w < self.selected_window {
+                self.selected_window -= 1;
+            }
+            if session.windows.is_empty() {
+                self.sessions.remove(s);
+                if s < self.selected_session {
+                    self.selected_session -= 1;
+                }
+            }
+        }
+        self.validate_selections();
+    }
+
+    pub fn validate_selections(&mut self) {
+        if !self.sessions.is_empty() {
+            self.selected_session = self.selected_session.min(self.sessions.len() - 1);
+            self.multi_session = self.multi_session.min(self.sessions.len() - 1);
+
+            if let Some(session) = self.sessions.get(self.selected_session)
+                && !session.windows.is_empty()
+            {
+                self.selected_window = self.selected_window.min(session.windows.len() - 1);
+                if let Some(window) = session.windows.get(self.selected_window)
+                    && !window.panes.is_empty()
+                {
+                    self.selected_pane = self.selected_pane.min(window.panes.len() - 1);
+                }
+            }
+
+            self.clamp_multi_to_visible();
+            if let Some(session) = self.sessions.get(self.multi_session)
+                && !session.windows.is_empty()
+            {
+                self.multi_window = self.multi_window.min(session.windows.len() - 1);
+                if let Some(window) = session.windows.get(self.multi_window)
+                    && !window.panes.is_empty()
+                {
+                    self.multi_pane = self.multi_pane.min(window.panes.len() - 1);
+                }
+            }
+
+            self.session_list_state.select(Some(self.selected_session));
+            self.window_list_state.select(Some(self.selected_window));
+            self.pane_list_state.select(Some(self.selected_pane));
+        } else {
+            self.session_list_state.select(None);
+            self.window_list_state.select(None);
+            self.pane_list_state.select(None);
+        }
+    }
+
+    // =========================================================================
+    // TreeView Navigation
+    // =========================================================================
+
+    pub fn get_selected_pane_target(&self) -> Option<String> {
+        let session = self.sessions.get(self.selected_session)?;
+        let window = session.windows.get(self.selected_window)?;
+        let pane = window.panes.get(self.selected_pane)?;
+        Some(format!("{}:{}.{}", session.target(), window.index, pane.index))
+    }
+
+    /// Id (`%N`) of the selected pane in TreeView.
+    pub fn selected_pane_id(&self) -> Option<String> {
+        let session = self.sessions.get(self.selected_session)?;
+        let window = session.windows.get(self.selected_window)?;
+        window.panes.get(self.selected_pane).map(|p| p.id.clone())
+    }
+
+    /// `session:window` target of the window the current view has selected,
+    /// for window-scoped operations (layout, rotate, …).
+    pub fn get_current_window_target(&self) -> Option<String> {
+        match self.view_mode {
+            ViewMode::TreeView => {
+                let session = self.sessions.get(self.selected_session)?;
+                let window = session.windows.get(self.selected_window)?;
+                Some(format!("{}:{}", session.target(), window.index))
+            }
+            ViewMode::MultiPreview | ViewMode::AllPanes => self.get_multi_selected_target(),
+            ViewMode::Dashboard => None,
+        }
+    }
+
+    /// The window the current view has selected, as for
+    /// [`Self::get_current_window_target`].
+    pub fn current_window(&self) -> Option<&TmuxWindow> {
+        let (session, window) = match self.view_mode {
+            ViewMode::TreeView => (self.selected_session, self.selected_window),
+            ViewMode::MultiPreview | ViewMode::AllPanes => (self.multi_session, self.multi_window),
+            ViewMode::Dashboard => return None,
+        };
+        self.sessions.get(session)?.windows.get(window)
+    }
+
+    /// Whether the TreeView preview follows the selected window's active pane
+    /// rather than the selected pane (`behavior.window_preview_active_pane`
+    /// while browsing windows).
+    pub fn preview_follows_active_pane(&self) -> bool {
+        self.behavior.window_preview_active_pane && self.focus == Focus::Windows
+    }
+
+    /// The pane the TreeView preview shows, with its session and window. A
+    /// zoomed MultiPreview shows the selected window's active pane instead.
+    fn preview_pane(&self) -> Option<(&TmuxSession, &TmuxWindow, &TmuxPane)> {
+        if self.view_mode == ViewMode::AllPanes {
+            return None;
+        }
+        if self.view_mode == ViewMode::MultiPreview {
+            if !self.zoomed || !self.multi_is_visible(self.multi_session) {
+                return None;
+            }
+            let session = self.sessions.get(self.multi_session)?;
+            let window = session.windows.get(self.multi_window)?;
+            return Some((session, window, window.get_active_pane()?));
+        }
+        let session = self.sessions.get(self.selected_session)?;
+        let window = session.windows.get(self.selected_window)?;
+        let pane = if self.preview_follows_active_pane() {
+            window.get_active_pane()?
+        } else {
+            window.panes.get(self.selected_pane)?
+        };
+        Some((session, window, pane))
+    }
+
+    /// Target of the pane the TreeView preview shows.
+    pub fn get_preview_pane_target(&self) -> Option<String> {
+        let (session, window, pane) = self.preview_pane()?;
+        Some(format!("{}:{}.{}", session.target(), window.index, pane.index))
+    }
+
+    pub fn get_selected_pane_target_with_capture_range(&self) -> Option<(String, i32, i32)> {
+        let (session, window, pane) = self.preview_pane()?;
+        let target = format!("{}:{}.{}", session.target(), window.index, pane.index);
+        let height = i32::try_from(pane.height).unwrap_or(i32::MAX);
+        // Scrolled back without a set range, the capture reaches a preview
+        // height past the scroll into history, so there is more to scroll to.
+        // It always reaches back `preview.history_lines`.
+        let scrolled = match self.preview_vscroll {
+            0 => 0,
+            up => up.saturating_add(self.preview_rows),
+        };
+        let history = scrolled.max(self.preview.history_lines.min(MAX_HISTORY_LINES));
+        let (start, end) = match self.capture_range {
+            Some(range) => range.lines(height),
+            None => (i32::try_from(history).map_or(i32::MIN, |h| -h), height),
+        };
+        Some((target, start, end))
+    }
+
+    /// Capture requests for every preview currently on screen. The TreeView
+    /// and a zoomed MultiPreview show one pane, which caps a tick at a single
+    /// capture whatever the server size; the all-panes grid asks for each of
+    /// its tiles. The deck's own pane is never captured.
+    pub fn visible_capture_targets(&self) -> Vec<(String, i32, i32)> {
+        match self.view_mode {
+            ViewMode::TreeView | ViewMode::MultiPreview if !self.preview_pane_is_self() => self
+                .get_selected_pane_target_with_capture_range()
+                .into_iter()
+                .collect(),
+            ViewMode::AllPanes => self.all_panes_capture_targets(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The pane with id `pane_id`, with its session and window.
+    pub fn find_pane(&self, pane_id: &str) -> Option<(&TmuxSession, &TmuxWindow, &TmuxPane)> {
+        self.sessions.iter().find_map(|s| {
+            s.windows.iter().find_map(|w| {
+                let p = w.panes.iter().find(|p| p.id == pane_id)?;
+                Some((s, w, p))
+            })
+        })
+    }
+
+    /// Pin the selected pane to the monitor strip, or unpin it.
+    pub fn toggle_monitor_selected(&mut self) {
+        let Some(id) = self.selected_pane_id() else {
+            return;
+        };
+        if self.tmux_server.is_none() && self.self_pane.as_ref() == Some(&id) {
+            self.status_message = Some("tmux-deck's own pane cannot be monitored".to_string());
+            return;
+        }
+        if let Some(pos) = self.monitored.iter().position(|m| *m == id) {
+            self.monitored.remove(pos);
+            self.monitor_content.remove(&id);
+            self.status_message = Some(format!("Stopped monitoring {id}"));
+        } else {
+            self.status_message = Some(format!("Monitoring {id}"));
+            self.monitored.push(id);
+        }
+    }
+
+    /// Capture requests for the monitor strip: each pane's visible screen,
+    /// targeted by id so a reply cannot be mistaken for the TreeView's.
+    pub fn monitor_capture_targets(&self) -> Vec<(String, i32, i32)> {
+        self.monitored
+            .iter()
+            .filter_map(|id| {
+                let (_, _, pane) = self.find_pane(id)?;
+                Some((id.clone(), 0, i32::try_from(pane.height).unwrap_or(i32::MAX)))
+            })
+            .collect()
+    }
+
+    /// Whether captures of pane `id` are kept by id: it is monitored, or a
+    /// tile of the all-panes grid on screen.
+    fn captured_by_id(&self, id: &str) -> bool {
+        self.monitored.iter().any(|m| m == id)
+            || (self.view_mode == ViewMode::AllPanes && self.find_pane(id).is_some())
+    }
+
+    /// Store a capture of `target` if it is a monitored pane or an all-panes
+    /// tile, returning whether its strip or tile changed. `None` (storing
+    /// nothing) for any other target. Binary output is replaced as in
+    /// [`Self::update_pane_content`].
+    pub fn update_monitor_content(
+        &mut self,
+        target: &str,
+        content: &str,
+        printable: f64,
+    ) -> Option<bool> {
+        if !self.captured_by_id(target) {
+            return None;
+        }
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.monitor_content.contains_key(target)
+            && self.monitor_hashes.get(target) == Some(&hash)
+        {
+            return Some(false);
+        }
+        self.monitor_hashes.insert(target.to_string(), hash);
+        if printable < self.preview.binary_threshold {
+            let text = Text::raw(BINARY_PREVIEW_PLACEHOLDER);
+            self.monitor_content.insert(target.to_string(), text);
+            return Some(true);
+        }
+        let mut text = preview::to_styled(content)
+            .map(|text| preview::reduce_colors(text, self.color_depth))
+            .unwrap_or_else(|| Text::from(preview::to_plain_text(content)));
+        // The strip shows the bottom rows; a prompt near the top of an
+        // otherwise empty screen should not scroll out of it.
+        while text.lines.last().is_some_and(|l| l.width() == 0) {
+            text.lines.pop();
+        }
+        self.monitor_content.insert(target.to_string(), text);
+        Some(true)
+    }
+
+    pub fn tree_move_up(&mut self) {
+        match self.focus {
+            Focus::Sessions => {
+                if let Some(prev) = self.prev_cursor_stop(self.selected_session) {
+                    self.selected_session = prev;
+                    self.selected_window = 0;
+                    self.selected_pane = 0;
+                    self.window_list_state.select(Some(0));
+                    self.pane_list_state.select(Some(0));
+                }
+                self.session_list_state.select(Some(self.selected_session));
+            }
+            Focus::Windows => {
+                if self.selected_window > 0 {
+                    self.selected_window -= 1;
+                    self.selected_pane = 0;
+                    self.pane_list_state.select(Some(0));
+                }
+                self.window_list_state.select(Some(self.selected_window));
+            }
+            Focus::Panes => {
+                if self.selected_pane > 0 {
+                    self.selected_pane -= 1;
+                }
+                self.pane_list_state.select(Some(self.selected_pane));
+            }
+        }
+    }
+
+    pub fn tree_move_down(&mut self) {
+        match self.focus {
+            Focus::Sessions => {
+                if let Some(next) = self.next_cursor_stop(self.selected_session) {
+                    self.selected_session = next;
+                    self.selected_window = 0;
+                    self.selected_pane = 0;
+                    self.window_list_state.select(Some(0));
+                    self.pane_list_state.select(Some(0));
+                }
+                self.session_list_state.select(Some(self.selected_session));
+            }
+            Focus::Windows => {
+                if let Some(session) = self.sessions.get(self.selected_session)
+                    && self.selected_window < session.windows.len().saturating_sub(1)
+                {
+                    self.selected_window += 1;
+                    self.selected_pane = 0;
+                    self.pane_list_state.select(Some(0));
+                }
+                self.window_list_state.select(Some(self.selected_window));
+            }
+            Focus::Panes => {
+                if let Some(session) = self.sessions.get(self.selected_session)
+                    && let Some(window) = session.windows.get(self.selected_window)
+                    && self.selected_pane < window.panes.len().saturating_sub(1)
+                {
+                    self.selected_pane += 1;
+                }
+                self.pane_list_state.select(Some(self.selected_pane));
+            }
+        }
+    }
+
+    /// Select the `n`th (from 0) session the view shows: a row of the
+    /// Sessions list, or a MultiPreview column. Past the last one, nothing
+    /// happens.
+    pub fn jump_to_session(&mut self, n: usize) {
+        match self.view_mode {
+            ViewMode::TreeView => {
+                let Some(s) = (0..self.sessions.len()).filter(|&i| self.is_cursor_stop(i)).nth(n)
+                else {
+                    return;
+                };
+                self.selected_session = s;
+                self.selected_window = 0;
+                self.selected_pane = 0;
+                self.session_list_state.select(Some(s));
+                self.window_list_state.select(Some(0));
+                self.pane_list_state.select(Some(0));
+            }
+            ViewMode::MultiPreview | ViewMode::AllPanes => {
+                if let Some(&s) = self.multi_visible_sessions().get(n) {
+                    self.multi_session = s;
+                    self.multi_window = self.multi_top_window(s);
+                    self.multi_pane = 0;
+                }
+            }
+            ViewMode::Dashboard => {}
+        }
+    }
+
+    /// Select the first row of the focused list.
+    pub fn tree_move_top(&mut self) {
+        match self.focus {
+            Focus::Sessions => {
+                if self.is_cursor_stop(0) {
+                    self.selected_session = 0;
+                } else if let Some(first) = self.next_cursor_stop(0) {
+                    self.selected_session = first;
+                }
+                self.selected_window = 0;
+                self.selected_pane = 0;
+                self.session_list_state.select(Some(self.selected_session));
+                self.window_list_state.select(Some(0));
+                self.pane_list_state.select(Some(0));
+            }
+            Focus::Windows => {
+                self.selected_window = 0;
+                self.selected_pane = 0;
+                self.window_list_state.select(Some(0));
+                self.pane_list_state.select(Some(0));
+            }
+            Focus::Panes => {
+                self.selected_pane = 0;
+                self.pane_list_state.select(Some(0));
+            }
+        }
+    }
+
+    /// Select the last row of the focused list.
+    pub fn tree_move_bottom(&mut self) {
+        match self.focus {
+            Focus::Sessions => {
+                if let Some(last) = self.prev_cursor_stop(self.sessions.len()) {
+                    self.selected_session = last;
+                    self.selected_window = 0;
+                    self.selected_pane = 0;
+                    self.window_list_state.select(Some(0));
+                    self.pane_list_state.select(Some(0));
+                }
+                self.session_list_state.select(Some(self.selected_session));
+            }
+            Focus::Windows => {
+                let count = self.sessions.get(self.selected_session).map_or(0, |s| s.windows.len());
+                self.selected_window = count.saturating_sub(1);
+                self.selected_pane = 0;
+                self.window_list_state.select(Some(self.selected_window));
+                self.pane_list_state.select(Some(0));
+            }
+            Focus::Panes => {
+                let count = self
+                    .sessions
+                    .get(self.selected_session)
+                    .and_then(|s| s.windows.get(self.selected_window))
+                    .map_or(0, |w| w.panes.len());
+                self.selected_pane = count.saturating_sub(1);
+                self.pane_list_state.select(Some(self.selected_pane));
+            }
+        }
+    }
+
+    /// Focus the list one level up (Panes → Windows → Sessions), keeping the
+    /// selection, so the neighbours of the selected item are in view.
+    pub fn tree_focus_parent(&mut self) {
+        self.focus = match self.focus {
+            Focus::Panes => Focus::Windows,
+            Focus::Windows if self.scoped_session.is_some() => Focus::Windows,
+            Focus::Windows | Focus::Sessions => Focus::Sessions,
+        };
+    }
+
+    pub fn tree_next_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Sessions => Focus::Windows,
+            Focus::Windows => Focus::Panes,
+            Focus::Panes if self.scoped_session.is_some() => Focus::Windows,
+            Focus::Panes => Focus::Sessions,
+        };
+    }
+
+    pub fn tree_prev_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Sessions => Focus::Panes,
+            Focus::Windows if self.scoped_session.is_some() => Focus::Panes,
+            Focus::Windows => Focus::Sessions,
+            Focus::Panes => Focus::Windows,
+        };
+    }
+
+    // =========================================================================
+    // MultiPreview Navigation
+    // =========================================================================
+
+    /// Zoom the MultiPreview in on the selected window, or back out to the
+    /// grid.
+    pub fn toggle_zoom(&mut self) {
+        if self.zoomed {
+            self.zoomed = false;
+        } else if self.get_multi_selected_target().is_some() {
+            self.zoomed = true;
+            // Drop the last capture of some other pane until this one's lands.
+            self.pane_content.clear();
+            self.pane_content_parsed = None;
+        }
+    }
+
+    pub fn get_multi_selected_target(&self) -> Option<String> {
+        if !self.multi_is_visible(self.multi_session) {
+            return None;
+        }
+        let session = self.sessions.get(self.multi_session)?;
+        let window = session.windows.get(self.multi_window)?;
+        // Use window-level target (tmux will switch to the active pane)
+        Some(format!("{}:{}", session.target(), window.index))
+    }
+
+    /// Turn following the active tmux client on or off. Turning it on moves
+    /// the selection there straight away.
+    pub fn toggle_follow(&mut self) {
+        self.follow_active = !self.follow_active;
+        if self.follow_active {
+            self.follow_active_client();
+            self.status_message = Some("Following the active tmux client".to_string());
+        } else {
+            self.status_message = Some("Follow off".to_string());
+        }
+    }
+
+    /// Filter the tree down to the windows with a pending alert, or show
+    /// every window again. Turning it on filters what is listed now; the
+    /// windows it hid only come back with the next refresh.
+    pub fn toggle_alerts_only(&mut self) {
+        self.alerts_only = !self.alerts_only;
+        if self.alerts_only {
+            self.update_sessions(self.sessions.clone());
+            self.status_message = Some(if self.sessions.is_empty() {
+                "No window has a pending alert".to_string()
+            } else {
+                "Showing only windows with alerts".to_string()
+            });
+        } else {
+            // Against the filtered tree, every window would look new.
+            self.skip_change_tracking = true;
+            self.status_message = Some("Showing all windows".to_string());
+        }
+    }
+
+    /// Manual navigation takes the selection back from follow mode.
+    pub fn suspend_follow(&mut self) {
+        if self.follow_active {
+            self.follow_active = false;
+            self.status_message = Some("Follow paused".to_string());
+        }
+    }
+
+    /// Select the window the most recently active tmux client shows: the
+    /// attached session with the latest client input, its current window and
+    /// that window's active pane. The session holding the deck's own pane is
+    /// passed over, as typing into the deck would otherwise always win.
+    fn follow_active_client(&mut self) {
+        let own = self
+            .self_pane
+            .as_deref()
+            .filter(|_| self.tmux_server.is_none())
+            .and_then(|id| self.find_pane(id))
+            .map(|(s, _, _)| s.name.clone());
+        let Some(s) = self
+            .sessions
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.client_activity > 0 && Some(&s.name) != own.as_ref())
+            .max_by_key(|(_, s)| s.client_activity)
+            .map(|(i, _)| i)
+        else {
+            return;
+        };
+        let session = &self.sessions[s];
+        let w = session.windows.iter().position(|w| w.active).unwrap_or(0);
+        let p = session
+            .windows
+            .get(w)
+            .and_then(|w| w.panes.iter().position(|p| p.active))
+            .unwrap_or(0);
+        self.collapsed_groups.remove(&session.group.clone());
+        self.selected_session = s;
+        self.selected_window = w;
+        self.selected_pane = p;
+        self.session_list_state.select(Some(s));
+        self.window_list_state.select(Some(w));
+        self.pane_list_state.select(Some(p));
+        if self.multi_is_visible(s) {
+            self.multi_session = s;
+            self.multi_window = w;
+        }
+    }
+
+    /// Shrink the focused TreeView list to its selected row, or expand it
+    /// again. The selection is untouched either way.
+    pub fn toggle_collapse_focused_list(&mut self) {
+        if !self.collapsed_lists.remove(&self.focus) {
+            self.collapsed_lists.insert(self.focus);
+        }
+    }
+
+    /// Flip between dimming and hiding unattached sessions in the MultiPreview.
+    pub fn toggle_inactive_sessions(&mut self) {
+        self.inactive_sessions = match self.inactive_sessions {
+            InactiveSessions::Dim => InactiveSessions::Hide,
+            InactiveSessions::Hide => InactiveSessions::Dim,
+        };
+        self.clamp_multi_to_visible();
+        self.status_message = Some(match self.inactive_sessions {
+            InactiveSessions::Dim => "Inactive sessions: dimmed".to_string(),
+            InactiveSessions::Hide => "Inactive sessions: hidden".to_string(),
+        });
+    }
+
+    fn multi_is_visible(&self, idx: usize) -> bool {
+        match self.inactive_sessions {
+            InactiveSessions::Dim => true,
+            InactiveSessions::Hide => self.sessions.get(idx).is_some_and(|s| s.attached),
+        }
+    }
+
+    /// Indices into `sessions` of the columns the MultiPreview shows, in order.
+    pub fn multi_visible_sessions(&self) -> Vec<usize> {
+        (0..self.sessions.len())
+            .filter(|&idx| self.multi_is_visible(idx))
+            .collect()
+    }
+
+    /// Move the MultiPreview selection off a hidden session onto the nearest
+    /// visible one (preferring the next). Leaves it alone if none is visible.
+    fn clamp_multi_to_visible(&mut self) {
+        if self.multi_is_visible(self.multi_session) {
+            return;
+        }
+        let visible = self.multi_visible_sessions();
+        let target = visible
+            .iter()
+            .find(|&&idx| idx > self.multi_session)
+            .or(visible.last());
+        if let Some(&idx) = target {
+            self.multi_session = idx;
+            self.multi_window = self.multi_top_window(idx);
+        }
+    }
+
+    /// Indices into `session.windows` in the order the MultiPreview stacks
+    /// them (`layout.multi_window_sort`), whatever the Windows list's order.
+    /// `multi_window` stays an index into
+    /// `windows`; up / down step through this order.
+    pub fn multi_window_order(&self, session: &TmuxSession) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..session.windows.len()).collect();
+        let sort = self.layout.multi_window_sort;
+        order.sort_by(|&a, &b| sort.cmp(&session.windows[a], &session.windows[b]));
+        order
+    }
+
+    /// The MultiPreview grid's cells in reading order: every window of every
+    /// visible session, as `(session, window)` indices, each session's
+    /// windows in [`Self::multi_window_order`].
+    pub fn multi_grid_cells(&self) -> Vec<(usize, usize)> {
+        self.multi_visible_sessions()
+            .into_iter()
+            .flat_map(|s| {
+                let order = self.multi_window_order(&self.sessions[s]);
+                order.into_iter().map(move |w| (s, w))
+            })
+            .collect()
+    }
+
+    /// Change the MultiPreview grid's columns by `delta`, within 0 (a column
+    /// per session) and [`MAX_MULTI_COLUMNS`].
+    pub fn adjust_multi_columns(&mut self, delta: i16) {
+        let columns = self.layout.multi_columns.saturating_add_signed(delta);
+        self.layout.multi_columns = columns.min(MAX_MULTI_COLUMNS);
+        self.status_message = Some(match self.layout.multi_columns {
+            0 if self.view_mode == ViewMode::AllPanes => "Grid: automatic columns".to_string(),
+            0 => "A column per session".to_string(),
+            1 => "Grid: 1 column".to_string(),
+            n => format!("Grid: {n} columns"),
+        });
+    }
+
+    /// Move `delta` cells through the grid: ±1 across, ± the column count a
+    /// row down or up. A move off the grid stays put.
+    pub fn multi_grid_move(&mut self, delta: isize) {
+        let cells = self.multi_grid_cells();
+        let Some(pos) = cells.iter().position(|&c| c == (self.multi_session, self.multi_window))
+        else {
+            return;
+        };
+        if let Some(&(s, w)) = pos.checked_add_signed(delta).and_then(|p| cells.get(p)) {
+            self.multi_session = s;
+            self.multi_window = w;
+        }
+    }
+
+    /// Select the grid's first cell, or its last.
+    pub fn multi_grid_edge(&mut self, last: bool) {
+        let cells = self.multi_grid_cells();
+        let cell = if last { cells.last() } else { cells.first() };
+        if let Some(&(s, w)) = cell {
+            self.multi_session = s;
+            self.multi_window = w;
+        }
+    }
+
+    /// The all-panes grid's tiles in reading order: every pane of each
+    /// window of [`Self::multi_grid_cells`], as `(session, window, pane)`
+    /// indices.
+    pub fn all_pane_cells(&self) -> Vec<(usize, usize, usize)> {
+        self.multi_grid_cells()
+            .into_iter()
+            .flat_map(|(s, w)| {
+                let panes = self.sessions[s].windows[w].panes.len();
+                (0..panes).map(move |p| (s, w, p))
+            })
+            .collect()
+    }
+
+    /// Columns of the all-panes grid: `layout.multi_columns` when set, else
+    /// the fewest that keep it no taller than wide.
+    pub fn all_panes_columns(&self) -> usize {
+        match self.layout.multi_columns {
+            0 => {
+                let tiles = self.all_pane_cells().len();
+                (1..).find(|c| c * c >= tiles).unwrap_or(1)
+            }
+            n => usize::from(n),
+        }
+    }
+
+    /// Move `delta` tiles through the all-panes grid, as
+    /// [`Self::multi_grid_move`] does through windows.
+    pub fn all_panes_move(&mut self, delta: isize) {
+        let cells = self.all_pane_cells();
+        let selected = (self.multi_session, self.multi_window, self.multi_pane);
+        let Some(pos) = cells.iter().position(|&c| c == selected) else {
+            return;
+        };
+        if let Some(&cell) = pos.checked_add_signed(delta).and_then(|p| cells.get(p)) {
+            (self.multi_session, self.multi_window, self.multi_pane) = cell;
+        }
+    }
+
+    /// Select the all-panes grid's first tile, or its last.
+    pub fn all_panes_edge(&mut self, last: bool) {
+        let cells = self.all_pane_cells();
+        let cell = if last { cells.last() } else { cells.first() };
+        if let Some(&cell) = cell {
+            (self.multi_session, self.multi_window, self.multi_pane) = cell;
+        }
+    }
+
+    /// `session:window.pane` target of the all-panes grid's selected tile.
+    pub fn get_all_panes_target(&self) -> Option<String> {
+        if !self.multi_is_visible(self.multi_session) {
+            return None;
+        }
+        let session = self.sessions.get(self.multi_session)?;
+        let window = session.windows.get(self.multi_window)?;
+        let pane = window.panes.get(self.multi_pane)?;
+        Some(format!("{}:{}.{}", session.target(), window.index, pane.index))
+    }
+
+    /// Capture requests for the all-panes grid: each tile's visible screen,
+    /// by pane id like the monitor strip's, leaving out the deck's own pane.
+    fn all_panes_capture_targets(&self) -> Vec<(String, i32, i32)> {
+        let own = self.self_pane.as_ref().filter(|_| self.tmux_server.is_none());
+        let captured = self.all_panes_captured_windows();
+        self.all_pane_cells()
+            .into_iter()
+            .filter(|&(s, w, _)| captured.contains(&(s, w)))
+            .map(|(s, w, p)| &self.sessions[s].windows[w].panes[p])
+            .filter(|pane| Some(&pane.id) != own)
+            .map(|pane| (pane.id.clone(), 0, i32::try_from(pane.height).unwrap_or(i32::MAX)))
+            .collect()
+    }
+
+    /// Windows of the all-panes grid, as (session, window) indexes, whose tiles
+    /// are captured: all of them unless `preview.max_capture_sessions` or
+    /// `preview.max_capture_windows` caps them. Then the selected tile's
+    /// window always is, followed by the windows of attached sessions and
+    /// then the most recently active, up to the caps.
+    pub fn all_panes_captured_windows(&self) -> HashSet<(usize, usize)> {
+        let mut windows: Vec<(usize, usize)> = Vec::new();
+        for (s, w, _) in self.all_pane_cells() {
+            if windows.last() != Some(&(s, w)) {
+                windows.push((s, w));
+            }
+        }
+        let (max_sessions, max_windows) =
+            (self.preview.max_capture_sessions, self.preview.max_capture_windows);
+        if max_sessions == 0 && max_windows == 0 {
+            return windows.into_iter().collect();
+        }
+        let selected = (self.multi_session, self.multi_window);
+        windows.sort_by_key(|&(s, w)| {
+            let session = &self.sessions[s];
+            (
+                (s, w) != selected,
+                !session.attached,
+                std::cmp::Reverse(session.windows[w].activity),
+            )
+        });
+        let mut sessions = HashSet::new();
+        let mut captured = HashSet::new();
+        for (s, w) in windows {
+            let session_fits =
+                sessions.contains(&s) || max_sessions == 0 || sessions.len() < max_sessions;
+            let window_fits = max_windows == 0 || captured.len() < max_windows;
+            if (s, w) == selected || (session_fits && window_fits) {
+                sessions.insert(s);
+                captured.insert((s, w));
+            }
+        }
+        captured
+    }
+
+    /// The window at the top of session `idx`'s MultiPreview column.
+    fn multi_top_window(&self, idx: usize) -> usize {
+        self.sessions
+            .get(idx)
+            .and_then(|s| self.multi_window_order(s).first().copied())
+            .unwrap_or(0)
+    }
+
+    pub fn multi_move_left(&mut self) {
+        if let Some(&prev) = self
+            .multi_visible_sessions()
+            .iter()
+            .rev()
+            .find(|&&idx| idx < self.multi_session)
+        {
+            self.multi_session = prev;
+            // Reset window selection for new session
+            self.multi_window = self.multi_top_window(prev);
+        }
+    }
+
+    pub fn multi_move_right(&mut self) {
+        if let Some(&next) = self
+            .multi_visible_sessions()
+            .iter()
+            .find(|&&idx| idx > self.multi_session)
+        {
+            self.multi_session = next;
+            // Reset window selection for new session
+            self.multi_window = self.multi_top_window(next);
+        }
+    }
+
+    pub fn multi_move_up(&mut self) {
+        self.multi_step_window(-1);
+    }
+
+    /// Select the top window of the selected session's column.
+    pub fn multi_move_top(&mut self) {
+        self.multi_step_window(isize::MIN);
+    }
+
+    /// Select the bottom window of the selected session's column.
+    pub fn multi_move_bottom(&mut self) {
+        self.multi_step_window(isize::MAX);
+    }
+
+    /// Select the rightmost session column.
+    pub fn multi_move_last(&mut self) {
+        if let Some(&last) = self.multi_visible_sessions().last() {
+            self.multi_session = last;
+            self.multi_window = self.multi_top_window(last);
+        }
+    }
+
+    pub fn multi_move_down(&mut self) {
+        self.multi_step_window(1);
+    }
+
+    /// Move `delta` windows down the column, stopping at either end.
+    fn multi_step_window(&mut self, delta: isize) {
+        let Some(session) = self.sessions.get(self.multi_session) else {
+            return;
+        };
+        let order = self.multi_window_order(session);
+        let Some(pos) = order.iter().position(|&w| w == self.multi_window) else {
+            return;
+        };
+        let target = pos.saturating_add_signed(delta).min(order.len().saturating_sub(1));
+        if let Some(&w) = order.get(target) {
+            self.multi_window = w;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(name: &str) -> TmuxSession {
+        TmuxSession {
+            name: name.to_string(),
+            windows: Vec::new(),
+            has_claude: false,
+            claude_state: None,
+            last_attached: 0,
+            activity: 0,
+            attached: false,
+            client_sizes: Vec::new(),
+            client_activity: 0,
+            group: None,
+        }
+    }
+
+    /// Build a UIState with an in-memory (no-disk) group store and the given
+    /// assignments, then load `names` as the session list.
+    fn state_with(names: &[&str], groups: &[(&str, &str)]) -> UIState {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        for (sess, grp) in groups {
+            state.groups.set(sess, Some(grp));
+        }
+        state.update_sessions(names.iter().map(|n| session(n)).collect());
+        state
+    }
+
+    /// Build a session with a single window holding the given panes, each
+    /// described as `(pane_id, index, claude_state, state_since)`.
+
+    #[test]
+    fn ungrouped_sessions_have_no_headers() {
+        let state = state_with(&["a", "b", "c"], &[]);
+        let rows = state.session_rows();
+        assert_eq!(rows.len(), 3);
+        assert!(rows.iter().all(|r| matches!(r, SessionRow::Session { .. })));
+    }
+
+    #[test]
+    fn grouped_sessions_cluster_with_ungrouped_last() {
+        // a, c -> "work"; b ungrouped. Names tie-break ascending within a group.
+        let state = state_with(&["a", "b", "c"], &[("a", "work"), ("c", "work")]);
+        let ordered: Vec<&str> = state.sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(ordered, vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn rows_insert_one_header_per_group() {
+        let state = state_with(
+            &["a", "b", "c"],
+            &[("a", "work"), ("c", "work"), ("b", "play")],
+        );
+        let rows = state.session_rows();
+        // play(b) sorts before work(a,c) alphabetically; ungrouped bucket absent.
+        let labels: Vec<String> = rows
+            .iter()
+            .filter_map(|r| match r {
+                SessionRow::Header { group, count, .. } => {
+                    Some(format!("{}:{}", group.as_deref().unwrap_or("none"), count))
+                }
+                SessionRow::Session { .. } => None,
+            })
+            .collect();
+        assert_eq!(labels, vec!["play:1".to_string(), "work:2".to_string()]);
+    }
+
+    #[test]
+    fn ungrouped_bucket_gets_a_header_when_mixed() {
+        let state = state_with(&["a", "b"], &[("a", "work")]);
+        let rows = state.session_rows();
+        let has_ungrouped_header = rows.iter().any(|r| {
+            matches!(r, SessionRow::Header { group: None, count, .. } if *count == 1)
+        });
+        assert!(has_ungrouped_header);
+    }
+
+    #[test]
+    fn folding_hides_members_but_keeps_header() {
+        // work: a, c ; play: b. Select "a" (in work) and fold its group.
+        let mut state = state_with(
+            &["a", "b", "c"],
+            &[("a", "work"), ("c", "work"), ("b", "play")],
+        );
+        let work_idx = state.sessions.iter().position(|s| s.name == "a").unwrap();
+        state.selected_session = work_idx;
+        state.toggle_fold_current_group();
+
+        let rows = state.session_rows();
+        // No "work" member sessions remain visible, but its header stays
+        // (now marked collapsed); play's member is still shown.
+        let work_collapsed = rows.iter().any(|r| matches!(
+            r,
+            SessionRow::Header { group: Some(g), collapsed: true, .. } if g == "work"
+        ));
+        assert!(work_collapsed);
+        let visible_sessions: Vec<&str> = rows
+            .iter()
+            .filter_map(|r| match r {
+                SessionRow::Session { index } => Some(state.sessions[*index].name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(visible_sessions, vec!["b"]);
+        // Selection parks on the folded group's head, so the cursor sits on the
+        // (collapsed) "work" header and can re-open it.
+        assert!(state.selection_on_folded_header());
+        assert_eq!(state.sessions[state.selected_session].group.as_deref(), Some("work"));
+
+        // Toggling again from the folded header re-expands the group — this is
+        // the regression that previously had no way to recover.
+        state.toggle_fold_current_group();
+        let rows = state.session_rows();
+        let names: Vec<&str> = rows
+            .iter()
+            .filter_map(|r| match r {
+                SessionRow::Session { index } => Some(state.sessions[*index].name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(names.contains(&"a") && names.contains(&"c"));
+        assert!(!state.selection_on_folded_header());
+    }
+
+    #[test]
+    fn navigation_lands_on_folded_group_then_reopens() {
+        let mut state = state_with(
+            &["a", "b", "c"],
+            &[("a", "work"), ("c", "work"), ("b", "play")],
+        );
+        // Order is play(b), work(a,c). Fold work (selection parks on work head).
+        state.selected_session = state.sessions.iter().position(|s| s.name == "a").unwrap();
+        state.toggle_fold_current_group();
+        // From the visible "b", moving down stops on the folded work header
+        // rather than skipping it entirely.
+        state.selected_session = state.sessions.iter().position(|s| s.name == "b").unwrap();
+        state.tree_move_down();
+        assert!(state.selection_on_folded_header());
+        assert_eq!(state.sessions[state.selected_session].group.as_deref(), Some("work"));
+        // And `za` there expands it back.
+        state.toggle_fold_current_group();
+        assert!(!state.selection_on_folded_header());
+    }
+
+    #[test]
+    fn fold_is_noop_without_groups() {
+        let mut state = state_with(&["a", "b"], &[]);
+        state.toggle_fold_current_group();
+        let rows = state.session_rows();
+        // No headers, all sessions still visible.
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|r| matches!(r, SessionRow::Session { .. })));
+    }
+
+    #[test]
+    fn assigning_group_updates_store_and_order() {
+        let mut state = state_with(&["a", "b"], &[]);
+        state.selected_session = 1; // "b"
+        state.assign_selected_group(Some("work".to_string()));
+        assert_eq!(state.groups.group_of("b"), Some("work".to_string()));
+        // "b" is now grouped and clusters above the ungrouped "a".
+        let ordered: Vec<&str> = state.sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(ordered, vec!["b", "a"]);
+        // Selection still tracks "b" after the reorder.
+        assert_eq!(state.sessions[state.selected_session].name, "b");
+    }
+
+    #[test]
+    fn group_popup_lists_existing_groups_and_highlights_current() {
+        let state = state_with(
+            &["a", "b", "c"],
+            &[("a", "work"), ("b", "personal"), ("c", "work")],
+        );
+        // Sorted, deduplicated existing groups.
+        assert_eq!(state.groups.group_names(), vec!["personal", "work"]);
+
+        let mut state = state;
+        state.selected_session = state.sessions.iter().position(|s| s.name == "b").unwrap();
+        state.open_group_session_popup();
+        assert_eq!(state.popup_mode, Some(PopupMode::GroupSession));
+        // "b" is in "personal", so that entry starts highlighted.
+        assert_eq!(
+            state.selected_group_choice(),
+            GroupChoice::Existing("personal".to_string())
+        );
+        // Entries: 2 groups + Ungrouped + New.
+        assert_eq!(state.group_choice_count(), 4);
+    }
+
+    #[test]
+    fn group_popup_defaults_to_ungrouped_for_ungrouped_session() {
+        let mut state = state_with(&["a", "b"], &[("b", "work")]);
+        state.selected_session = state.sessions.iter().position(|s| s.name == "a").unwrap();
+        state.open_group_session_popup();
+        // Index sits on the "Ungrouped" entry, just past the single group.
+        assert_eq!(state.selected_group_choice(), GroupChoice::Ungrouped);
+    }
+
+    #[test]
+    fn group_choice_navigation_wraps_and_reaches_new() {
+        let mut state = state_with(&["a"], &[("a", "work")]);
+        state.open_group_session_popup();
+        // Entries: ["work", Ungrouped, New]. Starts on "work".
+        assert_eq!(
+            state.selected_group_choice(),
+            GroupChoice::Existing("work".to_string())
+        );
+        state.group_choice_up(); // wraps to last entry
+        assert_eq!(state.selected_group_choice(), GroupChoice::New);
+        state.group_choice_down(); // wraps back to first
+        assert_eq!(
+            state.selected_group_choice(),
+            GroupChoice::Existing("work".to_string())
+        );
+        state.group_choice_down();
+        assert_eq!(state.selected_group_choice(), GroupChoice::Ungrouped);
+    }
+
+    #[test]
+    fn binary_capture_is_replaced_with_placeholder() {
+        let mut state = UIState::new(Config::default());
+        let garbage = [0xff, 0xfe, 0x00, 0x01, 0x02, b'a'];
+        let printable = preview::printable_ratio(&garbage);
+        state.update_pane_content(String::from_utf8_lossy(&garbage).into_owned(), printable);
+        let text = state.pane_content_parsed.as_ref().unwrap();
+        assert_eq!(text.lines[0].spans[0].content, BINARY_PREVIEW_PLACEHOLDER);
+
+        // A zero threshold disables the check entirely.
+        state.preview.binary_threshold = 0.0;
+        state.update_pane_content("\u{fffd}\u{fffd}".to_string(), 0.0);
+        let text = state.pane_content_parsed.as_ref().unwrap();
+        assert_ne!(text.lines[0].spans[0].content, BINARY_PREVIEW_PLACEHOLDER);
+    }
+
+    #[test]
+    fn binary_capture_of_a_monitored_pane_is_replaced_with_placeholder() {
+        let mut state = UIState::new(Config::default());
+        state.monitored.push("%7".to_string());
+        let garbage = [0xff, 0xfe, 0x00, 0x01, 0x02, b'a'];
+        let printable = preview::printable_ratio(&garbage);
+        let content = String::from_utf8_lossy(&garbage).into_owned();
+        assert_eq!(state.update_monitor_content("%7", &content, printable), Some(true));
+        let text = &state.monitor_content["%7"];
+        assert_eq!(text.lines[0].spans[0].content, BINARY_PREVIEW_PLACEHOLDER);
+    }
+
+    #[test]
+    fn unchanged_capture_keeps_the_parsed_preview() {
+        let mut state = UIState::new(Config::default());
+        assert!(state.update_pane_content("\x1b[32mok\x1b[0m".to_string(), 1.0));
+        // A marker only an actual re-parse would overwrite.
+        state.pane_content_parsed = Some(Text::raw("cached"));
+        assert!(!state.update_pane_content("\x1b[32mok\x1b[0m".to_string(), 1.0));
+        assert_eq!(state.pane_content_parsed, Some(Text::raw("cached")));
+        assert!(state.update_pane_content("changed".to_string(), 1.0));
+        assert_eq!(state.pane_content_parsed, Some(Text::raw("changed")));
+    }
+
+    #[test]
+    fn input_handles_multibyte_chars_without_panic() {
+        let mut state = UIState::new(Config::default());
+        // 日本語を複数文字入力（旧実装ではバイト境界パニックしていた）
+        state.input_char('あ');
+        state.input_char('い');
+        state.input_char('う');
+        assert_eq!(state.input_buffer, "あいう");
+        assert_eq!(state.input_cursor, 3);
+    }
+
+    #[test]
+    fn input_cursor_movement_and_editing_with_multibyte() {
+        let mut state = UIState::new(Config::default());
+        for c in "あいう".chars() {
+            state.input_char(c);
+        }
+        // 左へ2つ移動 → カーソルは「い」の前
+        state.input_move_left();
+        state.input_move_left();
+        assert_eq!(state.input_cursor, 1);
+        // カーソル位置に「ん」を挿入
+        state.input_char('ん');
+        assert_eq!(state.input_buffer, "あんいう");
+        assert_eq!(state.input_cursor, 2);
+        // backspace で「ん」を削除
+        state.input_backspace();
+        assert_eq!(state.input_buffer, "あいう");
+        assert_eq!(state.input_cursor, 1);
+        // delete でカーソル位置の「い」を削除
+        state.input_delete();
+        assert_eq!(state.input_buffer, "あう");
+        assert_eq!(state.input_cursor, 1);
+    }
+
+    #[test]
+    fn input_backspace_removes_a_whole_accented_char() {
+        let mut state = UIState::new(Config::default());
+        for c in "café".chars() {
+            state.input_char(c);
+        }
+        state.input_move_left();
+        state.input_move_left();
+        state.input_backspace();
+        assert_eq!(state.input_buffer, "cfé");
+        assert_eq!(state.input_cursor, 1);
+        state.input_move_end();
+        state.input_backspace();
+        assert_eq!(state.input_buffer, "cf");
+    }
+
+    #[test]
+    fn input_move_end_uses_char_count() {
+        let mut state = UIState::new(Config::default());
+        for c in "あい".chars() {
+            state.input_char(c);
+        }
+        state.input_move_home();
+        assert_eq!(state.input_cursor, 0);
+        state.input_move_end();
+        assert_eq!(state.input_cursor, 2);
+    }
+
+    #[test]
+    fn input_char_limited_caps_char_count() {
+        let mut state = UIState::new(Config::default());
+        for _ in 0..40 {
+            state.input_char_limited('a', SESSION_NAME_MAX_LEN);
+        }
+        assert_eq!(state.input_buffer.chars().count(), SESSION_NAME_MAX_LEN);
+    }
+
+    #[test]
+    fn input_char_limited_counts_chars_not_bytes() {
+        let mut state = UIState::new(Config::default());
+        // マルチバイト文字でもバイト長ではなく文字数で制限される
+        for _ in 0..40 {
+            state.input_char_limited('あ', SESSION_NAME_MAX_LEN);
+        }
+        assert_eq!(state.input_buffer.chars().count(), SESSION_NAME_MAX_LEN);
+    }
+
+    fn pane(id: &str, index: u32) -> TmuxPane {
+        TmuxPane {
+            id: id.to_string(),
+            index,
+            width: 80,
+            height: 24,
+            active: false,
+            current_command: "zsh".to_string(),
+            pid: 0,
+            dead: false,
+            dead_status: None,
+            cpu_percent: None,
+            rss_kb: None,
+            has_claude: false,
+            claude_state: None,
+            claude_activity: None,
+            claude_state_since: None,
+            claude_cwd: None,
+        }
+    }
+
+    fn session_with_panes(name: &str, ids: &[&str]) -> TmuxSession {
+        let mut s = session(name);
+        s.windows.push(TmuxWindow {
+            index: 0,
+            name: "main".to_string(),
+            active: true,
+            synchronized: false,
+            alerts: String::new(),
+            activity: 0,
+            panes: ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| pane(id, i as u32))
+                .collect(),
+            has_claude: false,
+            claude_state: None,
+        });
+        s
+    }
+
+    #[test]
+    fn pending_pane_select_follows_rotated_pane() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![session_with_panes("a", &["%1", "%2", "%3"])]);
+        state.selected_pane = 0;
+        state.pending_pane_select = state.selected_pane_id();
+
+        // After `rotate-window -D` the pane that was first ends up second.
+        state.update_sessions(vec![session_with_panes("a", &["%3", "%1", "%2"])]);
+        assert_eq!(state.selected_pane, 1);
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%1"));
+        assert!(state.pending_pane_select.is_none());
+    }
+
+    #[test]
+    fn template_picker_flows_into_prefilled_name_prompt() {
+        let mut state = UIState::new(Config::default());
+        assert!(!state.open_template_picker());
+        assert!(state.last_error.is_some());
+
+        state.templates = ["dev", "ops"]
+            .iter()
+            .map(|n| SessionTemplate {
+                name: n.to_string(),
+                ..SessionTemplate::default()
+            })
+            .collect();
+        assert!(state.open_template_picker());
+        state.template_choice_up();
+        assert_eq!(state.template_choice_index, 1);
+        state.begin_template_session_name();
+        assert_eq!(state.popup_mode, Some(PopupMode::TemplateSessionName));
+        assert_eq!(state.input_buffer, "ops");
+        state.input_char('2');
+        let (name, template) = state.get_template_session_info().unwrap();
+        assert_eq!((name.as_str(), template.name.as_str()), ("ops2", "ops"));
+    }
+
+    #[test]
+    fn self_pane_is_detected_only_when_nested() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![session_with_panes("a", &["%1", "%2"])]);
+        assert!(!state.preview_pane_is_self());
+
+        assert!(!state.inside_tmux);
+        state.note_nested_tmux(Some("%2".to_string()));
+        assert!(state.inside_tmux);
+        assert_eq!(state.status_message.as_deref(), Some(NESTED_TMUX_NOTE));
+        assert!(!state.preview_pane_is_self());
+        state.selected_pane = 1;
+        assert!(state.preview_pane_is_self());
+    }
+
+    #[test]
+    fn window_focus_can_preview_the_active_pane() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut s = session_with_panes("a", &["%1", "%2"]);
+        s.windows[0].panes[1].active = true;
+        state.update_sessions(vec![s]);
+        state.focus = Focus::Windows;
+        assert!(!state.preview_follows_active_pane());
+        assert_eq!(state.get_preview_pane_target().as_deref(), Some("=a:0.0"));
+
+        state.behavior.window_preview_active_pane = true;
+        assert!(state.preview_follows_active_pane());
+        assert_eq!(state.get_preview_pane_target().as_deref(), Some("=a:0.1"));
+        let (target, _, _) = state.get_selected_pane_target_with_capture_range().unwrap();
+        assert_eq!(target, "=a:0.1");
+
+        state.focus = Focus::Panes;
+        assert_eq!(state.get_preview_pane_target().as_deref(), Some("=a:0.0"));
+    }
+
+    #[test]
+    fn expand_drills_down_to_panes_then_stops() {
+        let mut state = UIState::new(Config::default());
+        state.focus = Focus::Sessions;
+        assert!(state.expand_selection());
+        assert_eq!(state.focus, Focus::Windows);
+        assert!(state.expand_selection());
+        assert_eq!(state.focus, Focus::Panes);
+        assert!(!state.expand_selection());
+
+        state.view_mode = ViewMode::MultiPreview;
+        assert!(state.expand_selection());
+        assert_eq!((state.view_mode, state.focus), (ViewMode::TreeView, Focus::Windows));
+    }
+
+    #[test]
+    fn kill_contents_list_windows_and_scroll_is_bounded() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut s = session_with_panes("a", &["%1", "%2"]);
+        for i in 1..10 {
+            s.windows.push(TmuxWindow {
+                index: i,
+                name: format!("w{i}"),
+                active: false,
+                synchronized: false,
+                alerts: String::new(),
+                activity: 0,
+                panes: vec![pane(&format!("%{}", i + 10), 0)],
+                has_claude: false,
+                claude_state: None,
+            });
+        }
+        state.update_sessions(vec![s]);
+        state.open_kill_session_popup();
+        let lines = state.kill_session_contents();
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[0], "0:main  zsh, zsh");
+
+        for _ in 0..20 {
+            state.kill_list_scroll_down();
+        }
+        assert_eq!(state.kill_list_scroll, 10 - KILL_LIST_MAX_ROWS);
+    }
+
+    #[test]
+    fn window_popups_target_the_selected_window() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut s = session_with_panes("a", &["%1", "%2"]);
+        let mut logs = s.windows[0].clone();
+        logs.index = 3;
+        logs.name = "logs".to_string();
+        logs.panes = vec![pane("%3", 0)];
+        s.windows.push(logs);
+        state.update_sessions(vec![s]);
+        state.focus = Focus::Windows;
+        state.tree_move_down();
+
+        state.open_new_window_popup();
+        assert_eq!(state.get_new_window_info(), Some(("=a".to_string(), None)));
+        state.input_char('x');
+        assert_eq!(state.get_new_window_info().unwrap().1.as_deref(), Some("x"));
+
+        state.open_rename_window_popup();
+        assert_eq!(state.input_buffer, "logs");
+        state.input_backspace();
+        assert_eq!(
+            state.get_rename_window_info(),
+            Some(("=a:3".to_string(), "log".to_string()))
+        );
+
+        state.open_kill_window_popup();
+        assert_eq!(state.kill_contents(), ["0  zsh"]);
+        assert_eq!(state.get_kill_window_target(), None);
+        state.toggle_confirm_selection();
+        assert_eq!(state.get_kill_window_target().as_deref(), Some("=a:3"));
+    }
+
+    #[test]
+    fn fuzzy_match_is_an_ordered_case_insensitive_subsequence() {
+        assert!(fuzzy_match("dpy", "deploy"));
+        assert!(fuzzy_match("DPy", "deploy"));
+        assert!(fuzzy_match("", "anything"));
+        assert!(!fuzzy_match("ydp", "deploy"));
+        assert!(!fuzzy_match("deployx", "deploy"));
+    }
+
+    #[test]
+    fn search_jumps_to_the_first_match_and_esc_restores_the_selection() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut build = session_with_panes("build", &["%1", "%2"]);
+        build.windows[0].panes[1].current_command = "deploy".to_string();
+        state.update_sessions(vec![session_with_panes("api", &["%3"]), build]);
+        let api = state.sessions.iter().position(|s| s.name == "api").unwrap();
+        let build = state.sessions.iter().position(|s| s.name == "build").unwrap();
+        state.selected_session = api;
+
+        state.open_search();
+        state.input_buffer = "dpy".to_string();
+        state.update_search();
+        assert!(!state.search_matches_session(&state.sessions[api]));
+        assert!(state.search_matches_session(&state.sessions[build]));
+        assert_eq!(state.search_match_count(), 1);
+        assert_eq!(state.accept_search(), Ok(()));
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%2"));
+        assert_eq!(state.focus, Focus::Panes);
+        assert!(state.search_query.is_empty());
+
+        state.open_search();
+        state.input_buffer = "api".to_string();
+        state.update_search();
+        state.popup_back();
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%2"));
+        assert!(state.search_query.is_empty());
+
+        state.open_search();
+        state.input_buffer = "zzz".to_string();
+        state.update_search();
+        assert_eq!(state.accept_search(), Err("no match for 'zzz'".to_string()));
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%2"));
+        assert_eq!(state.popup_mode, None);
+    }
+
+    #[test]
+    fn restore_selection_finds_the_saved_target_or_falls_back() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut work = session_with_panes("a:b", &["%1"]);
+        let mut second = session_with_panes("a:b", &["%2", "%3"]).windows.remove(0);
+        second.index = 4;
+        work.windows.push(second);
+        state.update_sessions(vec![session_with_panes("first", &["%9"]), work]);
+
+        state.restore_selection("=a:b:4.1");
+        assert_eq!(state.sessions[state.selected_session].name, "a:b");
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%3"));
+
+        state.restore_selection("=a:b:7");
+        assert_eq!(state.sessions[state.selected_session].name, "a:b");
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%1"));
+
+        state.restore_selection("=gone:0.0");
+        assert_eq!(state.selected_session, 0);
+        assert_eq!(state.selected_window, 0);
+    }
+
+    #[test]
+    fn yanked_preview_text_drops_escapes_and_trailing_blank_lines() {
+        let mut state = UIState::new(Config::default());
+        assert_eq!(state.preview_plain_text(), None);
+        state.update_pane_content("\x1b[1m$ ls\x1b[0m  \nsrc\n\n   \n".to_string(), 1.0);
+        assert_eq!(state.preview_plain_text().as_deref(), Some("$ ls\nsrc"));
+    }
+
+    #[test]
+    fn killing_the_servers_last_pane_is_refused() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![session_with_panes("a", &["%1", "%2"])]);
+        state.focus = Focus::Panes;
+        state.tree_move_down();
+
+        state.open_kill_pane_popup();
+        assert_eq!(state.popup_mode, Some(PopupMode::ConfirmKillPane));
+        assert_eq!(state.get_kill_pane_id(), None);
+        state.toggle_confirm_selection();
+        assert_eq!(state.get_kill_pane_id().as_deref(), Some("%2"));
+        state.close_popup();
+
+        state.forget_pane("%2");
+        state.open_kill_pane_popup();
+        assert_eq!(state.popup_mode, None);
+        assert!(state.last_error.is_some());
+    }
+
+    #[test]
+    fn hiding_inactive_sessions_skips_them_in_multi_navigation() {
+        let mut state = state_with(&["a", "b", "c", "d"], &[]);
+        for name in ["a", "d"] {
+            let idx = state.sessions.iter().position(|s| s.name == name).unwrap();
+            state.sessions[idx].attached = true;
+        }
+        let a = state.sessions.iter().position(|s| s.name == "a").unwrap();
+        let d = state.sessions.iter().position(|s| s.name == "d").unwrap();
+        assert_eq!(state.multi_visible_sessions().len(), 4);
+
+        state.multi_session = 1;
+        state.toggle_inactive_sessions();
+        assert_eq!(state.inactive_sessions, InactiveSessions::Hide);
+        assert_eq!(state.multi_visible_sessions(), vec![a, d]);
+        // The hidden selection moved onto the next visible session.
+        assert_eq!(state.multi_session, d);
+        state.multi_move_left();
+        assert_eq!(state.multi_session, a);
+        state.multi_move_left();
+        assert_eq!(state.multi_session, a);
+        state.multi_move_right();
+        assert_eq!(state.multi_session, d);
+
+        state.toggle_inactive_sessions();
+        assert_eq!(state.multi_visible_sessions().len(), 4);
+    }
+
+    #[test]
+    fn numeric_session_names_build_exact_match_targets() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut twenty = session_with_panes("20", &["%2"]);
+        twenty.windows[0].index = 2;
+        state.update_sessions(vec![session_with_panes("2", &["%1"]), twenty]);
+        let two = state.sessions.iter().position(|s| s.name == "2").unwrap();
+        let twenty = state.sessions.iter().position(|s| s.name == "20").unwrap();
+
+        state.selected_session = two;
+        assert_eq!(state.get_selected_pane_target().as_deref(), Some("=2:0.0"));
+        assert_eq!(state.get_enter_target().as_deref(), Some("=2"));
+        state.focus = Focus::Windows;
+        assert_eq!(state.get_enter_target().as_deref(), Some("=2:0"));
+
+        state.selected_session = twenty;
+        assert_eq!(state.get_selected_pane_target().as_deref(), Some("=20:2.0"));
+        assert_eq!(state.get_current_window_target().as_deref(), Some("=20:2"));
+        state.focus = Focus::Sessions;
+        assert_eq!(state.get_enter_target().as_deref(), Some("=20"));
+
+        state.view_mode = ViewMode::MultiPreview;
+        state.multi_session = twenty;
+        assert_eq!(state.get_multi_selected_target().as_deref(), Some("=20:2"));
+        assert_eq!(display_target("=20:2"), "20:2");
+        assert_eq!(display_target("%3"), "%3");
+    }
+
+    #[test]
+    fn hiding_every_session_leaves_no_multi_target() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![session_with_panes("a", &["%1"])]);
+        state.view_mode = ViewMode::MultiPreview;
+        assert_eq!(state.get_multi_selected_target().as_deref(), Some("=a:0"));
+        state.toggle_inactive_sessions();
+        assert!(state.multi_visible_sessions().is_empty());
+        assert_eq!(state.get_multi_selected_target(), None);
+    }
+
+    #[test]
+    fn multi_window_sort_by_index_orders_and_steps_the_column() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.layout.multi_window_sort = WindowSort::Index;
+        let mut s = session_with_panes("a", &["%1"]);
+        // Activity-sorted as listed: window 2 was busiest.
+        for (i, index) in [2, 0, 1].into_iter().enumerate() {
+            let mut w = s.windows[0].clone();
+            w.index = index;
+            w.activity = 3 - i as i64;
+            w.panes = vec![pane(&format!("%{}", i + 10), 0)];
+            s.windows.push(w);
+        }
+        s.windows.remove(0);
+        state.update_sessions(vec![s]);
+        state.view_mode = ViewMode::MultiPreview;
+
+        assert_eq!(state.multi_window_order(&state.sessions[0]), [1, 2, 0]);
+        state.multi_window = 1;
+        state.multi_move_down();
+        assert_eq!(state.get_multi_selected_target().as_deref(), Some("=a:1"));
+        state.multi_move_down();
+        state.multi_move_down();
+        assert_eq!(state.get_multi_selected_target().as_deref(), Some("=a:2"));
+        state.multi_move_up();
+        state.multi_move_up();
+        state.multi_move_up();
+        assert_eq!(state.get_multi_selected_target().as_deref(), Some("=a:0"));
+        state.multi_move_bottom();
+        assert_eq!(state.get_multi_selected_target().as_deref(), Some("=a:2"));
+        state.multi_move_top();
+        assert_eq!(state.get_multi_selected_target().as_deref(), Some("=a:0"));
+    }
+
+    #[test]
+    fn dead_panes_lists_every_dead_pane_and_clamps_the_selection() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut a = session_with_panes("a", &["%1", "%2"]);
+        a.windows[0].panes[1].dead = true;
+        a.windows[0].panes[1].dead_status = Some(1);
+        let mut b = session_with_panes("b", &["%3"]);
+        b.windows[0].panes[0].dead = true;
+        state.update_sessions(vec![a, b]);
+
+        let dead = state.dead_panes();
+        let ids: Vec<&str> = dead.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&"%2") && ids.contains(&"%3"));
+        let two = dead.iter().find(|p| p.id == "%2").unwrap();
+        assert_eq!((two.location.as_str(), two.status), ("a:0.1", Some(1)));
+
+        assert!(state.open_dead_panes_popup());
+        state.dead_pane_down();
+        state.dead_pane_down();
+        assert_eq!(state.dead_pane_index, 1);
+        // A refresh that cleared one pane leaves the index past the end.
+        state.update_sessions(vec![session_with_panes("a", &["%1"]), {
+            let mut b = session_with_panes("b", &["%3"]);
+            b.windows[0].panes[0].dead = true;
+            b
+        }]);
+        assert_eq!(state.selected_dead_pane().map(|p| p.id).as_deref(), Some("%3"));
+    }
+
+    #[test]
+    fn dead_panes_popup_does_not_open_without_dead_panes() {
+        let mut state = state_with(&["a"], &[]);
+        assert!(!state.open_dead_panes_popup());
+        assert_eq!(state.popup_mode, None);
+        assert!(state.status_message.is_some());
+    }
+
+    #[test]
+    fn typed_confirmation_wants_the_phrase_and_backs_out_to_the_list() {
+        let mut state = state_with(&["a"], &[]);
+        state.popup_mode = Some(PopupMode::DeadPanes);
+        state.open_typed_confirm();
+        assert!(!state.typed_confirmation_matches());
+        for c in "ye".chars() {
+            state.input_char(c);
+        }
+        assert!(!state.typed_confirmation_matches());
+        state.input_char('s');
+        assert!(state.typed_confirmation_matches());
+
+        assert!(!state.popup_back());
+        assert_eq!(state.popup_mode, Some(PopupMode::DeadPanes));
+        assert!(state.input_buffer.is_empty());
+    }
+
+    #[test]
+    fn kill_others_spares_the_selected_and_own_sessions() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![
+            session_with_panes("a", &["%1"]),
+            session_with_panes("b", &["%2"]),
+            session_with_panes("deck", &["%3"]),
+        ]);
+        state.self_pane = Some("%3".to_string());
+        state.selected_session = state.sessions.iter().position(|s| s.name == "b").unwrap();
+        assert_eq!(state.other_session_names(), vec!["a".to_string()]);
+
+        assert!(state.open_kill_others_popup());
+        assert_eq!(state.popup_mode, Some(PopupMode::ConfirmKillOthers));
+        assert_eq!(state.kill_contents(), vec!["a".to_string()]);
+        state.close_popup();
+
+        // With typed confirmation on, the phrase prompt opens directly and
+        // backing out of it closes it.
+        state.behavior.require_typed_confirmation = true;
+        assert!(state.open_kill_others_popup());
+        assert_eq!(state.popup_mode, Some(PopupMode::TypedConfirm));
+        assert_eq!(state.typed_confirm_origin, None);
+        assert!(state.popup_back());
+
+        state.sessions.retain(|s| s.name != "b");
+        state.selected_session = state.sessions.iter().position(|s| s.name == "a").unwrap();
+        assert!(!state.open_kill_others_popup());
+        assert_eq!(state.status_message.as_deref(), Some("No other sessions"));
+    }
+
+    #[test]
+    fn inspection_shows_what_the_command_added() {
+        let mut state = state_with(&["a"], &[]);
+        // Captures for other panes, or with nothing being inspected, are ignored.
+        assert!(!state.record_snapshot("%1", "$ ".to_string()));
+        state.begin_inspection("%1", "ls");
+        assert!(!state.record_snapshot("%2", "x".to_string()));
+        assert!(!state.record_snapshot("%1", "old\n$ ".to_string()));
+        assert_eq!(state.popup_mode, None);
+
+        assert!(state.record_snapshot("%1", "old\n$ ls\na\n$ ".to_string()));
+        assert_eq!(state.popup_mode, Some(PopupMode::CommandOutput));
+        assert_eq!(state.inspect_lines, ["a", "$"]);
+        state.inspect_scroll_down();
+        state.inspect_scroll_down();
+        assert_eq!(state.inspect_scroll, 1);
+
+        // A re-capture updates the open popup against the same "before".
+        assert!(!state.record_snapshot("%1", "old\n$ ls\na\nb\n$ ".to_string()));
+        assert_eq!(state.inspect_lines, ["a", "b", "$"]);
+        state.close_popup();
+        assert!(state.inspection.is_none() && state.inspect_lines.is_empty());
+    }
+
+    #[test]
+    fn batch_summarises_successes_and_failures_once_complete() {
+        let mut state = UIState::new(Config::default());
+        let items = ["%1", "%2", "%3"]
+            .iter()
+            .map(|id| (id.to_string(), format!("dev:0.{}", &id[1..])))
+            .collect();
+        let batch = state.begin_batch("killed", "pane", items);
+        state.record_batch_result(batch, "%1", None);
+        state.record_batch_result(batch, "%3", Some("can't find pane %3\n".to_string()));
+        assert_eq!(state.status_message, None);
+        // Unknown ids and batches are ignored.
+        state.record_batch_result(batch, "%9", None);
+        state.record_batch_result(batch + 1, "%2", None);
+        state.record_batch_result(batch, "%2", None);
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("killed 2 panes (1 failed: dev:0.3 — can't find pane %3)")
+        );
+        assert!(state.batches.is_empty());
+
+        let single = state.begin_batch("respawned", "pane", vec![("%4".into(), "a:1.0".into())]);
+        state.record_batch_result(single, "%4", None);
+        assert_eq!(state.status_message.as_deref(), Some("respawned 1 pane"));
+    }
+
+    #[test]
+    fn reveal_pane_selects_it_in_the_tree_and_parent_walks_up() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.groups.set("b", Some("work"));
+        state.update_sessions(vec![
+            session_with_panes("a", &["%1"]),
+            session_with_panes("b", &["%2", "%3"]),
+        ]);
+        state.collapsed_groups.insert(Some("work".to_string()));
+        state.view_mode = ViewMode::MultiPreview;
+
+        assert!(state.reveal_pane("%3"));
+        assert_eq!(state.view_mode, ViewMode::TreeView);
+        assert_eq!(state.focus, Focus::Panes);
+        assert_eq!(state.sessions[state.selected_session].name, "b");
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%3"));
+        assert!(!state.selection_on_folded_header());
+        assert!(!state.reveal_pane("%9"));
+
+        state.open_goto_pane_popup();
+        state.input_buffer = "1".to_string();
+        assert_eq!(state.goto_pane_input(), Ok(()));
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%1"));
+        state.input_buffer = "%9".to_string();
+        assert_eq!(state.goto_pane_input(), Err("no pane %9".to_string()));
+        assert!(state.reveal_pane("%3"));
+
+        state.tree_focus_parent();
+        assert_eq!(state.focus, Focus::Windows);
+        state.tree_focus_parent();
+        state.tree_focus_parent();
+        assert_eq!(state.focus, Focus::Sessions);
+    }
+
+    #[test]
+    fn send_scope_widens_the_targets_from_pane_to_session() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut work = session_with_panes("work", &["%1", "%2"]);
+        let mut second = session_with_panes("work", &["%3", "%4"]).windows.remove(0);
+        second.index = 1;
+        work.windows.push(second);
+        state.update_sessions(vec![work, session_with_panes("other", &["%5"])]);
+        state.note_nested_tmux(Some("%4".to_string()));
+        assert!(state.reveal_pane("%2"));
+
+        state.enter_input_mode();
+        assert_eq!(state.send_targets(), ["=work:0.1"]);
+        state.cycle_send_scope();
+        assert_eq!(state.send_targets(), ["%1", "%2"]);
+        assert_eq!(state.send_scope_label().as_deref(), Some("window work:0 (2 panes)"));
+        state.cycle_send_scope();
+        // The deck's own pane is left out.
+        assert_eq!(state.send_targets(), ["%1", "%2", "%3"]);
+        state.cycle_send_scope();
+        assert_eq!(state.send_scope, SendScope::Pane);
+
+        // A new popup starts on the pane again.
+        state.send_scope = SendScope::Session;
+        state.enter_input_mode();
+        assert_eq!(state.send_scope, SendScope::Pane);
+    }
+
+    #[test]
+    fn server_restart_keeps_the_selection_by_name_and_drops_pane_ids() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![
+            session_with_panes("a", &["%1"]),
+            session_with_panes("b", &["%2"]),
+        ]);
+        assert!(state.reveal_pane("%2"));
+        state.toggle_monitor_selected();
+
+        state.server_restarted();
+        assert!(state.monitored.is_empty());
+        // The restarted server numbers its panes afresh.
+        let fresh = vec![
+            session_with_panes("b", &["%0"]),
+            session_with_panes("a", &["%1"]),
+        ];
+        // A window count that differs would normally badge the session.
+        state.sessions[0].windows.clear();
+        state.track_session_changes(&fresh);
+        state.update_sessions(fresh);
+        assert_eq!(state.sessions[state.selected_session].name, "b");
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%0"));
+        assert!(state.session_changes.is_empty());
+    }
+
+    #[test]
+    fn killed_pane_leaves_the_selection_on_a_live_pane() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![
+            session_with_panes("a", &["%1", "%2", "%3"]),
+            session_with_panes("b", &["%4"]),
+        ]);
+        assert!(state.reveal_pane("%3"));
+
+        // Killing the last pane: the selection moves onto the new last one and
+        // navigation keeps working before any refresh arrives.
+        state.forget_pane("%3");
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%2"));
+        assert!(state.get_selected_pane_target().is_some());
+        state.tree_move_down();
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%2"));
+        state.tree_move_up();
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%1"));
+
+        // A pane before the selection: it stays on the same pane.
+        state.tree_move_down();
+        state.forget_pane("%1");
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%2"));
+
+        // The last pane of a session takes the session with it.
+        state.forget_pane("%2");
+        assert_eq!(state.sessions.len(), 1);
+        assert_eq!(state.selected_pane_id().as_deref(), Some("%4"));
+    }
+
+    #[test]
+    fn monitored_panes_are_captured_by_id_and_dropped_when_closed() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![
+            session_with_panes("a", &["%1"]),
+            session_with_panes("b", &["%2", "%3"]),
+        ]);
+        assert!(state.reveal_pane("%2"));
+        state.toggle_monitor_selected();
+        assert_eq!(state.monitored, ["%2"]);
+
+        // Browsing elsewhere keeps it monitored.
+        assert!(state.reveal_pane("%1"));
+        let targets = state.monitor_capture_targets();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].0, "%2");
+        assert_eq!(state.update_monitor_content("%2", "$ make\nok\n\n\n", 1.0), Some(true));
+        assert_eq!(state.monitor_content["%2"].lines.len(), 2);
+        // The same capture again changes nothing on screen.
+        assert_eq!(state.update_monitor_content("%2", "$ make\nok\n\n\n", 1.0), Some(false));
+        assert_eq!(state.update_monitor_content("a:0.0", "tree preview", 1.0), None);
+
+        state.update_sessions(vec![
+            session_with_panes("a", &["%1"]),
+            session_with_panes("b", &["%3"]),
+        ]);
+        assert!(state.monitored.is_empty());
+        assert!(state.monitor_content.is_empty());
+    }
+
+    #[test]
+    fn scoped_session_filters_refreshes_and_skips_the_sessions_list() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.scope_to_session("work".to_string());
+        state.update_sessions(vec![session("play"), session("work")]);
+        let names: Vec<&str> = state.sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["work"]);
+        assert!(state.last_error.is_none());
+
+        assert_eq!(state.focus, Focus::Windows);
+        state.tree_next_focus();
+        state.tree_next_focus();
+        assert_eq!(state.focus, Focus::Windows);
+        state.tree_prev_focus();
+        assert_eq!(state.focus, Focus::Panes);
+        state.tree_focus_parent();
+        state.tree_focus_parent();
+        assert_eq!(state.focus, Focus::Windows);
+
+        state.update_sessions(vec![session("play")]);
+        assert_eq!(state.last_error.as_deref(), Some("no session named 'work'"));
+    }
+
+    #[test]
+    fn session_changes_badge_window_deltas_and_decay() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let snapshot = |windows: usize, activity: i64| {
+            let mut s = session_with_panes("a", &[]);
+            s.windows = vec![s.windows[0].clone(); windows];
+            s.activity = activity;
+            vec![s, session("b")]
+        };
+        let refresh = |state: &mut UIState, sessions: Vec<TmuxSession>| {
+            state.track_session_changes(&sessions);
+            state.update_sessions(sessions);
+        };
+        refresh(&mut state, snapshot(1, 0));
+        assert!(state.session_changes.is_empty());
+
+        refresh(&mut state, snapshot(3, 5));
+        assert_eq!(state.session_changes["a"].badge(), "+2w~");
+        assert!(!state.session_changes.contains_key("b"));
+        refresh(&mut state, snapshot(2, 5));
+        assert_eq!(state.session_changes["a"].badge(), "+1w~");
+        refresh(&mut state, snapshot(2, 5));
+        assert_eq!(state.session_changes["a"].badge(), "+1w~");
+        refresh(&mut state, snapshot(2, 5));
+        assert!(state.session_changes.is_empty());
+
+        // Activity below the threshold is ignored; badges last as configured.
+        state.behavior.activity_min_secs = 10;
+        state.behavior.change_badge_refreshes = 1;
+        refresh(&mut state, snapshot(2, 14));
+        assert!(state.session_changes.is_empty());
+        refresh(&mut state, snapshot(2, 30));
+        assert_eq!(state.session_changes["a"].badge(), "~");
+        refresh(&mut state, snapshot(2, 30));
+        assert!(state.session_changes.is_empty());
+    }
+
+    #[test]
+    fn closing_the_selected_window_selects_the_one_before_it() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let with_windows = |indices: &[u32]| {
+            let mut s = session_with_panes("a", &["%1"]);
+            s.windows = indices
+                .iter()
+                .map(|&index| TmuxWindow { index, ..s.windows[0].clone() })
+                .collect();
+            vec![s]
+        };
+        let selected = |state: &UIState| state.sessions[0].windows[state.selected_window].index;
+        state.update_sessions(with_windows(&[0, 1, 2, 3]));
+        state.selected_window = 2;
+
+        // A window closing before the selection does not move it.
+        state.update_sessions(with_windows(&[1, 2, 3]));
+        assert_eq!(selected(&state), 2);
+        // The selected window closing lands on its predecessor.
+        state.update_sessions(with_windows(&[1, 3]));
+        assert_eq!(selected(&state), 1);
+        // With no predecessor left, the first window.
+        state.update_sessions(with_windows(&[3]));
+        state.update_sessions(with_windows(&[4, 5]));
+        assert_eq!(selected(&state), 4);
+    }
+
+    #[test]
+    fn capture_range_parses_and_drives_the_preview_capture() {
+        assert_eq!(CaptureRange::parse("  "), Ok(None));
+        let last = CaptureRange::parse("-500").unwrap().unwrap();
+        assert_eq!((last.lines(24), last.label().as_str()), ((-500, 24), "-500.."));
+        let slice = CaptureRange::parse("-1000..-500").unwrap().unwrap();
+        assert_eq!((slice.lines(24), slice.label().as_str()), ((-1000, -500), "-1000..-500"));
+        assert_eq!(
+            CaptureRange::parse("-1000.."),
+            Ok(Some(CaptureRange { start: -1000, end: None }))
+        );
+        assert!(CaptureRange::parse("-5..-10").is_err());
+        assert!(CaptureRange::parse("last").is_err());
+
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![session_with_panes("a", &["%1"])]);
+        state.open_capture_range_popup();
+        state.next_capture_range_preset();
+        assert_eq!(state.input_buffer, "-100");
+        state.apply_capture_range_input().unwrap();
+        state.close_popup();
+        let (_, start, end) = state.get_selected_pane_target_with_capture_range().unwrap();
+        assert_eq!((start, end), (-100, 24));
+
+        state.open_capture_range_popup();
+        assert_eq!(state.input_buffer, "-100..");
+        state.input_buffer = "oops".to_string();
+        assert!(state.apply_capture_range_input().is_err());
+        assert_eq!(state.capture_range.map(|r| r.start), Some(-100));
+    }
+
+    #[test]
+    fn switching_servers_drops_the_old_tree_and_self_pane() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.behavior.tmux_sockets = vec!["work".to_string()];
+        state.update_sessions(vec![session_with_panes("a", &["%1"])]);
+        state.note_nested_tmux(Some("%1".to_string()));
+        assert!(state.preview_pane_is_self());
+
+        state.open_server_picker();
+        assert_eq!(state.server_choice_index, 0);
+        assert_eq!(state.server_choices[..2], [None, Some("work".to_string())]);
+        state.server_choice_down();
+        let server = state.selected_server_choice().unwrap();
+        state.close_popup();
+        state.server_switched(server);
+        assert_eq!(state.tmux_server.as_deref(), Some("work"));
+        assert!(state.sessions.is_empty());
+
+        // `%1` on this server is some other pane, so it is previewed.
+        state.update_sessions(vec![session_with_panes("b", &["%1"])]);
+        assert!(!state.preview_pane_is_self());
+        state.open_server_picker();
+        assert_eq!(state.server_choice_index, 1);
+    }
+
+    #[test]
+    fn preview_hscroll_is_clamped_and_dropped_on_selection_change() {
+        let mut state = UIState::new(Config::default());
+        state.update_sessions(vec![session_with_panes("a", &["%1", "%2"])]);
+        state.focus = Focus::Panes;
+        state.update_pane_content(format!("{}\nshort", "x".repeat(20)), 1.0);
+
+        state.preview_scroll_right();
+        assert_eq!(state.preview_hscroll, PREVIEW_HSCROLL_STEP);
+        for _ in 0..5 {
+            state.preview_scroll_right();
+        }
+        assert_eq!(state.preview_hscroll, 19);
+        state.preview_scroll_left();
+        assert_eq!(state.preview_hscroll, 19 - PREVIEW_HSCROLL_STEP);
+
+        state.sync_preview_scroll();
+        assert_eq!(state.preview_hscroll, 19 - PREVIEW_HSCROLL_STEP);
+        state.tree_move_down();
+        state.sync_preview_scroll();
+        assert_eq!(state.preview_hscroll, 0);
+    }
+
+    #[test]
+    fn preview_vscroll_stops_at_the_top_and_resets_with_the_selection() {
+        let mut state = UIState::new(Config::default());
+        state.update_sessions(vec![session_with_panes("a", &["%1", "%2"])]);
+        state.focus = Focus::Panes;
+        let content: Vec<String> = (0..30).map(|i| format!("line {i}")).collect();
+        state.update_pane_content(content.join("\n"), 1.0);
+        state.preview_rows = 10;
+
+        state.preview_scroll_vertical(state.preview_half_page());
+        assert_eq!(state.preview_vscroll, 5);
+        state.preview_scroll_vertical(100);
+        assert_eq!(state.preview_vscroll, 20);
+        state.preview_scroll_vertical(-1);
+        assert_eq!(state.preview_vscroll, 19);
+        state.preview_scroll_vertical(-100);
+        assert_eq!(state.preview_vscroll, 0);
+
+        state.preview_scroll_vertical(3);
+        state.tree_move_down();
+        state.sync_preview_scroll();
+        assert_eq!(state.preview_vscroll, 0);
     }
 
-    /// Build a session with a single window holding the given panes, each
-    /// described as `(pane_id, index, claude_state, state_since)`.
+    #[test]
+    fn scrolling_back_captures_history_until_it_runs_out() {
+        let mut state = UIState::new(Config::default());
+        state.update_sessions(vec![session_with_panes("a", &["%1"])]);
+        state.focus = Focus::Panes;
+        state.preview_rows = 24;
+        state.update_pane_content(vec!["$"; 24].join("\n"), 1.0);
+        let range = |state: &UIState| {
+            let (_, start, end) = state.get_selected_pane_target_with_capture_range().unwrap();
+            (start, end)
+        };
+        assert_eq!(range(&state), (0, 24));
+
+        // The screen alone has nothing above it, but the scroll goes ahead
+        // and asks for history.
+        state.preview_scroll_vertical(12);
+        assert_eq!(state.preview_vscroll, 12);
+        assert_eq!(range(&state), (-36, 24));
+        // Only 5 lines of history came back: the offset settles on them.
+        state.update_pane_content(vec!["$"; 29].join("\n"), 1.0);
+        assert_eq!(state.preview_vscroll, 5);
+    }
 
     #[test]
-    fn ungrouped_sessions_have_no_headers() {
-        let state = state_with(&["a", "b", "c"], &[]);
-        let rows = state.session_rows();
-        assert_eq!(rows.len(), 3);
-        assert!(rows.iter().all(|r| matches!(r, SessionRow::Session { .. })));
+    fn esc_in_a_prompt_returns_to_the_picker_it_came_from() {
+        let mut state = state_with(&["a"], &[("a", "work")]);
+        state.open_group_session_popup();
+        state.begin_new_group_entry();
+        state.input_char('x');
+        assert!(!state.popup_back());
+        assert_eq!(state.popup_mode, Some(PopupMode::GroupSession));
+        assert_eq!(state.selected_group_choice(), GroupChoice::Existing("work".to_string()));
+        assert!(state.popup_back());
+        assert_eq!(state.popup_mode, None);
+
+        state.open_workspace_picker();
+        state.begin_new_workspace_entry();
+        assert!(!state.popup_back());
+        assert_eq!(state.popup_mode, Some(PopupMode::WorkspacePicker));
+        assert!(state.input_buffer.is_empty());
     }
 
     #[test]
-    fn grouped_sessions_cluster_with_ungrouped_last() {
-        // a, c -> "work"; b ungrouped. Names tie-break ascending within a group.
-        let state = state_with(&["a", "b", "c"], &[("a", "work"), ("c", "work")]);
-        let ordered: Vec<&str> = state.sessions.iter().map(|s| s.name.as_str()).collect();
-        assert_eq!(ordered, vec!["a", "c", "b"]);
+    fn applying_a_workspace_restores_its_view_sort_and_folds() {
+        let mut state = state_with(&["a", "b", "c"], &[("c", "ci")]);
+        state.workspaces = WorkspaceStore::default();
+        let name_at = |state: &UIState, idx: usize| state.sessions[idx].name.clone();
+
+        let a = state.sessions.iter().position(|s| s.name == "a").unwrap();
+        state.selected_session = a;
+        state.save_workspace("dev");
+
+        state.cycle_session_sort();
+        state.cycle_session_sort();
+        state.collapsed_groups.insert(None);
+        state.selected_session = state.sessions.iter().position(|s| s.name == "c").unwrap();
+        state.toggle_view_mode();
+        state.save_workspace(" builds ");
+
+        state.apply_workspace("dev");
+        assert_eq!(state.view_mode, ViewMode::TreeView);
+        assert_eq!(state.session_sort, SessionSort::default());
+        assert!(state.collapsed_groups.is_empty());
+        assert_eq!(name_at(&state, state.selected_session), "a");
+
+        state.apply_workspace("builds");
+        assert_eq!(state.view_mode, ViewMode::MultiPreview);
+        assert_eq!(state.session_sort.name(), "abc");
+        assert_eq!(state.collapsed_groups, HashSet::from([None]));
+        assert_eq!(name_at(&state, state.multi_session), "c");
+
+        // The picker lists both, then the "Save current" entry.
+        state.open_workspace_picker();
+        state.workspace_choice_up();
+        assert_eq!(state.selected_workspace(), None);
+        state.workspace_choice_up();
+        state.delete_selected_workspace();
+        assert_eq!(state.selected_workspace(), None);
+        assert!(state.workspaces.get("builds").is_none());
     }
 
     #[test]
-    fn rows_insert_one_header_per_group() {
-        let state = state_with(
-            &["a", "b", "c"],
-            &[("a", "work"), ("c", "work"), ("b", "play")],
-        );
-        let rows = state.session_rows();
-        // play(b) sorts before work(a,c) alphabetically; ungrouped bucket absent.
-        let labels: Vec<String> = rows
-            .iter()
-            .filter_map(|r| match r {
-                SessionRow::Header { group, count, .. } => {
-                    Some(format!("{}:{}", group.as_deref().unwrap_or("none"), count))
-                }
-                SessionRow::Session { .. } => None,
-            })
-            .collect();
-        assert_eq!(labels, vec!["play:1".to_string(), "work:2".to_string()]);
+    fn follow_tracks_the_most_recently_active_client() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.self_pane = None;
+        let sessions = |busy: &str| {
+            ["a", "b"]
+                .iter()
+                .map(|name| {
+                    let ids = [format!("%{name}0"), format!("%{name}1")];
+                    let mut s = session_with_panes(name, &[&ids[0], &ids[1]]);
+                    s.windows[0].panes[1].active = true;
+                    s.client_activity = if *name == busy { 200 } else { 100 };
+                    s
+                })
+                .collect::<Vec<_>>()
+        };
+        let selected = |state: &UIState| state.sessions[state.selected_session].name.clone();
+
+        state.update_sessions(sessions("a"));
+        state.toggle_follow();
+        assert!(state.follow_active);
+        assert_eq!(selected(&state), "a");
+        assert_eq!(state.selected_pane, 1);
+
+        state.update_sessions(sessions("b"));
+        assert_eq!(selected(&state), "b");
+
+        // Navigating by hand pauses following.
+        state.suspend_follow();
+        state.update_sessions(sessions("a"));
+        assert_eq!(selected(&state), "b");
     }
 
     #[test]
-    fn ungrouped_bucket_gets_a_header_when_mixed() {
-        let state = state_with(&["a", "b"], &[("a", "work")]);
-        let rows = state.session_rows();
-        let has_ungrouped_header = rows.iter().any(|r| {
-            matches!(r, SessionRow::Header { group: None, count, .. } if *count == 1)
-        });
-        assert!(has_ungrouped_header);
+    fn session_tree_serializes_for_dump_json() {
+        let mut s = session_with_panes("a", &["%1"]);
+        s.windows[0].panes[0].claude_state = Some(ClaudeState::Waiting);
+        let json = serde_json::to_value(vec![s]).unwrap();
+        let pane = &json[0]["windows"][0]["panes"][0];
+        assert_eq!(pane["id"], "%1");
+        assert!(pane["width"].is_u64() && pane["height"].is_u64());
+        assert!(pane["current_command"].is_string());
+        assert_eq!(pane["claude_state"], ClaudeState::Waiting.as_token());
     }
 
     #[test]
-    fn folding_hides_members_but_keeps_header() {
-        // work: a, c ; play: b. Select "a" (in work) and fold its group.
-        let mut state = state_with(
-            &["a", "b", "c"],
-            &[("a", "work"), ("c", "work"), ("b", "play")],
-        );
-        let work_idx = state.sessions.iter().position(|s| s.name == "a").unwrap();
-        state.selected_session = work_idx;
-        state.toggle_fold_current_group();
+    fn zoomed_multi_preview_captures_the_selected_windows_active_pane() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut b = session_with_panes("b", &["%3", "%4"]);
+        b.windows[0].panes[1].active = true;
+        state.update_sessions(vec![session_with_panes("a", &["%1"]), b]);
+        state.toggle_view_mode();
+        assert_eq!(state.view_mode, ViewMode::MultiPreview);
+        // The grid shows frames only.
+        assert!(state.visible_capture_targets().is_empty());
+
+        state.multi_session = 1;
+        state.toggle_zoom();
+        let targets = state.visible_capture_targets();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].0, "=b:0.1");
+
+        state.toggle_zoom();
+        assert!(!state.zoomed);
+        state.toggle_zoom();
+        // Leaving for the all-panes grid drops the zoom.
+        state.toggle_view_mode();
+        assert!(!state.zoomed);
+        state.toggle_view_mode();
+        assert_eq!(state.selected_session, 1);
+    }
 
-        let rows = state.session_rows();
-        // No "work" member sessions remain visible, but its header stays
-        // (now marked collapsed); play's member is still shown.
-        let work_collapsed = rows.iter().any(|r| matches!(
-            r,
-            SessionRow::Header { group: Some(g), collapsed: true, .. } if g == "work"
-        ));
-        assert!(work_collapsed);
-        let visible_sessions: Vec<&str> = rows
+    #[test]
+    fn select_target_takes_a_pane_id_or_session_window_pane() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut main = session_with_panes("main", &["%1"]);
+        let mut second = main.windows[0].clone();
+        second.index = 1;
+        second.panes = ["%4", "%5", "%6"]
             .iter()
-            .filter_map(|r| match r {
-                SessionRow::Session { index } => Some(state.sessions[*index].name.as_str()),
-                _ => None,
-            })
+            .enumerate()
+            .map(|(i, id)| pane(id, i as u32))
             .collect();
-        assert_eq!(visible_sessions, vec!["b"]);
-        // Selection parks on the folded group's head, so the cursor sits on the
-        // (collapsed) "work" header and can re-open it.
-        assert!(state.selection_on_folded_header());
-        assert_eq!(state.sessions[state.selected_session].group.as_deref(), Some("work"));
+        main.windows.push(second);
+        state.update_sessions(vec![session_with_panes("other", &["%2"]), main]);
+        let selected = |state: &UIState| state.selected_pane_id();
 
-        // Toggling again from the folded header re-expands the group — this is
-        // the regression that previously had no way to recover.
-        state.toggle_fold_current_group();
-        let rows = state.session_rows();
-        let names: Vec<&str> = rows
-            .iter()
-            .filter_map(|r| match r {
-                SessionRow::Session { index } => Some(state.sessions[*index].name.as_str()),
-                _ => None,
-            })
-            .collect();
-        assert!(names.contains(&"a") && names.contains(&"c"));
-        assert!(!state.selection_on_folded_header());
+        assert!(state.select_target("main:1.2"));
+        assert_eq!(selected(&state).as_deref(), Some("%6"));
+        assert_eq!((state.view_mode, state.focus), (ViewMode::TreeView, Focus::Panes));
+
+        assert!(state.select_target("%5"));
+        assert_eq!(selected(&state).as_deref(), Some("%5"));
+
+        // Unknown targets leave the selection where it was.
+        for missing in ["main:1.7", "main:3", "nope:0.0", "%99", "main:x"] {
+            assert!(!state.select_target(missing), "{missing}");
+        }
+        assert_eq!(selected(&state).as_deref(), Some("%5"));
     }
 
     #[test]
-    fn navigation_lands_on_folded_group_then_reopens() {
-        let mut state = state_with(
-            &["a", "b", "c"],
-            &[("a", "work"), ("c", "work"), ("b", "play")],
-        );
-        // Order is play(b), work(a,c). Fold work (selection parks on work head).
+    fn moving_a_window_offers_the_other_sessions_and_follows_it() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![
+            session_with_panes("a", &["%1", "%2"]),
+            session_with_panes("b", &["%3"]),
+        ]);
         state.selected_session = state.sessions.iter().position(|s| s.name == "a").unwrap();
-        state.toggle_fold_current_group();
-        // From the visible "b", moving down stops on the folded work header
-        // rather than skipping it entirely.
-        state.selected_session = state.sessions.iter().position(|s| s.name == "b").unwrap();
-        state.tree_move_down();
-        assert!(state.selection_on_folded_header());
-        assert_eq!(state.sessions[state.selected_session].group.as_deref(), Some("work"));
-        // And `za` there expands it back.
-        state.toggle_fold_current_group();
-        assert!(!state.selection_on_folded_header());
+        state.selected_window = 0;
+        state.sessions[state.selected_session].windows[0].panes[1].active = true;
+        state.focus = Focus::Windows;
+
+        state.open_move_window_picker();
+        assert_eq!(state.popup_mode, Some(PopupMode::MoveWindow));
+        assert_eq!(state.move_choices, ["b"]);
+        assert_eq!(
+            state.get_move_window_info(),
+            Some(("%2".to_string(), "=b".to_string()))
+        );
+        state.close_popup();
+
+        // The next listing has the window in `b`.
+        state.update_sessions(vec![
+            session_with_panes("a", &["%1"]),
+            session_with_panes("b", &["%3", "%2"]),
+        ]);
+        state.select_moved_window("%2");
+        assert_eq!(state.sessions[state.selected_session].name, "b");
+        assert_eq!(state.focus, Focus::Windows);
+
+        // With a single session there is nowhere to go.
+        state.update_sessions(vec![session_with_panes("b", &["%3"])]);
+        state.open_move_window_picker();
+        assert_eq!(state.popup_mode, None);
+        assert!(state.status_message.is_some());
     }
 
     #[test]
-    fn fold_is_noop_without_groups() {
-        let mut state = state_with(&["a", "b"], &[]);
-        state.toggle_fold_current_group();
-        let rows = state.session_rows();
-        // No headers, all sessions still visible.
-        assert_eq!(rows.len(), 2);
-        assert!(rows.iter().all(|r| matches!(r, SessionRow::Session { .. })));
+    fn top_and_bottom_jump_to_the_ends_of_the_focused_list() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.update_sessions(vec![
+            session_with_panes("a", &["%1", "%2", "%3"]),
+            session_with_panes("b", &["%4"]),
+            session_with_panes("c", &["%5"]),
+        ]);
+
+        state.focus = Focus::Sessions;
+        state.tree_move_bottom();
+        assert_eq!(state.selected_session, 2);
+        state.tree_move_top();
+        assert_eq!(state.selected_session, 0);
+
+        let a = state.sessions.iter().position(|s| s.name == "a").unwrap();
+        state.selected_session = a;
+        state.focus = Focus::Panes;
+        state.tree_move_bottom();
+        assert_eq!(state.selected_pane, 2);
+        assert_eq!(state.pane_list_state.selected(), Some(2));
+        state.tree_move_top();
+        assert_eq!(state.selected_pane, 0);
+
+        state.view_mode = ViewMode::MultiPreview;
+        state.multi_move_last();
+        assert_eq!(state.multi_session, *state.multi_visible_sessions().last().unwrap());
     }
 
     #[test]
-    fn assigning_group_updates_store_and_order() {
-        let mut state = state_with(&["a", "b"], &[]);
-        state.selected_session = 1; // "b"
-        state.assign_selected_group(Some("work".to_string()));
-        assert_eq!(state.groups.group_of("b"), Some("work".to_string()));
-        // "b" is now grouped and clusters above the ungrouped "a".
-        let ordered: Vec<&str> = state.sessions.iter().map(|s| s.name.as_str()).collect();
-        assert_eq!(ordered, vec!["b", "a"]);
-        // Selection still tracks "b" after the reorder.
-        assert_eq!(state.sessions[state.selected_session].name, "b");
+    fn digits_jump_to_the_nth_visible_session() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.groups.set("b", Some("work"));
+        state.groups.set("c", Some("work"));
+        state.update_sessions(vec![
+            session_with_panes("a", &["%1"]),
+            session_with_panes("b", &["%2"]),
+            session_with_panes("c", &["%3"]),
+            session_with_panes("d", &["%4"]),
+        ]);
+        state.selected_window = 1;
+
+        state.jump_to_session(1);
+        assert_eq!(state.selected_session, 1);
+        assert_eq!(state.selected_window, 0);
+
+        // A folded group counts as the one row it shows.
+        let folded = state.sessions[1].group.clone();
+        state.collapsed_groups.insert(folded.clone());
+        let stops: Vec<usize> = (0..4).filter(|&i| state.is_cursor_stop(i)).collect();
+        assert_eq!(stops.len(), 3);
+        state.jump_to_session(2);
+        assert_eq!(state.selected_session, stops[2]);
+
+        // Past the last session nothing moves.
+        state.jump_to_session(9);
+        assert_eq!(state.selected_session, stops[2]);
+
+        state.collapsed_groups.clear();
+        state.view_mode = ViewMode::MultiPreview;
+        state.jump_to_session(3);
+        assert_eq!(state.multi_session, state.multi_visible_sessions()[3]);
     }
 
     #[test]
-    fn group_popup_lists_existing_groups_and_highlights_current() {
-        let state = state_with(
-            &["a", "b", "c"],
-            &[("a", "work"), ("b", "personal"), ("c", "work")],
-        );
-        // Sorted, deduplicated existing groups.
-        assert_eq!(state.groups.group_names(), vec!["personal", "work"]);
+    fn command_palette_filters_actions_by_name() {
+        let mut state = UIState::new(Config::default());
+        state.open_command_palette();
+        assert_eq!(state.palette_matches().len(), 43);
+
+        state.input_buffer = "kill s".to_string();
+        state.palette_query_changed();
+        let matches = state.palette_matches();
+        assert_eq!(matches[0], (Action::KillOtherSessions, "A-x".to_string()));
+        assert_eq!(matches[1], (Action::KillSession, "C-x".to_string()));
+        state.palette_down();
+        assert_eq!(state.selected_palette_action(), Some(Action::KillSession));
+
+        state.input_buffer = "sync".to_string();
+        state.palette_down();
+        assert_eq!(state.selected_palette_action(), Some(Action::SyncPanes));
+
+        state.input_buffer = "zzz".to_string();
+        state.palette_query_changed();
+        assert_eq!(state.selected_palette_action(), None);
+    }
 
-        let mut state = state;
-        state.selected_session = state.sessions.iter().position(|s| s.name == "b").unwrap();
-        state.open_group_session_popup();
-        assert_eq!(state.popup_mode, Some(PopupMode::GroupSession));
-        // "b" is in "personal", so that entry starts highlighted.
-        assert_eq!(
-            state.selected_group_choice(),
-            GroupChoice::Existing("personal".to_string())
-        );
-        // Entries: 2 groups + Ungrouped + New.
-        assert_eq!(state.group_choice_count(), 4);
+    #[test]
+    fn multi_grid_reads_every_window_left_to_right() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        let mut a = session_with_panes("a", &["%1"]);
+        for i in 1..3 {
+            let mut w = a.windows[0].clone();
+            w.index = i;
+            w.panes = vec![pane(&format!("%{}", i + 10), 0)];
+            a.windows.push(w);
+        }
+        state.update_sessions(vec![a, session_with_panes("b", &["%2"])]);
+        state.view_mode = ViewMode::MultiPreview;
+        let cells = state.multi_grid_cells();
+        assert_eq!(cells.len(), 4);
+
+        state.adjust_multi_columns(-1);
+        assert_eq!(state.layout.multi_columns, 0);
+        for _ in 0..10 {
+            state.adjust_multi_columns(1);
+        }
+        assert_eq!(state.layout.multi_columns, MAX_MULTI_COLUMNS);
+        state.layout.multi_columns = 2;
+
+        (state.multi_session, state.multi_window) = cells[0];
+        state.multi_grid_move(2);
+        assert_eq!((state.multi_session, state.multi_window), cells[2]);
+        // No row below the last one.
+        state.multi_grid_move(2);
+        assert_eq!((state.multi_session, state.multi_window), cells[2]);
+        state.multi_grid_move(1);
+        assert_eq!((state.multi_session, state.multi_window), cells[3]);
+        state.multi_grid_edge(false);
+        assert_eq!((state.multi_session, state.multi_window), cells[0]);
     }
 
     #[test]
-    fn group_popup_defaults_to_ungrouped_for_ungrouped_session() {
-        let mut state = state_with(&["a", "b"], &[("b", "work")]);
-        state.selected_session = state.sessions.iter().position(|s| s.name == "a").unwrap();
-        state.open_group_session_popup();
-        // Index sits on the "Ungrouped" entry, just past the single group.
-        assert_eq!(state.selected_group_choice(), GroupChoice::Ungrouped);
+    fn all_panes_grid_tiles_every_pane() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.self_pane = None;
+        let mut a = session_with_panes("a", &["%1", "%2", "%3"]);
+        a.windows[0].panes[1].active = true;
+        state.update_sessions(vec![a, session_with_panes("b", &["%4"])]);
+
+        // Double-space cycles Tree -> Multi -> AllPanes -> Tree.
+        state.toggle_view_mode();
+        assert_eq!(state.view_mode, ViewMode::MultiPreview);
+        state.toggle_view_mode();
+        assert_eq!(state.view_mode, ViewMode::AllPanes);
+        assert_eq!(state.multi_pane, 1);
+        assert_eq!(state.get_enter_target().as_deref(), Some("=a:0.1"));
+
+        let cells = state.all_pane_cells();
+        assert_eq!(cells.len(), 4);
+        assert_eq!(state.all_panes_columns(), 2);
+        let targets: Vec<String> =
+            state.visible_capture_targets().into_iter().map(|t| t.0).collect();
+        assert_eq!(targets, ["%1", "%2", "%3", "%4"]);
+
+        // Below pane 1 is the other session's pane.
+        state.all_panes_move(2);
+        assert_eq!(state.get_current_target().as_deref(), Some("=b:0.0"));
+        state.all_panes_move(2);
+        assert_eq!((state.multi_session, state.multi_window, state.multi_pane), cells[3]);
+        state.all_panes_edge(false);
+        assert_eq!(state.get_current_target().as_deref(), Some("=a:0.0"));
+
+        // Tiles are captured by id while the grid shows, and dropped after.
+        assert_eq!(state.update_monitor_content("%3", "make\n", 1.0), Some(true));
+        state.all_panes_move(2);
+        state.toggle_view_mode();
+        assert_eq!(state.view_mode, ViewMode::TreeView);
+        assert_eq!(state.get_selected_pane_target().as_deref(), Some("=a:0.2"));
+        assert!(state.monitor_content.is_empty());
+        assert_eq!(state.update_monitor_content("%3", "make\n", 1.0), None);
     }
 
     #[test]
-    fn group_choice_navigation_wraps_and_reaches_new() {
-        let mut state = state_with(&["a"], &[("a", "work")]);
-        state.open_group_session_popup();
-        // Entries: ["work", Ungrouped, New]. Starts on "work".
-        assert_eq!(
-            state.selected_group_choice(),
-            GroupChoice::Existing("work".to_string())
-        );
-        state.group_choice_up(); // wraps to last entry
-        assert_eq!(state.selected_group_choice(), GroupChoice::New);
-        state.group_choice_down(); // wraps back to first
-        assert_eq!(
-            state.selected_group_choice(),
-            GroupChoice::Existing("work".to_string())
-        );
-        state.group_choice_down();
-        assert_eq!(state.selected_group_choice(), GroupChoice::Ungrouped);
+    fn wrapped_preview_scrolls_by_rows() {
+        let mut state = UIState::new(Config::default());
+        state.update_sessions(vec![session_with_panes("a", &["%1"])]);
+        let content: Vec<String> = (0..30).map(|i| format!("line {i:02}")).collect();
+        state.update_pane_content(content.join("\n"), 1.0);
+        (state.preview_rows, state.preview_cols) = (10, 4);
+
+        state.toggle_preview_wrap();
+        // Every line takes two rows.
+        state.preview_scroll_vertical(100);
+        assert_eq!(state.preview_vscroll, 50);
+        state.preview_scroll_right();
+        assert_eq!(state.preview_hscroll, 0);
+
+        state.toggle_preview_wrap();
+        assert_eq!(state.preview_vscroll, 20);
     }
 
     #[test]
-    fn input_handles_multibyte_chars_without_panic() {
+    fn alerts_only_keeps_windows_with_pending_alerts() {
         let mut state = UIState::new(Config::default());
-        // 日本語を複数文字入力（旧実装ではバイト境界パニックしていた）
-        state.input_char('あ');
-        state.input_char('い');
-        state.input_char('う');
-        assert_eq!(state.input_buffer, "あいう");
-        assert_eq!(state.input_cursor, 3);
+        state.groups = GroupStore::default();
+        let mut a = session_with_panes("a", &["%1"]);
+        let mut bell = a.windows[0].clone();
+        (bell.index, bell.alerts) = (1, "!".to_string());
+        a.windows.push(bell);
+        let listing = vec![a, session_with_panes("b", &["%2"])];
+        state.update_sessions(listing.clone());
+
+        state.toggle_alerts_only();
+        assert_eq!(state.sessions.len(), 1);
+        assert_eq!(state.sessions[0].windows.len(), 1);
+        assert_eq!(state.sessions[0].windows[0].index, 1);
+        // Later refreshes are filtered too, without badging the difference.
+        state.track_session_changes(&listing);
+        state.update_sessions(listing.clone());
+        assert_eq!(state.sessions[0].windows.len(), 1);
+        assert!(state.session_changes.is_empty());
+
+        state.toggle_alerts_only();
+        state.track_session_changes(&listing);
+        state.update_sessions(listing);
+        assert_eq!(state.sessions.len(), 2);
+        assert!(state.session_changes.is_empty());
     }
 
     #[test]
-    fn input_cursor_movement_and_editing_with_multibyte() {
+    fn window_sort_cycles_and_keeps_the_selection() {
         let mut state = UIState::new(Config::default());
-        for c in "あいう".chars() {
-            state.input_char(c);
+        state.groups = GroupStore::default();
+        let mut a = session_with_panes("a", &["%1"]);
+        a.windows[0].name = "zsh".to_string();
+        for (index, name, activity) in [(1, "build", 9), (2, "Editor", 5)] {
+            let mut w = a.windows[0].clone();
+            (w.index, w.name, w.activity) = (index, name.to_string(), activity);
+            a.windows.push(w);
         }
-        // 左へ2つ移動 → カーソルは「い」の前
-        state.input_move_left();
-        state.input_move_left();
-        assert_eq!(state.input_cursor, 1);
-        // カーソル位置に「ん」を挿入
-        state.input_char('ん');
-        assert_eq!(state.input_buffer, "あんいう");
-        assert_eq!(state.input_cursor, 2);
-        // backspace で「ん」を削除
-        state.input_backspace();
-        assert_eq!(state.input_buffer, "あいう");
-        assert_eq!(state.input_cursor, 1);
-        // delete でカーソル位置の「い」を削除
-        state.input_delete();
-        assert_eq!(state.input_buffer, "あう");
-        assert_eq!(state.input_cursor, 1);
+        state.update_sessions(vec![a]);
+        let names = |state: &UIState| -> Vec<String> {
+            state.sessions[0].windows.iter().map(|w| w.name.clone()).collect()
+        };
+        assert_eq!(names(&state), ["build", "Editor", "zsh"]);
+        state.selected_window = 1;
+
+        state.cycle_window_sort();
+        assert_eq!(state.window_sort, WindowSort::Index);
+        assert_eq!(names(&state), ["zsh", "build", "Editor"]);
+        assert_eq!(state.selected_window, 2);
+        state.cycle_window_sort();
+        assert_eq!(names(&state), ["build", "Editor", "zsh"]);
+        // A refresh keeps the chosen order.
+        let listing = state.sessions.clone();
+        state.update_sessions(listing);
+        assert_eq!(state.window_sort, WindowSort::Name);
+        assert_eq!(state.sessions[0].windows[state.selected_window].name, "Editor");
     }
 
     #[test]
-    fn input_move_end_uses_char_count() {
+    fn attached_sort_lists_attached_sessions_first() {
         let mut state = UIState::new(Config::default());
-        for c in "あい".chars() {
-            state.input_char(c);
-        }
-        state.input_move_home();
-        assert_eq!(state.input_cursor, 0);
-        state.input_move_end();
-        assert_eq!(state.input_cursor, 2);
+        state.groups = GroupStore::default();
+        let mut b = session("b");
+        b.attached = true;
+        state.update_sessions(vec![session("a"), b, session("c")]);
+        state.session_sort = SessionSort::from_name("abc_asc");
+        state.cycle_session_sort();
+        assert_eq!(state.sessions[0].name, "b");
+        assert_eq!(state.session_sort.label(), "attached↓");
     }
 
     #[test]
-    fn input_char_limited_caps_char_count() {
+    fn reloading_the_config_applies_it_or_reports_why_not() {
         let mut state = UIState::new(Config::default());
-        for _ in 0..40 {
-            state.input_char_limited('a', SESSION_NAME_MAX_LEN);
-        }
-        assert_eq!(state.input_buffer.chars().count(), SESSION_NAME_MAX_LEN);
+        state.groups = GroupStore::default();
+        let config: Config =
+            toml::from_str("[preview]\ninterval = 1000\n[keybindings]\nsort = \"o\"\n").unwrap();
+        state.reload_config(Ok(config));
+        assert_eq!(state.status_message.as_deref(), Some("Config reloaded"));
+        assert_eq!(state.interval, Duration::from_millis(1000));
+        assert_eq!(state.keybindings.sort, vec![crate::config::parse_key("o").unwrap()]);
+
+        // `--interval` keeps its value across reloads.
+        state.interval_from_cli = true;
+        state.interval = Duration::from_millis(50);
+        state.reload_config(Ok(Config::default()));
+        assert_eq!(state.interval, Duration::from_millis(50));
+
+        // A broken file changes nothing and says where it broke, on one line.
+        state.keybindings.sort = vec![crate::config::parse_key("o").unwrap()];
+        let err = toml::from_str::<Config>("[preview]\nwrap = yes\n").unwrap_err();
+        state.reload_config(Err(color_eyre::eyre::eyre!("invalid config c.toml:\n{err}")));
+        let note = state.status_message.as_deref().unwrap();
+        assert!(note.starts_with("Config not reloaded: invalid config c.toml: TOML parse error"));
+        assert!(!note.contains('\n') && !note.contains(" | "), "{note}");
+        assert_eq!(state.keybindings.sort, vec![crate::config::parse_key("o").unwrap()]);
     }
 
     #[test]
-    fn input_char_limited_counts_chars_not_bytes() {
+    fn history_lines_reach_back_into_scrollback() {
         let mut state = UIState::new(Config::default());
-        // マルチバイト文字でもバイト長ではなく文字数で制限される
-        for _ in 0..40 {
-            state.input_char_limited('あ', SESSION_NAME_MAX_LEN);
-        }
-        assert_eq!(state.input_buffer.chars().count(), SESSION_NAME_MAX_LEN);
+        state.groups = GroupStore::default();
+        state.preview.history_lines = 500;
+        state.update_sessions(vec![session_with_panes("a", &["%1"])]);
+        state.focus = Focus::Panes;
+        state.preview_rows = 24;
+        let range = |state: &UIState| {
+            let (_, start, end) = state.get_selected_pane_target_with_capture_range().unwrap();
+            (start, end)
+        };
+        assert_eq!(range(&state), (-500, 24));
+
+        // Scrolling back further than that asks for more.
+        state.update_pane_content(vec!["$"; 1024].join("\n"), 1.0);
+        state.preview_scroll_vertical(600);
+        assert_eq!(range(&state), (-624, 24));
+
+        // A set capture range wins, and huge values are capped.
+        state.preview_vscroll = 0;
+        state.preview.history_lines = usize::MAX;
+        assert_eq!(range(&state), (-(MAX_HISTORY_LINES as i32), 24));
+        state.capture_range = CaptureRange::parse("-100").unwrap();
+        assert_eq!(range(&state).0, -100);
+    }
+
+    #[test]
+    fn all_panes_capture_caps_keep_the_selected_and_attached_windows() {
+        let mut state = UIState::new(Config::default());
+        state.groups = GroupStore::default();
+        state.self_pane = None;
+        let mut a = session_with_panes("a", &["%1"]);
+        a.attached = true;
+        let mut b = session_with_panes("b", &["%2"]);
+        b.windows[0].activity = 5;
+        let mut c = session_with_panes("c", &["%3"]);
+        c.windows[0].activity = 9;
+        state.update_sessions(vec![a, b, c]);
+        state.toggle_view_mode();
+        state.toggle_view_mode();
+        assert_eq!(state.view_mode, ViewMode::AllPanes);
+        let select = |state: &mut UIState, name: &str| {
+            state.multi_session = state.sessions.iter().position(|s| s.name == name).unwrap();
+            (state.multi_window, state.multi_pane) = (0, 0);
+        };
+        let targets = |state: &UIState| {
+            let mut ids: Vec<String> =
+                state.visible_capture_targets().into_iter().map(|t| t.0).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(targets(&state), ["%1", "%2", "%3"]);
+
+        // The selected window, then the attached session's, beat the more
+        // recently active one.
+        state.preview.max_capture_windows = 2;
+        select(&mut state, "b");
+        assert_eq!(targets(&state), ["%1", "%2"]);
+        // Selecting a tile past the cap captures it.
+        select(&mut state, "c");
+        assert_eq!(targets(&state), ["%1", "%3"]);
+
+        state.preview.max_capture_windows = 0;
+        state.preview.max_capture_sessions = 1;
+        assert_eq!(targets(&state), ["%3"]);
     }
 }
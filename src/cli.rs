@@ -17,6 +17,22 @@ pub struct Cli {
     /// Preview refresh interval in milliseconds (overrides the config file)
     #[arg(short, long)]
     pub interval: Option<u64>,
+    /// Watch-only mode: disable every command that changes tmux (create,
+    /// rename, kill, send-keys, switch-client), leaving navigation and preview
+    #[arg(long)]
+    pub read_only: bool,
+    /// Scope the deck to one session: only its windows and panes are listed,
+    /// and the sessions list is hidden
+    #[arg(long, value_name = "NAME")]
+    pub session: Option<String>,
+    /// Redraw after every event, even when nothing on screen changed (for
+    /// debugging rendering)
+    #[arg(long)]
+    pub force_redraw: bool,
+    /// Print the session tree tmux-deck sees (sessions, windows and panes) as
+    /// JSON and exit, without starting the TUI
+    #[arg(long)]
+    pub dump_json: bool,
     /// Subcommand (omit to launch the interactive TUI)
     #[command(subcommand)]
     pub command: Option<Command>,
@@ -29,6 +45,31 @@ pub enum Command {
         #[command(subcommand)]
         action: HookAction,
     },
+    /// Print a pane's full scrollback with its colours as ANSI escapes (or
+    /// HTML), for rendering terminal output into images or documents.
+    Capture {
+        /// Pane to capture (e.g. "session:window.pane" or "%123"); defaults to
+        /// the current pane.
+        target: Option<String>,
+        /// Emit a self-contained HTML `<pre>` block instead of raw ANSI.
+        #[arg(long)]
+        html: bool,
+        /// Write to this file instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Create a session from a tmuxp-style YAML file (session_name, windows,
+    /// layout, panes with shell_command, shell_command_before).
+    Load {
+        /// The session file; a `.toml` file is read as written by `save`.
+        file: PathBuf,
+    },
+    /// Print a running session as a TOML template that `load` recreates it
+    /// from: window names, layouts and pane directories.
+    Save {
+        /// The session to save.
+        session: String,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -6,9 +6,9 @@
 //! per-state Claude hook markers (and, in future, Codex markers), the panel
 //! layout and a handful of behavioural toggles.
 //!
-//! Loading is best-effort, mirroring [`crate::group::GroupStore`]: a missing
-//! file yields defaults, and an unreadable / malformed file logs a warning and
-//! falls back to defaults so a broken config can never stop the app starting.
+//! A missing file yields defaults, the zero-config case. A file that exists
+//! but cannot be read or parsed stops the app with the parse error instead:
+//! falling back to defaults would quietly drop every other setting in it.
 //!
 //! Resolution order for the file path:
 //!   1. `--config <path>` on the CLI (`~` is expanded)
@@ -18,14 +18,16 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use color_eyre::Result;
+use color_eyre::eyre::eyre;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use directories::ProjectDirs;
 use ratatui::style::Color;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde::de::{self, Deserializer};
 use tracing::{debug, warn};
 
-use crate::app::{SessionSort, SessionSortKey, SortDirection, ViewMode};
+use crate::app::{SessionSort, TmuxWindow, ViewMode};
 
 // =============================================================================
 // Top-level config
@@ -41,36 +43,33 @@ pub struct Config {
     pub layout: LayoutConfig,
     pub behavior: BehaviorConfig,
     pub agents: AgentsConfig,
+    /// Named session layouts offered by the template picker.
+    pub templates: Vec<SessionTemplate>,
+    /// User commands run on deck events.
+    pub events: EventsConfig,
 }
 
 impl Config {
     /// Load the config, preferring an explicit `--config` path, then the XDG
-    /// config dir, then built-in defaults. Never fails: any error degrades to
-    /// the default config with a warning.
-    pub fn load(cli_path: Option<&Path>) -> Self {
+    /// config dir, then built-in defaults. Only a missing file falls back to
+    /// the defaults; an unreadable or malformed one is an error.
+    pub fn load(cli_path: Option<&Path>) -> Result<Self> {
         let path = cli_path
             .map(expand_tilde)
             .or_else(Self::default_path);
         let Some(path) = path else {
-            return Self::default();
+            return Ok(Self::default());
         };
         match std::fs::read_to_string(&path) {
-            Ok(contents) => match toml::from_str::<Config>(&contents) {
-                Ok(cfg) => {
-                    debug!("loaded config from {}", path.display());
-                    cfg
-                }
-                Err(e) => {
-                    warn!("failed to parse config {}: {e}; using defaults", path.display());
-                    Self::default()
-                }
-            },
-            // Missing file is the common zero-config case: silently use defaults.
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
-            Err(e) => {
-                warn!("failed to read config {}: {e}; using defaults", path.display());
-                Self::default()
+            Ok(contents) => {
+                let cfg = toml::from_str::<Config>(&contents)
+                    .map_err(|e| eyre!("invalid config {}:\n{e}", path.display()))?;
+                debug!("loaded config from {}", path.display());
+                Ok(cfg)
             }
+            // Missing file is the common zero-config case: silently use defaults.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(eyre!("failed to read config {}: {e}", path.display())),
         }
     }
 
@@ -94,12 +93,150 @@ fn expand_tilde(p: &Path) -> PathBuf {
 // [preview]
 // =============================================================================
 
-#[derive(Debug, Clone, Default, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct PreviewConfig {
     /// Preview refresh interval in milliseconds. `None` lets the CLI flag / the
     /// built-in default (300ms) win, so the precedence is CLI > config > 300.
     pub interval: Option<u64>,
+    /// Minimum fraction (0.0–1.0) of printable bytes a capture needs to be
+    /// previewed. Captures below it show "(binary output)" instead of a screen
+    /// full of replacement characters. `0.0` disables the check; values
+    /// outside 0.0–1.0 are a config error.
+    #[serde(deserialize_with = "de_fraction")]
+    pub binary_threshold: f64,
+    /// Vertical placement of a capture shorter than the preview area.
+    pub align: PreviewAlign,
+    /// Capture the TreeView preview with `-J`, joining soft-wrapped lines.
+    /// Turn off to see the pane's literal wrapping, e.g. of a long prompt.
+    pub join_wrapped: bool,
+    /// Draw a border around each preview. Without one a preview keeps only its
+    /// title row, coloured the way the border would have been, and gets the
+    /// reclaimed rows and columns for content.
+    pub borders: bool,
+    /// Wrap long lines in the TreeView preview instead of cutting them at the
+    /// right edge. Off by default, so the preview lays out like the pane.
+    pub wrap: bool,
+    /// Lines of scrollback the TreeView preview captures above the visible
+    /// screen, so scrolling back has history to show at once. `0` captures
+    /// the screen only; values are capped at 10000.
+    pub history_lines: usize,
+    /// Most sessions whose panes the all-panes grid captures per tick, `0`
+    /// for no cap. Tiles past the cap show a placeholder until selected.
+    pub max_capture_sessions: usize,
+    /// Most windows whose panes the all-panes grid captures per tick, `0`
+    /// for no cap.
+    pub max_capture_windows: usize,
+    /// Colours the terminal can show. Captured truecolor styles are mapped
+    /// down to the nearest palette colour below `truecolor`. `None` detects it
+    /// from `$COLORTERM`.
+    pub color_depth: Option<ColorDepth>,
+}
+
+/// How many colours the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ColorDepth {
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Palette256,
+    #[serde(rename = "16")]
+    Palette16,
+}
+
+impl ColorDepth {
+    /// `truecolor` when `$COLORTERM` advertises it (`truecolor` / `24bit`),
+    /// else `256`.
+    pub fn detect() -> Self {
+        match std::env::var("COLORTERM").as_deref() {
+            Ok("truecolor" | "24bit") => ColorDepth::TrueColor,
+            _ => ColorDepth::Palette256,
+        }
+    }
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            interval: None,
+            binary_threshold: 0.7,
+            align: PreviewAlign::Top,
+            join_wrapped: true,
+            borders: true,
+            wrap: false,
+            history_lines: 0,
+            max_capture_sessions: 0,
+            max_capture_windows: 0,
+            color_depth: None,
+        }
+    }
+}
+
+/// Where an underfilled preview sits vertically. Trailing blank lines of the
+/// capture do not count as content for `center` / `bottom`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewAlign {
+    #[default]
+    Top,
+    Center,
+    Bottom,
+}
+
+impl PreviewAlign {
+    /// Blank rows to insert above `content` lines in an area `height` tall.
+    pub fn offset(self, content: usize, height: usize) -> usize {
+        let spare = height.saturating_sub(content);
+        match self {
+            Self::Top => 0,
+            Self::Center => spare / 2,
+            Self::Bottom => spare,
+        }
+    }
+}
+
+// =============================================================================
+// [[templates]]
+// =============================================================================
+
+/// A named session shape instantiated from the template picker, which only
+/// asks for the new session's name.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionTemplate {
+    pub name: String,
+    /// One-line description shown next to the name in the picker.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// Start directory for every window and pane (`~` is expanded). Unset
+    /// leaves it to tmux.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Windows to create, in order. An empty list yields a bare session.
+    pub windows: Vec<TemplateWindow>,
+}
+
+impl SessionTemplate {
+    /// [`Self::root`] with a leading `~` expanded.
+    pub fn root_dir(&self) -> Option<String> {
+        self.root
+            .as_deref()
+            .map(|r| expand_tilde(Path::new(r)).to_string_lossy().into_owned())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TemplateWindow {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// One command per pane. The first runs in the window's initial pane, each
+    /// further entry splits off a new one; an empty string leaves a plain shell.
+    /// A command of several lines is typed one line at a time.
+    pub panes: Vec<String>,
+    /// Layout applied once every pane exists (`tiled`, `main-vertical`, …).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
 }
 
 // =============================================================================
@@ -135,15 +272,96 @@ impl Default for AgentsConfig {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct BehaviorConfig {
-    /// View shown on startup: `tree` or `multi`.
+    /// View shown on startup: `tree`, `multi` or `panes`.
     pub default_view: String,
-    /// Initial session sort: `recent`, `recent_asc`, `abc`, `abc_asc`.
+    /// Initial session sort: `recent`, `recent_asc`, `abc`, `abc_asc`,
+    /// `attached`, `attached_asc`.
     pub default_sort: String,
+    /// Initial order of the Windows list: `activity`, `index` or `name`.
+    pub window_sort: WindowSort,
     /// Window (ms) within which a second Space press toggles the view mode.
     pub double_space_ms: u64,
     /// Whether selecting a session/window (Enter) exits tmux-deck after the
     /// tmux client switch. When false, the deck stays open.
     pub exit_on_switch: bool,
+    /// Per-view override of what Enter does; unset views follow
+    /// `exit_on_switch`.
+    pub enter: EnterConfig,
+    /// tmux layout the even-layout action (`=`) applies: `tiled`,
+    /// `even-horizontal`, `even-vertical`, …
+    pub even_layout: String,
+    /// Command the open-in-terminal action (`o`) runs to attach in a new OS
+    /// window, e.g. `alacritty -e tmux attach -t {target}`. Split on
+    /// whitespace (no shell); `{target}` becomes the selection. Unset means
+    /// the action switches in place.
+    pub terminal_command: Option<String>,
+    /// tmux key names sent after the text of the send-keys popup, e.g.
+    /// `["Enter"]` (the default), `["M-Enter"]`, or `[]` to submit nothing.
+    #[serde(deserialize_with = "de_tmux_keys")]
+    pub submit_keys: Vec<String>,
+    /// While the TreeView's windows list has focus, preview each window's
+    /// active pane instead of its first pane.
+    pub window_preview_active_pane: bool,
+    /// Seconds a session's last-activity time must advance between re-lists
+    /// before its change badge shows activity (`~`).
+    pub activity_min_secs: i64,
+    /// Re-lists a session's change badge stays up after the change.
+    pub change_badge_refreshes: u8,
+    /// Extra tmux servers the server picker offers besides the default one
+    /// and the sockets found in the tmux tmpdir: socket names (`-L`) or
+    /// paths (`-S`).
+    pub tmux_sockets: Vec<String>,
+    /// Pause the preview refresh while a popup or the send-keys input is open.
+    /// Off keeps the preview live, e.g. to watch output while composing.
+    pub pause_on_input: bool,
+    /// Cells each `resize_pane_*` press moves the pane edge by.
+    pub resize_step: u16,
+    /// tmux format variables the refresh leaves out of its listings (e.g.
+    /// `pane_pid`), for old tmux versions or to save work on a large server.
+    /// Their columns read empty and fall back to defaults.
+    pub skip_fields: Vec<String>,
+    /// Make bulk kills (`X` in the dead panes list, `kill_other_sessions`)
+    /// wait for `yes` to be typed instead of going ahead on a single key.
+    pub require_typed_confirmation: bool,
+    /// Milliseconds after a `M-Enter` send before the pane is captured again
+    /// to show what the command printed.
+    pub inspect_delay_ms: u64,
+    /// Where `yank` copies the preview's text.
+    pub yank_to: YankTarget,
+}
+
+/// Where [`Action::Yank`] puts the text it copies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum YankTarget {
+    /// The system clipboard, set by the terminal through OSC 52.
+    #[default]
+    Clipboard,
+    /// A tmux paste buffer (`load-buffer`), pasted with `prefix ]`.
+    Tmux,
+}
+
+/// What Enter does on a tmux target in TreeView / MultiPreview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnterAction {
+    /// `switch-client` to the selection, then exit tmux-deck.
+    SwitchExit,
+    /// `switch-client` to the selection and keep the deck open.
+    SwitchStay,
+    /// Drill in: sessions → windows → panes in TreeView, MultiPreview → the
+    /// tree on the selected window. On a pane it switches like `exit_on_switch`.
+    Expand,
+    /// Hand the terminal to `tmux attach` on the selection; detaching returns
+    /// to the deck. Inside tmux this would nest, so it switches instead.
+    Attach,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct EnterConfig {
+    pub tree: Option<EnterAction>,
+    pub multi: Option<EnterAction>,
 }
 
 impl Default for BehaviorConfig {
@@ -151,8 +369,23 @@ impl Default for BehaviorConfig {
         Self {
             default_view: "tree".to_string(),
             default_sort: "recent".to_string(),
+            window_sort: WindowSort::Activity,
             double_space_ms: 300,
             exit_on_switch: true,
+            enter: EnterConfig::default(),
+            even_layout: "tiled".to_string(),
+            terminal_command: None,
+            submit_keys: vec!["Enter".to_string()],
+            window_preview_active_pane: false,
+            activity_min_secs: 1,
+            change_badge_refreshes: 2,
+            tmux_sockets: Vec::new(),
+            pause_on_input: true,
+            resize_step: 5,
+            skip_fields: Vec::new(),
+            require_typed_confirmation: false,
+            inspect_delay_ms: 500,
+            yank_to: YankTarget::Clipboard,
         }
     }
 }
@@ -161,30 +394,40 @@ impl BehaviorConfig {
     pub fn view_mode(&self) -> ViewMode {
         match self.default_view.to_ascii_lowercase().as_str() {
             "multi" | "multipreview" => ViewMode::MultiPreview,
+            "panes" | "allpanes" => ViewMode::AllPanes,
             _ => ViewMode::TreeView,
         }
     }
 
+    /// The Enter behaviour for `view`: its `[behavior.enter]` entry, else the
+    /// switch variant `exit_on_switch` selects.
+    pub fn enter_action(&self, view: ViewMode) -> EnterAction {
+        let configured = match view {
+            ViewMode::TreeView => self.enter.tree,
+            ViewMode::MultiPreview => self.enter.multi,
+            ViewMode::AllPanes | ViewMode::Dashboard => None,
+        };
+        configured.unwrap_or(if self.exit_on_switch {
+            EnterAction::SwitchExit
+        } else {
+            EnterAction::SwitchStay
+        })
+    }
+
+    /// `terminal_command` as an argv with `{target}` filled in. `None` when
+    /// unset or blank.
+    pub fn terminal_argv(&self, target: &str) -> Option<Vec<String>> {
+        let argv: Vec<String> = self
+            .terminal_command
+            .as_deref()?
+            .split_whitespace()
+            .map(|word| word.replace("{target}", target))
+            .collect();
+        (!argv.is_empty()).then_some(argv)
+    }
+
     pub fn session_sort(&self) -> SessionSort {
-        match self.default_sort.to_ascii_lowercase().as_str() {
-            "recent_asc" | "oldest" => SessionSort {
-                key: SessionSortKey::LastAttached,
-                direction: SortDirection::Asc,
-            },
-            "abc" | "alphabet" => SessionSort {
-                key: SessionSortKey::Alphabet,
-                direction: SortDirection::Desc,
-            },
-            "abc_asc" | "alphabet_asc" => SessionSort {
-                key: SessionSortKey::Alphabet,
-                direction: SortDirection::Asc,
-            },
-            // "recent" / unknown -> the historical default (most recent first).
-            _ => SessionSort {
-                key: SessionSortKey::LastAttached,
-                direction: SortDirection::Desc,
-            },
-        }
+        SessionSort::from_name(&self.default_sort)
     }
 }
 
@@ -195,8 +438,9 @@ impl BehaviorConfig {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct LayoutConfig {
-    /// Width of the left (lists) panel as a percentage of the screen; the
-    /// preview takes the rest. TreeView only.
+    /// Width of the left (lists) panel as a percentage of the screen, or its
+    /// height when stacked (see `stack_below_width`); the preview takes the
+    /// rest. TreeView only.
     pub session_panel_width: u16,
     /// Vertical split of the left panel into Sessions / Windows / Panes, as
     /// three percentages.
@@ -204,6 +448,83 @@ pub struct LayoutConfig {
     /// In MultiPreview, the width percentage given to the selected session; the
     /// remaining sessions share what's left.
     pub multi_selected_ratio: u16,
+    /// Order of the windows stacked in each MultiPreview column.
+    pub multi_window_sort: WindowSort,
+    /// Columns of the MultiPreview grid (1–6), which shows every window as
+    /// an equal thumbnail; 0 keeps a column per session. `+` / `-` change it.
+    pub multi_columns: u16,
+    /// Show the terminal size of each attached client after the session name,
+    /// to explain why a window looks different on another client.
+    pub show_client_size: bool,
+    /// Show each pane's CPU and memory use in the Panes list (Linux only).
+    pub show_resources: bool,
+    /// Show how long the last refresh took and how long ago it landed at the
+    /// end of the TreeView status bar, to help tune `--interval`.
+    pub show_metrics: bool,
+    /// Terminal width (columns) below which the TreeView stacks the lists
+    /// above the preview instead of beside it; 0 keeps it side by side.
+    pub stack_below_width: u16,
+    /// Rows of the monitor strip, borders included (see [`Action::Monitor`]).
+    pub monitor_height: u16,
+    /// Screen edge the monitor strip sits on.
+    pub monitor_position: MonitorPosition,
+}
+
+/// How a session's windows are ordered: in the TreeView's Windows list
+/// (`behavior.window_sort`) and, separately, down a MultiPreview column
+/// (`layout.multi_window_sort`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowSort {
+    /// Most recently active first, the session's current window first on a
+    /// tie.
+    #[default]
+    Activity,
+    /// By window index, so windows keep their place as activity moves.
+    Index,
+    /// Case-insensitive window name.
+    Name,
+}
+
+impl WindowSort {
+    /// Label shown in the Windows list title.
+    pub fn label(self) -> &'static str {
+        match self {
+            WindowSort::Activity => "activity",
+            WindowSort::Index => "index",
+            WindowSort::Name => "name",
+        }
+    }
+
+    /// The next order the `sort` key cycles to in the Windows list.
+    pub fn next(self) -> Self {
+        match self {
+            WindowSort::Activity => WindowSort::Index,
+            WindowSort::Index => WindowSort::Name,
+            WindowSort::Name => WindowSort::Activity,
+        }
+    }
+
+    /// Compare two windows of a session; ties fall back to the index.
+    pub fn cmp(self, a: &TmuxWindow, b: &TmuxWindow) -> std::cmp::Ordering {
+        let ord = match self {
+            WindowSort::Activity => {
+                b.activity.cmp(&a.activity).then_with(|| b.active.cmp(&a.active))
+            }
+            WindowSort::Index => std::cmp::Ordering::Equal,
+            WindowSort::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        };
+        ord.then_with(|| a.index.cmp(&b.index))
+    }
+}
+
+/// Where the monitor strip is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorPosition {
+    #[default]
+    Top,
+    Bottom,
 }
 
 impl Default for LayoutConfig {
@@ -212,10 +533,25 @@ impl Default for LayoutConfig {
             session_panel_width: 30,
             tree_split: [30, 35, 35],
             multi_selected_ratio: 70,
+            multi_window_sort: WindowSort::Activity,
+            multi_columns: 0,
+            show_client_size: false,
+            show_resources: false,
+            show_metrics: false,
+            stack_below_width: 80,
+            monitor_height: 8,
+            monitor_position: MonitorPosition::Top,
         }
     }
 }
 
+impl LayoutConfig {
+    /// Whether a TreeView `width` columns wide stacks lists over the preview.
+    pub fn tree_stacked(&self, width: u16) -> bool {
+        width < self.stack_below_width
+    }
+}
+
 // =============================================================================
 // [theme]
 // =============================================================================
@@ -227,13 +563,19 @@ impl Default for LayoutConfig {
 pub struct ThemeConfig {
     pub preset: String,
     pub colors: HashMap<String, String>,
+    /// Colours for pane commands in the Panes list, by command name. A
+    /// `[theme.commands]` table replaces the built-in map.
+    pub commands: HashMap<String, String>,
 }
 
 impl Default for ThemeConfig {
     fn default() -> Self {
+        let commands =
+            [("vim", "green"), ("nvim", "green"), ("node", "yellow"), ("ssh", "magenta")];
         Self {
             preset: "default".to_string(),
             colors: HashMap::new(),
+            commands: commands.iter().map(|(c, v)| (c.to_string(), v.to_string())).collect(),
         }
     }
 }
@@ -253,6 +595,21 @@ impl ThemeConfig {
         }
         theme
     }
+
+    /// The `commands` map with its colours parsed; an invalid colour is
+    /// dropped with a warning.
+    pub fn command_colors(&self) -> HashMap<String, Color> {
+        self.commands
+            .iter()
+            .filter_map(|(command, value)| match parse_color(value) {
+                Some(color) => Some((command.clone(), color)),
+                None => {
+                    warn!("invalid colour '{value}' for command '{command}', ignoring");
+                    None
+                }
+            })
+            .collect()
+    }
 }
 
 /// A resolved set of semantic UI colours. Roles are intentionally coarse so the
@@ -265,10 +622,13 @@ pub struct Theme {
     pub unfocus_border: Color,
     /// General accent: preview/popup borders, headers, info text.
     pub accent: Color,
-    /// Background of the selected row.
+    /// Background of the selected row in the focused list.
     pub selection_bg: Color,
-    /// Foreground of the selected row.
+    /// Foreground of the selected row in the focused list.
     pub selection_fg: Color,
+    /// Foreground of the (underlined) selected row in unfocused lists, which
+    /// get no background so only one list ever shows a selection bar.
+    pub inactive_selection_fg: Color,
     /// Background of the status bar.
     pub status_bar_bg: Color,
     /// Errors and destructive actions (e.g. kill).
@@ -294,6 +654,7 @@ impl Theme {
             "accent" => self.accent = color,
             "selection_bg" => self.selection_bg = color,
             "selection_fg" => self.selection_fg = color,
+            "inactive_selection_fg" => self.inactive_selection_fg = color,
             "status_bar_bg" => self.status_bar_bg = color,
             "error" => self.error = color,
             "success" => self.success = color,
@@ -307,14 +668,15 @@ impl Theme {
     pub fn preset(name: &str) -> Self {
         let rgb = Color::Rgb;
         match name.to_ascii_lowercase().as_str() {
-            // The historical hard-coded palette, kept byte-for-byte so an
-            // unconfigured deck looks exactly as before.
+            // The historical hard-coded palette, except that the selection
+            // bar is blue: DarkGray clashed with the status bar and borders.
             "default" => Self {
                 focus_border: Color::Yellow,
                 unfocus_border: Color::DarkGray,
                 accent: Color::Cyan,
-                selection_bg: Color::DarkGray,
+                selection_bg: Color::Blue,
                 selection_fg: Color::White,
+                inactive_selection_fg: Color::LightCyan,
                 status_bar_bg: Color::DarkGray,
                 error: Color::Red,
                 success: Color::Green,
@@ -327,6 +689,7 @@ impl Theme {
                 accent: rgb(0xab, 0xb2, 0xbf),
                 selection_bg: rgb(0x3e, 0x44, 0x51),
                 selection_fg: rgb(0xff, 0xff, 0xff),
+                inactive_selection_fg: rgb(0xab, 0xb2, 0xbf),
                 status_bar_bg: rgb(0x3e, 0x44, 0x51),
                 error: rgb(0xff, 0xff, 0xff),
                 success: rgb(0xab, 0xb2, 0xbf),
@@ -338,6 +701,7 @@ impl Theme {
                 accent: rgb(0x8b, 0xe9, 0xfd),         // cyan
                 selection_bg: rgb(0x44, 0x47, 0x5a),   // current line
                 selection_fg: rgb(0xf8, 0xf8, 0xf2),   // foreground
+                inactive_selection_fg: rgb(0x8b, 0xe9, 0xfd), // cyan
                 status_bar_bg: rgb(0x44, 0x47, 0x5a),
                 error: rgb(0xff, 0x55, 0x55),          // red
                 success: rgb(0x50, 0xfa, 0x7b),        // green
@@ -349,6 +713,7 @@ impl Theme {
                 accent: rgb(0x88, 0xc0, 0xd0),
                 selection_bg: rgb(0x43, 0x4c, 0x5e),
                 selection_fg: rgb(0xec, 0xef, 0xf4),
+                inactive_selection_fg: rgb(0x88, 0xc0, 0xd0),
                 status_bar_bg: rgb(0x3b, 0x42, 0x52),
                 error: rgb(0xbf, 0x61, 0x6a),
                 success: rgb(0xa3, 0xbe, 0x8c),
@@ -360,6 +725,7 @@ impl Theme {
                 accent: rgb(0x8e, 0xc0, 0x7c),
                 selection_bg: rgb(0x3c, 0x38, 0x36),
                 selection_fg: rgb(0xeb, 0xdb, 0xb2),
+                inactive_selection_fg: rgb(0x8e, 0xc0, 0x7c),
                 status_bar_bg: rgb(0x3c, 0x38, 0x36),
                 error: rgb(0xfb, 0x49, 0x34),
                 success: rgb(0xb8, 0xbb, 0x26),
@@ -371,6 +737,7 @@ impl Theme {
                 accent: rgb(0x7d, 0xcf, 0xff),
                 selection_bg: rgb(0x28, 0x2e, 0x44),
                 selection_fg: rgb(0xc0, 0xca, 0xf5),
+                inactive_selection_fg: rgb(0x7d, 0xcf, 0xff),
                 status_bar_bg: rgb(0x24, 0x28, 0x3b),
                 error: rgb(0xf7, 0x76, 0x8e),
                 success: rgb(0x9e, 0xce, 0x6a),
@@ -382,6 +749,7 @@ impl Theme {
                 accent: rgb(0x89, 0xdc, 0xeb),
                 selection_bg: rgb(0x31, 0x32, 0x44),
                 selection_fg: rgb(0xcd, 0xd6, 0xf4),
+                inactive_selection_fg: rgb(0x89, 0xdc, 0xeb),
                 status_bar_bg: rgb(0x31, 0x32, 0x44),
                 error: rgb(0xf3, 0x8b, 0xa8),
                 success: rgb(0xa6, 0xe3, 0xa1),
@@ -393,6 +761,7 @@ impl Theme {
                 accent: rgb(0x2a, 0xa1, 0x98),
                 selection_bg: rgb(0x07, 0x36, 0x42),
                 selection_fg: rgb(0x93, 0xa1, 0xa1),
+                inactive_selection_fg: rgb(0x2a, 0xa1, 0x98),
                 status_bar_bg: rgb(0x07, 0x36, 0x42),
                 error: rgb(0xdc, 0x32, 0x2f),
                 success: rgb(0x85, 0x99, 0x00),
@@ -404,6 +773,7 @@ impl Theme {
                 accent: rgb(0x5e, 0xf1, 0xff),
                 selection_bg: rgb(0x3c, 0x40, 0x48),
                 selection_fg: rgb(0xff, 0xff, 0xff),
+                inactive_selection_fg: rgb(0x5e, 0xf1, 0xff),
                 status_bar_bg: rgb(0x3c, 0x40, 0x48),
                 error: rgb(0xff, 0x6e, 0x5e),
                 success: rgb(0x5e, 0xff, 0x6c),
@@ -415,6 +785,7 @@ impl Theme {
                 accent: rgb(0x33, 0xb1, 0xff),
                 selection_bg: rgb(0x28, 0x28, 0x28),
                 selection_fg: rgb(0xf2, 0xf4, 0xf8),
+                inactive_selection_fg: rgb(0x33, 0xb1, 0xff),
                 status_bar_bg: rgb(0x28, 0x28, 0x28),
                 error: rgb(0xee, 0x53, 0x96),
                 success: rgb(0x25, 0xbe, 0x6a),
@@ -475,6 +846,34 @@ pub fn parse_hex_color(s: &str) -> Option<Color> {
     Some(Color::Rgb(r, g, b))
 }
 
+// =============================================================================
+// [events]
+// =============================================================================
+
+/// Shell commands run (detached, via `sh -c`) when a deck operation succeeds.
+/// Every field is opt-in; each runs an arbitrary user command. Placeholders
+/// are substituted shell-quoted, so names with spaces or quotes stay one word.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct EventsConfig {
+    /// After switching the client. `{target}` is the switched-to target.
+    pub on_switch: Option<String>,
+    /// After creating a session (plain or from a template). `{session}`.
+    pub on_session_created: Option<String>,
+    /// After killing a session. `{session}`.
+    pub on_session_killed: Option<String>,
+}
+
+/// Fill `{name}` placeholders in `template` from `vars`, single-quoting each
+/// value for `sh`. Unknown placeholders are left as they are.
+pub fn render_event_command(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |cmd, (name, value)| {
+            let quoted = format!("'{}'", value.replace('\'', "'\\''"));
+            cmd.replace(&format!("{{{name}}}"), &quoted)
+        })
+}
+
 // =============================================================================
 // [hooks.claude] / [hooks.codex]
 // =============================================================================
@@ -588,8 +987,128 @@ pub enum Action {
     NewSession,
     RenameSession,
     KillSession,
+    /// Ask to kill every session but the selected one.
+    KillOtherSessions,
     /// Toggle the fleet dashboard (all Claude panes, sorted by attention).
     Dashboard,
+    /// Rotate the panes of the selected window (`rotate-window`).
+    RotatePanes,
+    /// Rotate the panes of the selected window the other way (`rotate-window -D`).
+    RotatePanesReverse,
+    /// Pick a `[[templates]]` entry and create a session from it.
+    NewFromTemplate,
+    /// Re-capture the visible previews now, without re-listing the tree.
+    Recapture,
+    /// Re-apply an even layout to the selected window (`select-layout`).
+    EvenLayout,
+    /// Attach to the selection in a new OS terminal window
+    /// (`behavior.terminal_command`).
+    OpenInTerminal,
+    /// List the server's dead (`remain-on-exit`) panes to respawn or kill.
+    DeadPanes,
+    /// TreeView: focus the list containing the selection's parent.
+    FocusParent,
+    /// MultiPreview: switch unattached sessions between dimmed and hidden.
+    ToggleInactive,
+    /// Re-run the last window adjustment (rotate / even layout) on the
+    /// current selection.
+    RepeatLast,
+    /// TreeView: set the scrollback range the preview captures.
+    CaptureRange,
+    /// Pick another tmux server for the whole deck.
+    SwitchServer,
+    /// Switch to a saved workspace, or save the current view as one.
+    Workspaces,
+    /// Grow or shrink the selected pane (`resize-pane -L/-R/-U/-D`) by
+    /// `behavior.resize_step` cells.
+    ResizePaneLeft,
+    ResizePaneRight,
+    ResizePaneUp,
+    ResizePaneDown,
+    /// Jump to a pane by its `%N` id.
+    GotoPane,
+    /// Show or hide the borders around previews (`preview.borders`).
+    ToggleBorders,
+    /// TreeView: pin the selected pane to the monitor strip, or unpin it.
+    Monitor,
+    /// TreeView: shrink the focused list to its selected row, or expand it.
+    CollapseList,
+    /// Keep the selection on the session of the most recently active tmux
+    /// client; any navigation key pauses it.
+    Follow,
+    /// TreeView: dim the rows that do not fuzzy-match a typed query, then
+    /// jump to the first match.
+    Search,
+    /// TreeView: copy the preview's text (`behavior.yank_to`).
+    Yank,
+    /// TreeView: turn the selected window's `synchronize-panes` on or off.
+    SyncPanes,
+    /// Wrap long preview lines, or cut them again (`preview.wrap`).
+    WrapPreview,
+    /// Show only the windows with a pending bell, activity or silence alert,
+    /// or every window again.
+    AlertsOnly,
+    /// Re-read the config file and apply it without restarting.
+    ReloadConfig,
+    /// Panes list: split the selected pane side by side.
+    SplitPane,
+    /// Panes list: split the selected pane into two stacked panes.
+    SplitPaneVertical,
+    /// Panes list: ask to kill the selected pane.
+    KillPane,
+    /// Windows list: move the selected window to another session.
+    MoveWindow,
+}
+
+impl Action {
+    /// The action's key under `[keybindings]`, e.g. `new_session`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Refresh => "refresh",
+            Action::Sort => "sort",
+            Action::Group => "group",
+            Action::Input => "input",
+            Action::Enter => "enter",
+            Action::NewSession => "new_session",
+            Action::RenameSession => "rename_session",
+            Action::KillSession => "kill_session",
+            Action::KillOtherSessions => "kill_other_sessions",
+            Action::Dashboard => "dashboard",
+            Action::RotatePanes => "rotate_panes",
+            Action::RotatePanesReverse => "rotate_panes_reverse",
+            Action::NewFromTemplate => "new_from_template",
+            Action::Recapture => "recapture",
+            Action::EvenLayout => "even_layout",
+            Action::OpenInTerminal => "open_in_terminal",
+            Action::DeadPanes => "dead_panes",
+            Action::FocusParent => "focus_parent",
+            Action::ToggleInactive => "toggle_inactive",
+            Action::RepeatLast => "repeat_last",
+            Action::CaptureRange => "capture_range",
+            Action::SwitchServer => "switch_server",
+            Action::Workspaces => "workspaces",
+            Action::ResizePaneLeft => "resize_pane_left",
+            Action::ResizePaneRight => "resize_pane_right",
+            Action::ResizePaneUp => "resize_pane_up",
+            Action::ResizePaneDown => "resize_pane_down",
+            Action::GotoPane => "goto_pane",
+            Action::ToggleBorders => "toggle_borders",
+            Action::WrapPreview => "wrap_preview",
+            Action::AlertsOnly => "alerts_only",
+            Action::ReloadConfig => "reload_config",
+            Action::SplitPane => "split_pane",
+            Action::SplitPaneVertical => "split_pane_vertical",
+            Action::KillPane => "kill_pane",
+            Action::MoveWindow => "move_window",
+            Action::Monitor => "monitor",
+            Action::CollapseList => "collapse_list",
+            Action::Follow => "follow",
+            Action::Search => "search",
+            Action::Yank => "yank",
+            Action::SyncPanes => "sync_panes",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -614,7 +1133,73 @@ pub struct KeyBindings {
     #[serde(deserialize_with = "de_keys")]
     pub kill_session: Vec<KeySpec>,
     #[serde(deserialize_with = "de_keys")]
+    pub kill_other_sessions: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
     pub dashboard: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub rotate_panes: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub rotate_panes_reverse: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub new_from_template: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub recapture: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub even_layout: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub toggle_inactive: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub open_in_terminal: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub dead_panes: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub focus_parent: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub repeat_last: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub capture_range: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub switch_server: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub workspaces: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub resize_pane_left: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub resize_pane_right: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub resize_pane_up: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub resize_pane_down: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub goto_pane: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub toggle_borders: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub monitor: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub collapse_list: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub follow: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub search: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub yank: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub sync_panes: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub wrap_preview: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub alerts_only: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub reload_config: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub split_pane: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub split_pane_vertical: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub kill_pane: Vec<KeySpec>,
+    #[serde(deserialize_with = "de_keys")]
+    pub move_window: Vec<KeySpec>,
 }
 
 impl Default for KeyBindings {
@@ -630,7 +1215,41 @@ impl Default for KeyBindings {
             new_session: vec![ctrl('n')],
             rename_session: vec![ctrl('r')],
             kill_session: vec![ctrl('x')],
+            kill_other_sessions: vec![alt('x')],
             dashboard: vec![key('d')],
+            // Same chords tmux itself binds to rotate-window.
+            rotate_panes: vec![ctrl('o')],
+            rotate_panes_reverse: vec![alt('o')],
+            new_from_template: vec![key('t')],
+            recapture: vec![key('R')],
+            even_layout: vec![key('=')],
+            toggle_inactive: vec![key('a')],
+            open_in_terminal: vec![key('o')],
+            dead_panes: vec![key('D')],
+            focus_parent: vec![key('u')],
+            repeat_last: vec![key('.')],
+            capture_range: vec![key('S')],
+            switch_server: vec![ctrl('s')],
+            workspaces: vec![key('w')],
+            resize_pane_left: vec![ctrl_named(KeyCode::Left)],
+            resize_pane_right: vec![ctrl_named(KeyCode::Right)],
+            resize_pane_up: vec![ctrl_named(KeyCode::Up)],
+            resize_pane_down: vec![ctrl_named(KeyCode::Down)],
+            goto_pane: vec![key('%')],
+            toggle_borders: vec![key('B')],
+            monitor: vec![key('m')],
+            collapse_list: vec![key('c')],
+            follow: vec![key('F')],
+            search: vec![key('/')],
+            yank: vec![key('y')],
+            sync_panes: vec![key('P')],
+            wrap_preview: vec![key('W')],
+            alerts_only: vec![key('!')],
+            reload_config: vec![ctrl('l')],
+            split_pane: vec![key('s')],
+            split_pane_vertical: vec![key('v')],
+            kill_pane: vec![key('x')],
+            move_window: vec![key('M')],
         }
     }
 }
@@ -638,11 +1257,20 @@ impl Default for KeyBindings {
 impl KeyBindings {
     /// Pairs of (action, bindings) in match priority order. Modifier-bearing
     /// bindings (e.g. `C-r`) are listed so they win over the plain `r` refresh.
-    fn entries(&self) -> [(Action, &Vec<KeySpec>); 10] {
+    fn entries(&self) -> [(Action, &Vec<KeySpec>); 43] {
         [
             (Action::NewSession, &self.new_session),
             (Action::RenameSession, &self.rename_session),
             (Action::KillSession, &self.kill_session),
+            (Action::KillOtherSessions, &self.kill_other_sessions),
+            (Action::RotatePanes, &self.rotate_panes),
+            (Action::RotatePanesReverse, &self.rotate_panes_reverse),
+            (Action::SwitchServer, &self.switch_server),
+            (Action::ReloadConfig, &self.reload_config),
+            (Action::ResizePaneLeft, &self.resize_pane_left),
+            (Action::ResizePaneRight, &self.resize_pane_right),
+            (Action::ResizePaneUp, &self.resize_pane_up),
+            (Action::ResizePaneDown, &self.resize_pane_down),
             (Action::Quit, &self.quit),
             (Action::Refresh, &self.refresh),
             (Action::Sort, &self.sort),
@@ -650,9 +1278,47 @@ impl KeyBindings {
             (Action::Input, &self.input),
             (Action::Enter, &self.enter),
             (Action::Dashboard, &self.dashboard),
+            (Action::NewFromTemplate, &self.new_from_template),
+            (Action::Recapture, &self.recapture),
+            (Action::EvenLayout, &self.even_layout),
+            (Action::ToggleInactive, &self.toggle_inactive),
+            (Action::OpenInTerminal, &self.open_in_terminal),
+            (Action::DeadPanes, &self.dead_panes),
+            (Action::FocusParent, &self.focus_parent),
+            (Action::RepeatLast, &self.repeat_last),
+            (Action::CaptureRange, &self.capture_range),
+            (Action::Workspaces, &self.workspaces),
+            (Action::GotoPane, &self.goto_pane),
+            (Action::ToggleBorders, &self.toggle_borders),
+            (Action::Monitor, &self.monitor),
+            (Action::CollapseList, &self.collapse_list),
+            (Action::Follow, &self.follow),
+            (Action::Search, &self.search),
+            (Action::Yank, &self.yank),
+            (Action::SyncPanes, &self.sync_panes),
+            (Action::WrapPreview, &self.wrap_preview),
+            (Action::AlertsOnly, &self.alerts_only),
+            // List-scoped actions share keys with global ones (`s` also
+            // sorts); the UI tries them first in the list they belong to.
+            (Action::SplitPane, &self.split_pane),
+            (Action::SplitPaneVertical, &self.split_pane_vertical),
+            (Action::KillPane, &self.kill_pane),
+            (Action::MoveWindow, &self.move_window),
         ]
     }
 
+    /// Every action with the label of its primary binding (empty when
+    /// unbound), sorted by name, for the command palette.
+    pub fn actions(&self) -> Vec<(Action, String)> {
+        let mut actions: Vec<(Action, String)> = self
+            .entries()
+            .into_iter()
+            .map(|(action, specs)| (action, specs.first().map(KeySpec::label).unwrap_or_default()))
+            .collect();
+        actions.sort_by_key(|(action, _)| action.name());
+        actions
+    }
+
     /// The action a key event maps to, if any.
     pub fn action_for(&self, key: &KeyEvent) -> Option<Action> {
         self.entries()
@@ -661,6 +1327,13 @@ impl KeyBindings {
             .map(|(action, _)| action)
     }
 
+    /// Whether `key` is one of `action`'s bindings.
+    pub fn binds(&self, action: Action, key: &KeyEvent) -> bool {
+        self.entries()
+            .into_iter()
+            .any(|(a, specs)| a == action && specs.iter().any(|s| s.matches(key)))
+    }
+
     /// Human-readable label for an action's primary binding, e.g. `C-n` or `q`,
     /// used to keep the on-screen hint bar in sync with the user's remaps.
     /// Returns an empty string if the action has no binding.
@@ -683,9 +1356,14 @@ pub struct KeySpec {
 
 impl KeySpec {
     /// Whether this spec matches a crossterm key event. Only the C/S/A
-    /// modifiers are considered (other state flags are masked out).
+    /// modifiers are considered (other state flags are masked out). For a
+    /// character key the case already encodes Shift, so `R` matches the
+    /// `Shift+R` terminals report unless the spec asks for `S-` explicitly.
     pub fn matches(&self, key: &KeyEvent) -> bool {
-        let relevant = KeyModifiers::CONTROL | KeyModifiers::SHIFT | KeyModifiers::ALT;
+        let mut relevant = KeyModifiers::CONTROL | KeyModifiers::SHIFT | KeyModifiers::ALT;
+        if matches!(self.code, KeyCode::Char(_)) && !self.mods.contains(KeyModifiers::SHIFT) {
+            relevant.remove(KeyModifiers::SHIFT);
+        }
         self.code == key.code && (key.modifiers & relevant) == self.mods
     }
 
@@ -738,6 +1416,13 @@ fn ctrl(c: char) -> KeySpec {
     }
 }
 
+fn alt(c: char) -> KeySpec {
+    KeySpec {
+        code: KeyCode::Char(c),
+        mods: KeyModifiers::ALT,
+    }
+}
+
 fn named(code: KeyCode) -> KeySpec {
     KeySpec {
         code,
@@ -745,6 +1430,13 @@ fn named(code: KeyCode) -> KeySpec {
     }
 }
 
+fn ctrl_named(code: KeyCode) -> KeySpec {
+    KeySpec {
+        code,
+        mods: KeyModifiers::CONTROL,
+    }
+}
+
 /// Parse a key string like `q`, `Esc`, `C-n`, `S-Tab`, `Up`, `Space`.
 pub fn parse_key(s: &str) -> Option<KeySpec> {
     let s = s.trim();
@@ -813,6 +1505,73 @@ where
     })
 }
 
+/// Deserialize a fraction, rejecting anything outside `0.0..=1.0`.
+fn de_fraction<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = f64::deserialize(deserializer)?;
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(de::Error::custom(format!("expected a fraction from 0.0 to 1.0, got {value}")))
+    }
+}
+
+/// Whether `name` is a key tmux's `send-keys` accepts: an optional run of
+/// `C-` / `M-` / `S-` modifiers, then a single character or a named key
+/// (`Enter`, `Escape`, `F1`–`F12`, `KP0`, …). Names are case-insensitive, as
+/// in tmux.
+pub fn is_tmux_key_name(name: &str) -> bool {
+    const NAMED: &[&str] = &[
+        "Enter", "Escape", "Space", "Tab", "BTab", "BSpace", "Up", "Down", "Left", "Right",
+        "Home", "End", "PPage", "PageUp", "PgUp", "NPage", "PageDown", "PgDn", "IC", "Insert",
+        "DC", "Delete", "Any", "KP/", "KP*", "KP-", "KP+", "KP.", "KPEnter",
+    ];
+    let mut base = name;
+    while let Some(rest) = ["C-", "M-", "S-"]
+        .iter()
+        .find_map(|m| base.strip_prefix(m).filter(|r| !r.is_empty()))
+    {
+        base = rest;
+    }
+    let mut chars = base.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return !c.is_control();
+    }
+    let fkey = base
+        .strip_prefix(['F', 'f'])
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| (1..=12).contains(&n));
+    let keypad = base
+        .strip_prefix("KP")
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| n <= 9);
+    fkey || keypad || NAMED.iter().any(|k| k.eq_ignore_ascii_case(base))
+}
+
+/// Deserialize a single tmux key name or a list of them, rejecting names
+/// `send-keys` would not understand (see [`is_tmux_key_name`]).
+fn de_tmux_keys<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    let keys = match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(k) => vec![k],
+        OneOrMany::Many(v) => v,
+    };
+    match keys.iter().find(|k| !is_tmux_key_name(k)) {
+        Some(bad) => Err(de::Error::custom(format!("invalid tmux key name: {bad}"))),
+        None => Ok(keys),
+    }
+}
+
 // =============================================================================
 // Tests
 // =============================================================================
@@ -820,6 +1579,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app::{SessionSortKey, SortDirection};
 
     #[test]
     fn parses_color_forms() {
@@ -869,8 +1629,16 @@ mod tests {
     fn empty_config_is_default() {
         let cfg: Config = toml::from_str("").unwrap();
         assert_eq!(cfg.preview.interval, None);
+        assert_eq!(cfg.preview.binary_threshold, 0.7);
+        for bad in ["1.5", "-0.1", "nan"] {
+            let toml = format!("[preview]\nbinary_threshold = {bad}");
+            assert!(toml::from_str::<Config>(&toml).is_err(), "accepted {bad}");
+        }
         assert_eq!(cfg.behavior.double_space_ms, 300);
         assert!(cfg.behavior.exit_on_switch);
+        assert!(cfg.behavior.pause_on_input);
+        assert!(cfg.preview.borders);
+        assert_eq!(cfg.preview.color_depth, None);
         assert_eq!(cfg.layout.session_panel_width, 30);
         // Default markers match the historical glyphs.
         assert_eq!(cfg.hooks.claude.done.glyph, "✓");
@@ -883,6 +1651,7 @@ mod tests {
             r##"
             [preview]
             interval = 500
+            color_depth = "256"
 
             [keybindings]
             quit = "x"
@@ -893,6 +1662,7 @@ mod tests {
         )
         .unwrap();
         assert_eq!(cfg.preview.interval, Some(500));
+        assert_eq!(cfg.preview.color_depth, Some(ColorDepth::Palette256));
         // Overridden binding takes effect...
         assert_eq!(cfg.keybindings.quit, vec![key('x')]);
         // ...while untouched bindings keep their defaults.
@@ -915,6 +1685,54 @@ mod tests {
         assert_eq!(cfg.keybindings.quit.len(), 3);
     }
 
+    #[test]
+    fn ctrl_arrows_resize_panes_by_default() {
+        let kb = KeyBindings::default();
+        let ctrl_left = KeyEvent::new(KeyCode::Left, KeyModifiers::CONTROL);
+        assert_eq!(kb.action_for(&ctrl_left), Some(Action::ResizePaneLeft));
+        assert_eq!(kb.action_for(&KeyEvent::new(KeyCode::Left, KeyModifiers::NONE)), None);
+        assert_eq!(kb.label(Action::ResizePaneDown), "C-Down");
+    }
+
+    /// WCAG contrast ratio of two colours; named colours use xterm's palette.
+    fn contrast(a: Color, b: Color) -> f64 {
+        fn rgb(c: Color) -> (u8, u8, u8) {
+            match c {
+                Color::Rgb(r, g, b) => (r, g, b),
+                Color::Blue => (0x00, 0x00, 0xee),
+                Color::LightCyan => (0x00, 0xff, 0xff),
+                Color::White => (0xe5, 0xe5, 0xe5),
+                Color::DarkGray => (0x7f, 0x7f, 0x7f),
+                Color::Black => (0x00, 0x00, 0x00),
+                other => panic!("no RGB for {other:?}"),
+            }
+        }
+        let luminance = |c: Color| {
+            let (r, g, b) = rgb(c);
+            let lin = |v: u8| {
+                let v = f64::from(v) / 255.0;
+                if v <= 0.03928 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) }
+            };
+            0.2126 * lin(r) + 0.7152 * lin(g) + 0.0722 * lin(b)
+        };
+        let (la, lb) = (luminance(a), luminance(b));
+        (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+    }
+
+    #[test]
+    fn default_theme_selection_is_distinct_and_readable() {
+        let t = Theme::default();
+        // The selection bar never blends into the status bar or muted borders.
+        assert_ne!(t.selection_bg, t.status_bar_bg);
+        assert_ne!(t.selection_bg, t.unfocus_border);
+        // Focus (border) and selection (row) use different colours.
+        assert_ne!(t.inactive_selection_fg, t.focus_border);
+        assert_ne!(t.selection_bg, t.focus_border);
+        assert!(contrast(t.selection_fg, t.selection_bg) >= 4.5);
+        // Unfocused selections are text on the terminal's (dark) background.
+        assert!(contrast(t.inactive_selection_fg, Color::Black) >= 4.5);
+    }
+
     #[test]
     fn theme_preset_and_overrides_resolve() {
         let cfg: Config = toml::from_str(
@@ -932,6 +1750,22 @@ mod tests {
         assert_eq!(theme.accent, Color::Rgb(0x12, 0x34, 0x56));
         // Preset value retained for a non-overridden role.
         assert_eq!(theme.success, Color::Rgb(0x50, 0xfa, 0x7b));
+        assert_eq!(cfg.theme.command_colors()["nvim"], Color::Green);
+    }
+
+    #[test]
+    fn command_colors_replace_the_builtin_map() {
+        let cfg: Config = toml::from_str(
+            r##"
+            [theme.commands]
+            htop = "#ff0000"
+            cargo = "nonsense"
+        "##,
+        )
+        .unwrap();
+        let colors = cfg.theme.command_colors();
+        assert_eq!(colors.len(), 1);
+        assert_eq!(colors["htop"], Color::Rgb(0xff, 0, 0));
     }
 
     #[test]
@@ -948,8 +1782,22 @@ mod tests {
         let ctrl_r = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
         assert_eq!(kb.action_for(&plain_r), Some(Action::Refresh));
         assert_eq!(kb.action_for(&ctrl_r), Some(Action::RenameSession));
+        let ctrl_o = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL);
+        let alt_o = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::ALT);
+        assert_eq!(kb.action_for(&ctrl_o), Some(Action::RotatePanes));
+        assert_eq!(kb.action_for(&alt_o), Some(Action::RotatePanesReverse));
+        let shift_r = KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT);
+        assert_eq!(kb.action_for(&shift_r), Some(Action::Recapture));
+        let eq = KeyEvent::new(KeyCode::Char('='), KeyModifiers::NONE);
+        assert_eq!(kb.action_for(&eq), Some(Action::EvenLayout));
         let j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
         assert_eq!(kb.action_for(&j), None);
+        // `s` splits in the Panes list, which the UI checks with `binds`
+        // first; the global lookup still finds `sort`.
+        let s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(kb.action_for(&s), Some(Action::Sort));
+        assert!(kb.binds(Action::SplitPane, &s));
+        assert!(!kb.binds(Action::SplitPaneVertical, &s));
     }
 
     #[test]
@@ -963,6 +1811,102 @@ mod tests {
         assert!(cfg.hooks.claude.working.animated);
         // Marker colours in the example are hex codes.
         assert_eq!(cfg.hooks.claude.waiting.color, Color::Rgb(0xff, 0x87, 0x00));
+        assert_eq!(cfg.templates[0].windows[1].panes.len(), 2);
+        assert_eq!(cfg.preview.align, PreviewAlign::Top);
+        assert!(cfg.preview.join_wrapped);
+    }
+
+    #[test]
+    fn load_fails_on_a_malformed_file_but_not_a_missing_one() {
+        let dir = std::env::temp_dir().join(format!("tmux-deck-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+
+        assert!(Config::load(Some(&path)).is_ok());
+
+        std::fs::write(&path, "[preview]\ninterval = 250\n[behavior]\ndefault_view = \"multi\"\n")
+            .unwrap();
+        let cfg = Config::load(Some(&path)).unwrap();
+        assert_eq!(cfg.preview.interval, Some(250));
+        assert_eq!(cfg.behavior.default_view, "multi");
+
+        std::fs::write(&path, "[preview]\ninterval = \"fast\"\n").unwrap();
+        let err = Config::load(Some(&path)).unwrap_err().to_string();
+        assert!(err.contains("invalid config") && err.contains("interval"), "{err}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn terminal_argv_fills_target_into_each_word() {
+        let mut behavior = BehaviorConfig::default();
+        assert_eq!(behavior.terminal_argv("dev:1"), None);
+        behavior.terminal_command = Some("  ".to_string());
+        assert_eq!(behavior.terminal_argv("dev:1"), None);
+        behavior.terminal_command = Some("kitty --title={target} tmux attach -t {target}".into());
+        assert_eq!(
+            behavior.terminal_argv("dev:1").unwrap(),
+            ["kitty", "--title=dev:1", "tmux", "attach", "-t", "dev:1"]
+        );
+    }
+
+    #[test]
+    fn submit_keys_accept_tmux_key_names_only() {
+        for ok in ["Enter", "M-Enter", "C-m", "C-M-x", "S-F5", "KP7", "escape", "-"] {
+            assert!(is_tmux_key_name(ok), "{ok}");
+        }
+        for bad in ["", "Return", "F13", "C-Foo", "hello"] {
+            assert!(!is_tmux_key_name(bad), "{bad}");
+        }
+        let cfg: Config = toml::from_str("[behavior]\nsubmit_keys = []\n").unwrap();
+        assert!(cfg.behavior.submit_keys.is_empty());
+        let cfg: Config = toml::from_str("[behavior]\nsubmit_keys = \"M-Enter\"\n").unwrap();
+        assert_eq!(cfg.behavior.submit_keys, vec!["M-Enter"]);
+        assert!(toml::from_str::<Config>("[behavior]\nsubmit_keys = [\"Return\"]\n").is_err());
+        assert_eq!(Config::default().behavior.submit_keys, vec!["Enter"]);
+    }
+
+    #[test]
+    fn event_commands_quote_substituted_values() {
+        assert_eq!(
+            render_event_command("notify-send switched {target}", &[("target", "dev:1")]),
+            "notify-send switched 'dev:1'"
+        );
+        assert_eq!(
+            render_event_command("echo {session} {other}", &[("session", "it's; rm")]),
+            "echo 'it'\\''s; rm' {other}"
+        );
+    }
+
+    #[test]
+    fn preview_align_offsets() {
+        assert_eq!(PreviewAlign::Top.offset(3, 10), 0);
+        assert_eq!(PreviewAlign::Center.offset(3, 10), 3);
+        assert_eq!(PreviewAlign::Bottom.offset(3, 10), 7);
+        // Overfilled content is never offset.
+        assert_eq!(PreviewAlign::Bottom.offset(12, 10), 0);
+    }
+
+    #[test]
+    fn templates_parse_inline_with_defaults() {
+        let cfg: Config = toml::from_str(
+            "templates = [{ name = \"dev\", windows = [{ panes = [\"nvim\"] }, {}] }]\n",
+        )
+        .unwrap();
+        let t = &cfg.templates[0];
+        assert_eq!(t.name, "dev");
+        assert!(t.description.is_empty() && t.root_dir().is_none());
+        assert_eq!(t.windows.len(), 2);
+        assert!(t.windows[0].name.is_none() && t.windows[0].layout.is_none());
+        assert!(t.windows[1].panes.is_empty());
+    }
+
+    #[test]
+    fn tree_stacks_below_the_width_breakpoint() {
+        let layout = LayoutConfig::default();
+        assert!(layout.tree_stacked(79));
+        assert!(!layout.tree_stacked(80));
+        let cfg: Config = toml::from_str("[layout]\nstack_below_width = 0\n").unwrap();
+        assert!(!cfg.layout.tree_stacked(20));
     }
 
     #[test]
@@ -976,4 +1920,21 @@ mod tests {
         assert_eq!(b.session_sort().key, SessionSortKey::Alphabet);
         assert_eq!(b.session_sort().direction, SortDirection::Desc);
     }
+
+    #[test]
+    fn enter_action_falls_back_to_exit_on_switch() {
+        let cfg: Config = toml::from_str(
+            "[behavior]\nexit_on_switch = false\n[behavior.enter]\ntree = \"expand\"\n",
+        )
+        .unwrap();
+        assert_eq!(cfg.behavior.enter_action(ViewMode::TreeView), EnterAction::Expand);
+        assert_eq!(
+            cfg.behavior.enter_action(ViewMode::MultiPreview),
+            EnterAction::SwitchStay
+        );
+        assert_eq!(
+            BehaviorConfig::default().enter_action(ViewMode::MultiPreview),
+            EnterAction::SwitchExit
+        );
+    }
 }
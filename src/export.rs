@@ -0,0 +1,301 @@
+//! Pane capture export (`tmux-deck capture`).
+//!
+//! Dumps a pane's full scrollback with its escape sequences intact, so the
+//! output can be piped into tools that render terminal output to images
+//! (`ansilove`, `aha`, …). With `--html` the dump is instead converted by a
+//! small built-in ANSI→HTML translator into a self-contained `<pre>` block that
+//! can be pasted into a document and keeps its colours.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::Command;
+
+use color_eyre::eyre::eyre;
+
+/// Capture `target` (or the current pane when `None`) and write it to
+/// `output`, or stdout when `None`.
+pub fn run_capture(
+    target: Option<&str>,
+    html: bool,
+    output: Option<&Path>,
+) -> color_eyre::Result<()> {
+    let ansi = capture_with_escapes(target)?;
+    let out = if html { ansi_to_html(&ansi) } else { ansi };
+    match output {
+        Some(path) => std::fs::write(path, out)?,
+        None => print!("{out}"),
+    }
+    Ok(())
+}
+
+/// `capture-pane -e -p` over the whole history (`-S -`), keeping SGR escapes.
+fn capture_with_escapes(target: Option<&str>) -> color_eyre::Result<String> {
+    let mut args = vec!["capture-pane", "-e", "-p", "-S", "-"];
+    if let Some(t) = target {
+        args.extend(["-t", t]);
+    }
+    let output = Command::new("tmux").args(&args).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("tmux capture-pane failed: {}", stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// =============================================================================
+// ANSI → HTML
+// =============================================================================
+
+/// The SGR attributes in effect for a run of text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct SgrState {
+    fg: Option<(u8, u8, u8)>,
+    bg: Option<(u8, u8, u8)>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    reverse: bool,
+}
+
+impl SgrState {
+    fn css(&self) -> String {
+        let (fg, bg) = if self.reverse {
+            (self.bg.or(Some(DEFAULT_BG)), self.fg.or(Some(DEFAULT_FG)))
+        } else {
+            (self.fg, self.bg)
+        };
+        let mut css = String::new();
+        if let Some((r, g, b)) = fg {
+            let _ = write!(css, "color:#{r:02x}{g:02x}{b:02x};");
+        }
+        if let Some((r, g, b)) = bg {
+            let _ = write!(css, "background-color:#{r:02x}{g:02x}{b:02x};");
+        }
+        if self.bold {
+            css.push_str("font-weight:bold;");
+        }
+        if self.dim {
+            css.push_str("opacity:0.6;");
+        }
+        if self.italic {
+            css.push_str("font-style:italic;");
+        }
+        if self.underline {
+            css.push_str("text-decoration:underline;");
+        }
+        css
+    }
+
+    /// Apply one SGR parameter list (the `1;31` of `ESC[1;31m`).
+    fn apply(&mut self, params: &str) {
+        let codes: Vec<u16> = if params.is_empty() {
+            vec![0]
+        } else {
+            params
+                .split([';', ':'])
+                .map(|p| p.parse().unwrap_or(0))
+                .collect()
+        };
+        let mut i = 0;
+        while i < codes.len() {
+            match codes[i] {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                7 => self.reverse = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                27 => self.reverse = false,
+                c @ 30..=37 => self.fg = Some(palette((c - 30) as u8)),
+                c @ 90..=97 => self.fg = Some(palette((c - 90 + 8) as u8)),
+                c @ 40..=47 => self.bg = Some(palette((c - 40) as u8)),
+                c @ 100..=107 => self.bg = Some(palette((c - 100 + 8) as u8)),
+                39 => self.fg = None,
+                49 => self.bg = None,
+                c @ (38 | 48) => {
+                    let (color, used) = extended_color(&codes[i + 1..]);
+                    if let Some(color) = color {
+                        if c == 38 {
+                            self.fg = Some(color);
+                        } else {
+                            self.bg = Some(color);
+                        }
+                    }
+                    i += used;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+const DEFAULT_FG: (u8, u8, u8) = (0xe5, 0xe5, 0xe5);
+const DEFAULT_BG: (u8, u8, u8) = (0x00, 0x00, 0x00);
+
+/// Parse the tail of a `38;…`/`48;…` sequence: `5;n` (256-colour) or
+/// `2;r;g;b` (truecolor). Returns the colour and how many codes it consumed.
+fn extended_color(rest: &[u16]) -> (Option<(u8, u8, u8)>, usize) {
+    match rest {
+        [5, n, ..] => (Some(palette(*n as u8)), 2),
+        [2, r, g, b, ..] => (Some((*r as u8, *g as u8, *b as u8)), 4),
+        [5, ..] => (None, 1),
+        [2, ..] => (None, rest.len()),
+        _ => (None, 0),
+    }
+}
+
+/// RGB for an xterm 256-colour index (16 system colours, 6×6×6 cube, greys).
+fn palette(n: u8) -> (u8, u8, u8) {
+    const SYSTEM: [(u8, u8, u8); 16] = [
+        (0x00, 0x00, 0x00),
+        (0xcd, 0x00, 0x00),
+        (0x00, 0xcd, 0x00),
+        (0xcd, 0xcd, 0x00),
+        (0x00, 0x00, 0xee),
+        (0xcd, 0x00, 0xcd),
+        (0x00, 0xcd, 0xcd),
+        (0xe5, 0xe5, 0xe5),
+        (0x7f, 0x7f, 0x7f),
+        (0xff, 0x00, 0x00),
+        (0x00, 0xff, 0x00),
+        (0xff, 0xff, 0x00),
+        (0x5c, 0x5c, 0xff),
+        (0xff, 0x00, 0xff),
+        (0x00, 0xff, 0xff),
+        (0xff, 0xff, 0xff),
+    ];
+    match n {
+        0..=15 => SYSTEM[n as usize],
+        16..=231 => {
+            let idx = n - 16;
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            (level(idx / 36), level((idx / 6) % 6), level(idx % 6))
+        }
+        _ => {
+            let v = 8 + (n - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+fn escape_html(c: char, out: &mut String) {
+    match c {
+        '&' => out.push_str("&amp;"),
+        '<' => out.push_str("&lt;"),
+        '>' => out.push_str("&gt;"),
+        '"' => out.push_str("&quot;"),
+        _ => out.push(c),
+    }
+}
+
+/// Convert ANSI-coloured text into a self-contained HTML `<pre>` block. SGR
+/// sequences become inline-styled `<span>`s; every other escape sequence
+/// (cursor movement, OSC titles, …) is dropped.
+pub fn ansi_to_html(input: &str) -> String {
+    let mut out = String::from(
+        "<pre style=\"background-color:#000000;color:#e5e5e5;font-family:monospace\">",
+    );
+    let mut state = SgrState::default();
+    let mut span_open = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            escape_html(c, &mut out);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters up to a final byte in @..~; only `m` is styling.
+            Some('[') => {
+                let mut params = String::new();
+                let mut fin = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        fin = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if fin != Some('m') {
+                    continue;
+                }
+                let next = {
+                    let mut s = state;
+                    s.apply(&params);
+                    s
+                };
+                if next == state {
+                    continue;
+                }
+                state = next;
+                if span_open {
+                    out.push_str("</span>");
+                    span_open = false;
+                }
+                let css = state.css();
+                if !css.is_empty() {
+                    let _ = write!(out, "<span style=\"{css}\">");
+                    span_open = true;
+                }
+            }
+            // OSC: skip to BEL or ST (ESC \).
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Other two-byte escapes carry nothing worth rendering.
+            _ => {}
+        }
+    }
+
+    if span_open {
+        out.push_str("</span>");
+    }
+    out.push_str("</pre>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_keeps_colours_and_escapes_markup() {
+        let html = ansi_to_html("\x1b[1;31mred <b>\x1b[0m plain & \x1b[38;5;208mo\x1b[m");
+        assert!(
+            html.contains("<span style=\"color:#cd0000;font-weight:bold;\">red &lt;b&gt;</span>")
+        );
+        assert!(html.contains(" plain &amp; "));
+        assert!(html.contains("<span style=\"color:#ff8700;\">o</span>"));
+        assert!(html.ends_with("</pre>\n"));
+    }
+
+    #[test]
+    fn html_handles_truecolor_background_and_drops_other_escapes() {
+        let html = ansi_to_html("\x1b]0;title\x07\x1b[2J\x1b[48;2;1;2;3mx\x1b[49my");
+        assert!(html.contains("<span style=\"background-color:#010203;\">x</span>y"));
+        assert!(!html.contains("title"));
+    }
+
+    #[test]
+    fn palette_maps_cube_and_greys() {
+        assert_eq!(palette(16), (0, 0, 0));
+        assert_eq!(palette(231), (255, 255, 255));
+        assert_eq!(palette(232), (8, 8, 8));
+    }
+}
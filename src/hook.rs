@@ -294,35 +294,43 @@ fn info_from_value(value: &Value, ts: i64) -> Option<ClaudeInfo> {
 
 /// Apply the current hook states to a session tree, recomputing the
 /// per-pane / per-window / per-session markers. Always recomputes from the
-/// files on disk, so a marker that has gone away is cleared too.
-pub fn apply_states(sessions: &mut [TmuxSession]) {
+/// files on disk, so a marker that has gone away is cleared too. Returns
+/// whether anything changed.
+pub fn apply_states(sessions: &mut [TmuxSession]) -> bool {
     let map = load_states();
+    let mut changed = false;
     for session in sessions.iter_mut() {
         let mut session_state = None;
         for window in session.windows.iter_mut() {
             let mut window_state = None;
             for pane in window.panes.iter_mut() {
-                match map.get(&pane.id) {
-                    Some(info) => {
-                        pane.claude_state = Some(info.state);
-                        pane.claude_activity = info.activity.clone();
-                        pane.claude_state_since = info.state_since;
-                        pane.claude_cwd = info.cwd.clone();
-                    }
-                    None => {
-                        pane.claude_state = None;
-                        pane.claude_activity = None;
-                        pane.claude_state_since = None;
-                        pane.claude_cwd = None;
-                    }
-                }
+                let (state, activity, since, cwd) = match map.get(&pane.id) {
+                    Some(info) => (
+                        Some(info.state),
+                        info.activity.clone(),
+                        info.state_since,
+                        info.cwd.clone(),
+                    ),
+                    None => (None, None, None, None),
+                };
+                changed |= pane.claude_state != state
+                    || pane.claude_activity != activity
+                    || pane.claude_state_since != since
+                    || pane.claude_cwd != cwd;
+                pane.claude_state = state;
+                pane.claude_activity = activity;
+                pane.claude_state_since = since;
+                pane.claude_cwd = cwd;
                 window_state = ClaudeState::merge(window_state, pane.claude_state);
             }
+            changed |= window.claude_state != window_state;
             window.claude_state = window_state;
             session_state = ClaudeState::merge(session_state, window_state);
         }
+        changed |= session.claude_state != session_state;
         session.claude_state = session_state;
     }
+    changed
 }
 
 // =============================================================================
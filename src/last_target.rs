@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use tracing::warn;
+
+// =============================================================================
+// Last target — where the deck was looking when it last switched or quit
+// =============================================================================
+//
+// The current target (`=session:window.pane`, or `=session:window` from the
+// MultiPreview) is written to a one-line file in the state directory, next to
+// the log, whenever the deck switches the client and when it quits. The next
+// run selects it again once the first listing arrives.
+
+fn path() -> Option<PathBuf> {
+    let dirs = ProjectDirs::from("dev", "tkcd", "tmux-deck")?;
+    let dir = dirs.state_dir().unwrap_or_else(|| dirs.data_local_dir());
+    Some(dir.join("last_target"))
+}
+
+/// The target saved by the previous run, if any.
+pub fn load() -> Option<String> {
+    let contents = std::fs::read_to_string(path()?).ok()?;
+    Some(contents.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Remember `target` for the next run (best effort).
+pub fn save(target: &str) {
+    let Some(path) = path() else {
+        return;
+    };
+    if let Some(parent) = path.parent()
+        && let Err(e) = std::fs::create_dir_all(parent)
+    {
+        warn!("failed to create state dir for the last target: {e}");
+        return;
+    }
+    if let Err(e) = std::fs::write(&path, format!("{target}\n")) {
+        warn!("failed to write the last target: {e}");
+    }
+}
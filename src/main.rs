@@ -2,16 +2,24 @@ mod actor;
 mod agents;
 mod app;
 mod cli;
+mod clipboard;
 mod config;
+mod export;
 mod group;
 mod hook;
+mod last_target;
+mod preview;
+mod resources;
+mod server;
+mod template;
 mod termscreen;
 mod ui;
+mod workspace;
 
 use std::io;
 use std::time::Duration;
 
-use color_eyre::Result;
+use color_eyre::{Result, eyre::eyre};
 use crossterm::{
     ExecutableCommand,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
@@ -48,17 +56,40 @@ async fn main() -> Result<()> {
                 }
                 HookAction::Install { project } => hook::run_install(*project),
             },
+            Command::Capture {
+                target,
+                html,
+                output,
+            } => export::run_capture(target.as_deref(), *html, output.as_deref()),
+            Command::Load { file } => {
+                let template = template::load(file)?;
+                TmuxActor::load_template(&template)
+                    .await
+                    .map_err(|e| eyre!("{}: {e}", file.display()))?;
+                println!("created session '{}'", template.name);
+                Ok(())
+            }
+            Command::Save { session } => {
+                let template = TmuxActor::save_template(session)
+                    .await
+                    .map_err(|e| eyre!("{session}: {e}"))?;
+                print!("{}", template::to_toml(&template)?);
+                Ok(())
+            }
         };
     }
 
-    // Load user config (best-effort): CLI --config > XDG config.toml > defaults.
-    let config = Config::load(cmd.config.as_deref());
-    // CLI --interval wins over the config, which wins over the built-in default.
-    let interval_ms = cmd
-        .interval
-        .or(config.preview.interval)
-        .unwrap_or(DEFAULT_INTERVAL_MS);
+    // Load user config: CLI --config > XDG config.toml > defaults. A config
+    // that exists but does not parse stops here with the error.
+    let config = Config::load(cmd.config.as_deref())?;
 
+    if cmd.dump_json {
+        let sessions = TmuxActor::snapshot(&config.behavior.skip_fields)
+            .await
+            .map_err(|e| eyre!("listing tmux failed: {e}"))?;
+        println!("{}", serde_json::to_string_pretty(&sessions)?);
+        return Ok(());
+    }
     let project_dir =
         ProjectDirs::from("dev", "tkcd", "tmux-deck").expect("cannot determine project directory");
     let log_dir = project_dir.state_dir().expect("failed to get log dir");
@@ -82,7 +113,7 @@ async fn main() -> Result<()> {
     io::stdout().execute(EnterAlternateScreen)?;
     let terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
 
-    let result = run_app(terminal, config, interval_ms).await;
+    let result = run_app(terminal, config, cmd).await;
 
     disable_raw_mode()?;
     io::stdout().execute(LeaveAlternateScreen)?;
@@ -93,7 +124,7 @@ async fn main() -> Result<()> {
 async fn run_app(
     terminal: Terminal<CrosstermBackend<io::Stdout>>,
     config: Config,
-    interval_ms: u64,
+    cmd: Cli,
 ) -> Result<()> {
     // Create channels.
     // tmux_cmd_*: high-priority user-initiated commands.
@@ -106,12 +137,37 @@ async fn run_app(
     // Create shared refresh control
     let refresh_control = RefreshControl::new();
 
+    // CLI --interval wins over the config, which wins over the built-in default.
+    let interval_ms = cmd
+        .interval
+        .or(config.preview.interval)
+        .unwrap_or(DEFAULT_INTERVAL_MS);
+
     // Initialize UIState
-    let state = UIState::new(config);
+    let mut state = UIState::new(config);
+    state.read_only = cmd.read_only;
+    state.force_redraw = cmd.force_redraw;
+    if let Some(name) = cmd.session {
+        state.scope_to_session(name);
+    }
+    state.pending_reveal = cmd.target;
+    state.config_path = cmd.config;
+    state.interval_from_cli = cmd.interval.is_some();
+    if std::env::var_os("TMUX").is_some() {
+        state.note_nested_tmux(std::env::var("TMUX_PANE").ok());
+    }
     let interval = Duration::from_millis(interval_ms);
+    state.interval = interval;
 
     // Create actors
-    let tmux_actor = TmuxActor::new(tmux_cmd_rx, tmux_capture_rx, tmux_resp_tx);
+    let tmux_actor = TmuxActor::new(
+        tmux_cmd_rx,
+        tmux_capture_rx,
+        tmux_resp_tx,
+        refresh_control.clone(),
+        &state.behavior.skip_fields,
+        state.read_only,
+    );
     let refresh_actor = RefreshActor::new(
         tmux_capture_tx.clone(),
         ui_event_tx,
@@ -0,0 +1,489 @@
+//! Turning a `capture-pane -e` dump into something the preview can show.
+//!
+//! Captures arrive as text with SGR escapes. The styled path parses them into a
+//! ratatui [`Text`]; the plain path strips every escape sequence, and doubles as
+//! the fallback when the styled parse fails. Cropping and vertical alignment
+//! operate on the resulting `Text`, so both paths share them.
+
+use ansi_to_tui::IntoText;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span, Text};
+use unicode_width::UnicodeWidthChar;
+
+use crate::config::{ColorDepth, PreviewAlign};
+
+/// Shown in place of a preview whose capture is predominantly non-printable
+/// (see [`crate::config::PreviewConfig::binary_threshold`]).
+pub const BINARY_PREVIEW_PLACEHOLDER: &str = "(binary output)";
+
+/// Preview text for the pane tmux-deck is running in, shown instead of a
+/// capture of the deck's own screen.
+pub const SELF_PREVIEW_PLACEHOLDER: &str = "(this pane is running tmux-deck)";
+
+/// Fraction of the bytes of `content`, a capture as tmux wrote it, that are
+/// printable. Whitespace and ESC (the start of the colour sequences
+/// `capture-pane -e` emits) count as printable; other control characters and
+/// bytes that are not valid UTF-8 do not. A character weighs as many bytes as
+/// it takes, so a run of invalid bytes is not shrunk to one U+FFFD first.
+/// Empty content counts as fully printable.
+pub fn printable_ratio(content: &[u8]) -> f64 {
+    if content.is_empty() {
+        return 1.0;
+    }
+    let printable: usize = content
+        .utf8_chunks()
+        .flat_map(|chunk| chunk.valid().chars())
+        .filter(|&c| matches!(c, '\n' | '\r' | '\t' | '\x1b') || !c.is_control())
+        .map(char::len_utf8)
+        .sum();
+    printable as f64 / content.len() as f64
+}
+
+/// Parse ANSI-coloured `content` into styled text. `None` if the escapes
+/// cannot be parsed.
+pub fn to_styled(content: &str) -> Option<Text<'static>> {
+    content.as_bytes().into_text().ok()
+}
+
+/// `content` with every escape sequence (CSI, OSC, two-byte ESC) and stray
+/// control character removed; newlines and tabs are kept.
+pub fn to_plain_text(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters up to a final byte in @..~.
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: up to BEL or ST (ESC \).
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\n' | '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The last `max_lines` lines of `text` — the bottom of a pane is where the
+/// prompt and the latest output live — each cut to the `width` columns from
+/// column `skip` on, so a frame never copies (or lays out) more of a huge
+/// single-line capture than fits.
+pub fn tail(text: &Text<'static>, max_lines: usize, skip: usize, width: usize) -> Text<'static> {
+    tail_above(text, max_lines, 0, skip, width)
+}
+
+/// Like [`tail`], but ending `up` lines above the bottom of `text`, for a
+/// preview scrolled back into its capture.
+pub fn tail_above(
+    text: &Text<'static>,
+    max_lines: usize,
+    up: usize,
+    skip: usize,
+    width: usize,
+) -> Text<'static> {
+    let end = text.lines.len().saturating_sub(up);
+    let start = end.saturating_sub(max_lines);
+    Text::from(
+        text.lines[start..end]
+            .iter()
+            .map(|line| clip_line(line, skip, width))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Like [`tail_above`], but with each line wrapped at `width` columns
+/// instead of cut: the last `max_lines` rows, ending `up` rows above the
+/// bottom. Only the lines those rows come from are wrapped.
+pub fn tail_above_wrapped(
+    text: &Text<'static>,
+    max_lines: usize,
+    up: usize,
+    width: usize,
+) -> Text<'static> {
+    // Bottom row first.
+    let mut rows = Vec::new();
+    for line in text.lines.iter().rev() {
+        if rows.len() >= up + max_lines {
+            break;
+        }
+        rows.extend(wrap_line(line, width).into_iter().rev());
+    }
+    let mut rows: Vec<Line<'static>> = rows.into_iter().skip(up).take(max_lines).collect();
+    rows.reverse();
+    Text::from(rows)
+}
+
+/// Rows `text` takes wrapped at `width` columns.
+pub fn wrapped_height(text: &Text<'static>, width: usize) -> usize {
+    text.lines.iter().map(|line| wrap_line(line, width).len()).sum()
+}
+
+/// `line` broken into rows of at most `width` columns, as a terminal wraps
+/// it: a wide character that would straddle the edge starts the next row.
+/// An empty line is one empty row.
+fn wrap_line(line: &Line<'static>, width: usize) -> Vec<Line<'static>> {
+    let width = width.max(1);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut used = 0;
+    for span in &line.spans {
+        let mut chunk = String::new();
+        for c in span.content.chars() {
+            let w = c.width().unwrap_or(0);
+            if used > 0 && used + w > width {
+                if !chunk.is_empty() {
+                    row.push(Span::styled(std::mem::take(&mut chunk), span.style));
+                }
+                rows.push(Line { spans: std::mem::take(&mut row), ..line.clone() });
+                used = 0;
+            }
+            chunk.push(c);
+            used += w;
+        }
+        if !chunk.is_empty() {
+            row.push(Span::styled(chunk, span.style));
+        }
+    }
+    rows.push(Line { spans: row, ..line.clone() });
+    rows
+}
+
+/// The lines a command added to a pane, given plain captures taken just
+/// before its keys were sent and some time after. `before` is lined up with
+/// the start of `after` by the longest run of its lines (the prompt line the
+/// command was typed on excepted) that `after` begins with, which also
+/// covers output that scrolled the top of the capture away; everything past
+/// the prompt line is the command's. When nothing lines up — the command
+/// cleared the screen — all of `after` is returned.
+pub fn appended_lines(before: &str, after: &str) -> Vec<String> {
+    let trimmed = |s: &str| -> Vec<String> {
+        let mut lines: Vec<String> = s.lines().map(|l| l.trim_end().to_string()).collect();
+        while lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines
+    };
+    let before = trimmed(before);
+    let after = trimmed(after);
+    let settled = before.len().saturating_sub(1);
+    (0..settled)
+        .find(|&s| after.starts_with(&before[s..settled]))
+        .map(|s| after[(settled - s + 1).min(after.len())..].to_vec())
+        .unwrap_or(after)
+}
+
+/// Columns `skip..skip + width` of `line`. Only the columns up to the right
+/// edge are scanned, and a wide character straddling either edge is dropped.
+fn clip_line(line: &Line<'static>, skip: usize, width: usize) -> Line<'static> {
+    let mut skip = skip;
+    let mut left = width;
+    let mut spans = Vec::new();
+    for span in &line.spans {
+        let (mut start, mut end) = (0, 0);
+        for (i, c) in span.content.char_indices() {
+            let w = c.width().unwrap_or(0);
+            if skip > 0 {
+                // Still left of the viewport; a character reaching into it
+                // would only show in part, so it goes too.
+                skip = skip.saturating_sub(w);
+                start = i + c.len_utf8();
+                end = start;
+                continue;
+            }
+            if w > left {
+                left = 0;
+                break;
+            }
+            left -= w;
+            end = i + c.len_utf8();
+        }
+        if end > start {
+            spans.push(Span::styled(span.content[start..end].to_string(), span.style));
+        }
+        if left == 0 {
+            break;
+        }
+    }
+    Line {
+        spans,
+        ..line.clone()
+    }
+}
+
+/// `text` with its colours mapped down to what a `depth` terminal can show.
+pub fn reduce_colors(mut text: Text<'static>, depth: ColorDepth) -> Text<'static> {
+    if depth == ColorDepth::TrueColor {
+        return text;
+    }
+    let reduce = |style: &mut Style| {
+        style.fg = style.fg.map(|c| reduce_color(c, depth));
+        style.bg = style.bg.map(|c| reduce_color(c, depth));
+        style.underline_color = style.underline_color.map(|c| reduce_color(c, depth));
+    };
+    reduce(&mut text.style);
+    for line in &mut text.lines {
+        reduce(&mut line.style);
+        for span in &mut line.spans {
+            reduce(&mut span.style);
+        }
+    }
+    text
+}
+
+/// The 16 basic colours, in palette order, with xterm's default RGB values.
+const BASIC_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel values of the 6×6×6 cube at palette indices 16–231.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The nearest colour to `color` a `depth` terminal has. Named colours are
+/// left alone; they exist at every depth.
+fn reduce_color(color: Color, depth: ColorDepth) -> Color {
+    match (color, depth) {
+        (_, ColorDepth::TrueColor) => color,
+        (Color::Rgb(r, g, b), ColorDepth::Palette256) => Color::Indexed(rgb_to_256((r, g, b))),
+        (Color::Rgb(r, g, b), ColorDepth::Palette16) => rgb_to_16((r, g, b)),
+        (Color::Indexed(n), ColorDepth::Palette16) => rgb_to_16(palette_rgb(n)),
+        _ => color,
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).unsigned_abs().pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// The closest of the cube and grey-ramp entries of the 256-colour palette.
+fn rgb_to_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |v: u8| match v {
+        0..48 => 0,
+        48..115 => 1,
+        _ => (v - 35) / 40,
+    };
+    let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = 16 + 36 * r + 6 * g + b;
+    // Grey ramp 232–255 runs 8, 18, …, 238.
+    let avg = (u32::from(rgb.0) + u32::from(rgb.1) + u32::from(rgb.2)) / 3;
+    let step = (avg.saturating_sub(3) / 10).min(23) as u8;
+    let grey = 232 + step;
+    if distance(rgb, palette_rgb(grey)) < distance(rgb, palette_rgb(cube)) {
+        grey
+    } else {
+        cube
+    }
+}
+
+fn rgb_to_16(rgb: (u8, u8, u8)) -> Color {
+    BASIC_COLORS
+        .iter()
+        .min_by_key(|(_, c)| distance(rgb, *c))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
+/// xterm's RGB value for palette entry `n`.
+fn palette_rgb(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..16 => BASIC_COLORS[usize::from(n)].1,
+        16..232 => {
+            let i = n - 16;
+            let level = |x: u8| CUBE_LEVELS[usize::from(x)];
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + 10 * (n - 232);
+            (v, v, v)
+        }
+    }
+}
+
+/// Shift an underfilled preview down per `align`, padding with blank rows.
+/// Trailing blank lines are dropped first so they do not count as content.
+pub fn align_underfilled(text: &mut Text<'_>, align: PreviewAlign, height: usize) {
+    if align == PreviewAlign::Top {
+        return;
+    }
+    while text.lines.last().is_some_and(|l| l.to_string().trim().is_empty()) {
+        text.lines.pop();
+    }
+    let offset = align.offset(text.lines.len(), height);
+    text.lines.splice(0..0, std::iter::repeat_n(Line::default(), offset));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &Text<'_>) -> Vec<String> {
+        text.lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn printable_ratio_counts_ansi_as_printable_and_garbage_as_not() {
+        assert_eq!(printable_ratio(b""), 1.0);
+        assert_eq!(printable_ratio(b"\x1b[31mred\x1b[0m\n"), 1.0);
+        assert_eq!(printable_ratio(b"\xff\xfe\x01a"), 0.25);
+        // Multi-byte characters count in full, and a truncated sequence as
+        // every byte of it rather than as the one U+FFFD it decodes to.
+        assert_eq!(printable_ratio("déjà".as_bytes()), 1.0);
+        assert_eq!(printable_ratio(b"\xe2\x82ab"), 0.5);
+    }
+
+    #[test]
+    fn plain_text_strips_escapes() {
+        assert_eq!(to_plain_text("\x1b[1;31mred\x1b[0m\tok\n"), "red\tok\n");
+        assert_eq!(to_plain_text("\x1b]0;title\x07a\x1b]2;t\x1b\\b\r"), "ab");
+        assert_eq!(to_plain_text("plain"), "plain");
+        assert_eq!(to_plain_text(""), "");
+    }
+
+    #[test]
+    fn styled_and_plain_paths_agree_on_text() {
+        for input in ["\x1b[32mgreen\x1b[0m\nnext", "plain\ntext", ""] {
+            let styled = to_styled(input).expect("parses");
+            assert_eq!(lines(&styled).join("\n"), to_plain_text(input));
+        }
+    }
+
+    #[test]
+    fn tail_keeps_the_last_lines() {
+        let text = Text::raw("a\nb\nc");
+        assert_eq!(lines(&tail(&text, 2, 0, 80)), vec!["b", "c"]);
+        assert_eq!(lines(&tail(&text, 10, 0, 80)), vec!["a", "b", "c"]);
+        assert!(tail(&Text::default(), 3, 0, 80).lines.is_empty());
+        assert_eq!(lines(&tail_above(&text, 2, 1, 0, 80)), vec!["a", "b"]);
+        assert_eq!(lines(&tail_above(&text, 2, 5, 0, 80)), Vec::<String>::new());
+    }
+
+    #[test]
+    fn appended_lines_are_what_follows_the_prompt() {
+        let before = "old\n$ \n\n\n";
+        assert_eq!(appended_lines(before, "old\n$ ls\na\nb\n$ \n\n"), ["a", "b", "$"]);
+        // Output that pushed the top line off the capture still lines up.
+        let before = "one\ntwo\n$ ";
+        assert_eq!(appended_lines(before, "two\n$ ls\na\n$ "), ["a", "$"]);
+        // A cleared screen has nothing in common: keep the whole capture.
+        assert_eq!(appended_lines(before, "$ "), ["$"]);
+    }
+
+    #[test]
+    fn tail_cuts_long_lines_to_the_width() {
+        let long = format!("\x1b[31m{}\x1b[0m", "x".repeat(100_000));
+        let text = to_styled(&long).unwrap();
+        let clipped = tail(&text, 5, 0, 80);
+        assert_eq!(clipped.lines[0].width(), 80);
+        assert_eq!(clipped.lines[0].spans[0].style, text.lines[0].spans[0].style);
+        // A double-width character is never split at the edge.
+        assert_eq!(lines(&tail(&Text::raw("ab日本"), 1, 0, 3)), vec!["ab"]);
+        assert_eq!(lines(&tail(&Text::raw("ab日本"), 1, 0, 4)), vec!["ab日"]);
+    }
+
+    #[test]
+    fn tail_scrolls_lines_horizontally() {
+        let text = Text::from(vec![
+            Line::from(vec![Span::raw("abc"), Span::raw("def")]),
+            Line::raw("ab"),
+        ]);
+        assert_eq!(lines(&tail(&text, 2, 2, 3)), vec!["cde", ""]);
+        assert_eq!(lines(&tail(&text, 2, 4, 10)), vec!["ef", ""]);
+        // Half of a wide character is not drawn at the left edge either.
+        assert_eq!(lines(&tail(&Text::raw("日本語"), 1, 1, 4)), vec!["本語"]);
+    }
+
+    #[test]
+    fn wrapped_tail_counts_rows_not_lines() {
+        let text = Text::from(vec![
+            Line::from(vec![Span::raw("abc"), Span::raw("defg")]),
+            Line::raw(""),
+            Line::raw("a日本"),
+        ]);
+        assert_eq!(wrapped_height(&text, 3), 6);
+        // The wide character that would straddle the edge moves down a row.
+        assert_eq!(lines(&tail_above_wrapped(&text, 3, 0, 3)), vec!["", "a日", "本"]);
+        assert_eq!(lines(&tail_above_wrapped(&text, 2, 3, 3)), vec!["def", "g"]);
+        assert_eq!(lines(&tail_above_wrapped(&text, 9, 4, 3)), vec!["abc", "def"]);
+    }
+
+    #[test]
+    fn bottom_alignment_ignores_trailing_blank_lines() {
+        let mut text = Text::raw("a\nb\n\n  \n");
+        align_underfilled(&mut text, PreviewAlign::Bottom, 5);
+        assert_eq!(lines(&text), vec!["", "", "", "a", "b"]);
+    }
+
+    #[test]
+    fn rgb_maps_to_the_nearest_palette_colour() {
+        assert_eq!(rgb_to_256((255, 0, 0)), 196);
+        assert_eq!(rgb_to_256((0, 0, 0)), 16);
+        assert_eq!(rgb_to_256((95, 135, 175)), 67);
+        // Greys prefer the finer grey ramp over the cube.
+        assert_eq!(rgb_to_256((128, 128, 128)), 244);
+        assert_eq!(rgb_to_16((250, 10, 10)), Color::LightRed);
+        assert_eq!(rgb_to_16((120, 120, 130)), Color::DarkGray);
+        assert_eq!(reduce_color(Color::Indexed(196), ColorDepth::Palette16), Color::LightRed);
+        assert_eq!(reduce_color(Color::Blue, ColorDepth::Palette16), Color::Blue);
+    }
+
+    #[test]
+    fn reduce_colors_rewrites_captured_styles() {
+        let text = to_styled("\x1b[38;2;255;0;0mred\x1b[48;2;0;0;0m on black").unwrap();
+        assert_eq!(reduce_colors(text.clone(), ColorDepth::TrueColor), text);
+        let reduced = reduce_colors(text, ColorDepth::Palette256);
+        let spans = &reduced.lines[0].spans;
+        assert_eq!(spans[0].style.fg, Some(Color::Indexed(196)));
+        assert_eq!(spans[1].style.bg, Some(Color::Indexed(16)));
+    }
+
+    #[test]
+    fn clipping_keeps_each_columns_own_true_colour() {
+        let red = Color::Rgb(255, 0, 0);
+        let green = Color::Rgb(0, 255, 0);
+        let text = to_styled("\x1b[48;2;255;0;0m    \x1b[48;2;0;255;0m    \x1b[0m").unwrap();
+        let text = reduce_colors(text, ColorDepth::TrueColor);
+        // A viewport straddling the two blocks keeps both colours, each on
+        // exactly its own columns.
+        let clipped = tail(&text, 1, 2, 4);
+        let runs: Vec<(usize, Option<Color>)> = clipped.lines[0]
+            .spans
+            .iter()
+            .map(|s| (s.width(), s.style.bg))
+            .collect();
+        assert_eq!(runs, [(2, Some(red)), (2, Some(green))]);
+    }
+}
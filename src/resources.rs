@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::app::TmuxSession;
+
+// =============================================================================
+// ResourceSampler — per-pane CPU and memory from /proc (Linux only)
+// =============================================================================
+//
+// Each pane is charged for its whole process tree: the shell tmux started
+// (`pane_pid`) and everything below it, so a build running under the shell
+// shows up on its pane. CPU is the tree's share of the CPU time spent since
+// the previous sample, in percent of one core like `top`; memory is the sum
+// of the tree's resident sets.
+//
+// Everything is read from /proc. Where that is missing (macOS, BSD) a sample
+// finds no processes and the panes simply carry no figures.
+//
+// The tree is re-listed on tmux events rather than on a timer, so the figures
+// are kept here by pane id and stamped onto every listing, and re-sampled at
+// most every `SAMPLE_EVERY` from the UI's tick.
+
+/// Minimum time between two samples; shorter spans make CPU % jumpy.
+const SAMPLE_EVERY: Duration = Duration::from_secs(2);
+
+/// What one process contributes to its pane.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ProcStat {
+    ppid: u32,
+    /// utime + stime, in clock ticks.
+    ticks: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct ResourceSampler {
+    /// Ticks of each pane's tree at the previous sample, by pane id.
+    prev_ticks: HashMap<String, u64>,
+    /// Total CPU time of the machine at the previous sample.
+    prev_total: Option<u64>,
+    /// The latest figures (CPU %, RSS kB), by pane id.
+    usage: HashMap<String, (Option<f32>, u64)>,
+    sampled_at: Option<Instant>,
+}
+
+impl ResourceSampler {
+    /// Fill in `cpu_percent` and `rss_kb` of every pane, sampling /proc
+    /// again first when the last sample is old enough. Returns whether any
+    /// figure changed.
+    pub fn annotate(&mut self, sessions: &mut [TmuxSession]) -> bool {
+        if self.sampled_at.is_none_or(|at| at.elapsed() >= SAMPLE_EVERY) {
+            self.sample(sessions);
+            self.sampled_at = Some(Instant::now());
+        }
+        let mut changed = false;
+        for pane in sessions.iter_mut().flat_map(|s| &mut s.windows).flat_map(|w| &mut w.panes) {
+            let usage = self.usage.get(&pane.id);
+            let (cpu, rss) = (usage.and_then(|u| u.0), usage.map(|u| u.1));
+            changed |= pane.cpu_percent != cpu || pane.rss_kb != rss;
+            pane.cpu_percent = cpu;
+            pane.rss_kb = rss;
+        }
+        changed
+    }
+
+    /// Take new figures for the panes of `sessions`. CPU needs two samples,
+    /// so the first one only records memory.
+    fn sample(&mut self, sessions: &[TmuxSession]) {
+        let Some((total, cpus)) = std::fs::read_to_string("/proc/stat")
+            .ok()
+            .as_deref()
+            .and_then(parse_cpu_total)
+        else {
+            return;
+        };
+        let procs = read_procs();
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for (&pid, stat) in &procs {
+            children.entry(stat.ppid).or_default().push(pid);
+        }
+
+        let elapsed = self.prev_total.map(|prev| total.saturating_sub(prev));
+        let mut ticks_now = HashMap::new();
+        self.usage.clear();
+        for pane in sessions.iter().flat_map(|s| &s.windows).flat_map(|w| &w.panes) {
+            if pane.pid == 0 || !procs.contains_key(&pane.pid) {
+                continue;
+            }
+            let tree = process_tree(pane.pid, &children);
+            let ticks: u64 = tree.iter().filter_map(|p| procs.get(p)).map(|s| s.ticks).sum();
+            let rss_kb = tree.iter().filter_map(|&p| read_rss_kb(p)).sum();
+            let cpu = match (elapsed, self.prev_ticks.get(&pane.id)) {
+                (Some(elapsed), Some(&prev)) if elapsed > 0 => {
+                    let used = ticks.saturating_sub(prev) as f32;
+                    Some(used / elapsed as f32 * cpus as f32 * 100.0)
+                }
+                _ => None,
+            };
+            self.usage.insert(pane.id.clone(), (cpu, rss_kb));
+            ticks_now.insert(pane.id.clone(), ticks);
+        }
+        self.prev_ticks = ticks_now;
+        self.prev_total = Some(total);
+    }
+}
+
+/// `pid` and every process below it.
+fn process_tree(pid: u32, children: &HashMap<u32, Vec<u32>>) -> Vec<u32> {
+    let mut tree = Vec::new();
+    let mut stack = vec![pid];
+    while let Some(p) = stack.pop() {
+        if tree.contains(&p) {
+            continue;
+        }
+        tree.push(p);
+        if let Some(kids) = children.get(&p) {
+            stack.extend(kids);
+        }
+    }
+    tree
+}
+
+/// Every process's parent and CPU time, by pid.
+fn read_procs() -> HashMap<u32, ProcStat> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(|e| e.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| {
+            let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+            Some((pid, parse_stat(&stat)?))
+        })
+        .collect()
+}
+
+fn read_rss_kb(pid: u32) -> Option<u64> {
+    parse_vm_rss(&std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?)
+}
+
+/// Parent pid and utime + stime from a `/proc/<pid>/stat` line. The command
+/// name is in parentheses and may hold spaces or `)`, so fields are counted
+/// from the last `)`.
+fn parse_stat(line: &str) -> Option<ProcStat> {
+    let (_, rest) = line.rsplit_once(')')?;
+    // After the name: state(3) ppid(4) ... utime(14) stime(15).
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let ppid = fields.get(1)?.parse().ok()?;
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some(ProcStat {
+        ppid,
+        ticks: utime + stime,
+    })
+}
+
+/// Total CPU time of all cores (the `cpu` line) and the number of cores
+/// (`cpuN` lines) from `/proc/stat`.
+fn parse_cpu_total(stat: &str) -> Option<(u64, usize)> {
+    let mut lines = stat.lines();
+    let total = lines
+        .next()?
+        .strip_prefix("cpu ")?
+        .split_whitespace()
+        .filter_map(|f| f.parse::<u64>().ok())
+        .sum();
+    let cpus = lines.take_while(|l| l.starts_with("cpu")).count().max(1);
+    Some((total, cpus))
+}
+
+/// `VmRSS` in kB from `/proc/<pid>/status`; kernel threads have none.
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proc_files_parse() {
+        let stat = "4242 (my (odd) cmd) S 17 4242 17 34816 4242 4194304 \
+                    120 0 0 0 250 30 0 0 20 0 1 0 8 1000 200";
+        assert_eq!(parse_stat(stat), Some(ProcStat { ppid: 17, ticks: 280 }));
+        assert_eq!(parse_stat("garbage"), None);
+
+        let proc_stat = "cpu  100 0 50 1000 0 0 0 0 0 0\n\
+                         cpu0 50 0 25 500 0 0 0 0 0 0\n\
+                         cpu1 50 0 25 500 0 0 0 0 0 0\n\
+                         intr 1 2 3\n";
+        assert_eq!(parse_cpu_total(proc_stat), Some((1150, 2)));
+
+        assert_eq!(parse_vm_rss("Name:\tzsh\nVmRSS:\t    5120 kB\n"), Some(5120));
+        assert_eq!(parse_vm_rss("Name:\tkthreadd\n"), None);
+    }
+
+    #[test]
+    fn a_pane_is_charged_for_its_whole_tree() {
+        let children = HashMap::from([(1, vec![2, 3]), (3, vec![4]), (9, vec![10])]);
+        let mut tree = process_tree(1, &children);
+        tree.sort();
+        assert_eq!(tree, [1, 2, 3, 4]);
+    }
+}
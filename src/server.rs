@@ -0,0 +1,92 @@
+//! Which tmux server the deck talks to.
+//!
+//! A server is named the way tmux's own flags name one: `None` is the server
+//! tmux picks by default (the one in `$TMUX` when the deck runs inside tmux),
+//! a bare name is a socket in the tmux tmpdir (`-L name`), and anything with a
+//! `/` is a socket path (`-S path`).
+
+use std::path::{Path, PathBuf};
+
+/// tmux flags that select `server`; empty for the default server.
+pub fn socket_args(server: Option<&str>) -> Vec<&str> {
+    match server {
+        None => Vec::new(),
+        Some(s) if s.contains('/') => vec!["-S", s],
+        Some(s) => vec!["-L", s],
+    }
+}
+
+/// How `server` is shown in the picker and status line.
+pub fn label(server: Option<&str>) -> &str {
+    server.unwrap_or("(default)")
+}
+
+/// Where tmux keeps its per-user socket directories: `$TMUX_TMPDIR`, else
+/// `/tmp`.
+fn tmux_tmpdir() -> PathBuf {
+    std::env::var_os("TMUX_TMPDIR")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+}
+
+/// Socket paths in the `tmux-*` directories under `tmpdir`, sorted. Other
+/// users' directories are unreadable and so skipped, and each `default`
+/// socket is left out as the picker always offers the default server.
+pub fn discover_sockets_in(tmpdir: &Path) -> Vec<String> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let Ok(dirs) = std::fs::read_dir(tmpdir) else {
+        return Vec::new();
+    };
+    let mut sockets: Vec<String> = dirs
+        .flatten()
+        .filter(|d| d.file_name().to_string_lossy().starts_with("tmux-"))
+        .filter_map(|d| std::fs::read_dir(d.path()).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_socket()) && e.file_name() != "default")
+        .map(|e| e.path().to_string_lossy().into_owned())
+        .collect();
+    sockets.sort();
+    sockets
+}
+
+/// The servers the picker offers: the default one, then `configured`, then
+/// every discovered socket, without duplicates.
+pub fn known_servers(configured: &[String]) -> Vec<Option<String>> {
+    let mut servers = vec![None];
+    for server in configured.iter().cloned().chain(discover_sockets_in(&tmux_tmpdir())) {
+        if !servers.contains(&Some(server.clone())) {
+            servers.push(Some(server));
+        }
+    }
+    servers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_args_follow_tmux_flags() {
+        assert!(socket_args(None).is_empty());
+        assert_eq!(socket_args(Some("work")), ["-L", "work"]);
+        assert_eq!(socket_args(Some("/tmp/tmux-1000/home")), ["-S", "/tmp/tmux-1000/home"]);
+        assert_eq!(label(None), "(default)");
+    }
+
+    #[test]
+    fn discovery_lists_sockets_but_not_default() {
+        let root = std::env::temp_dir().join(format!("tmux-deck-server-{}", std::process::id()));
+        let dir = root.join("tmux-1000");
+        std::fs::create_dir_all(&dir).unwrap();
+        let _work = std::os::unix::net::UnixListener::bind(dir.join("work")).unwrap();
+        let _default = std::os::unix::net::UnixListener::bind(dir.join("default")).unwrap();
+        std::fs::write(dir.join("notes"), "").unwrap();
+        std::fs::create_dir_all(root.join("other")).unwrap();
+
+        let found = discover_sockets_in(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(found, [dir.join("work").to_string_lossy()]);
+    }
+}
@@ -0,0 +1,245 @@
+//! Session files for `tmux-deck load` and `tmux-deck save`.
+//!
+//! `load` reads tmuxp-style YAML: a file names the session and lists its
+//! windows, each with an optional layout and its panes; a pane is a command,
+//! a list of commands or empty. `shell_command_before` (at the top, or per
+//! window) is typed into every pane ahead of the pane's own commands. The file
+//! is turned into a [`SessionTemplate`], the same shape the `[[templates]]`
+//! picker builds sessions from, so creating it goes through the tmux actor's
+//! template path and rolls back the same way on failure.
+//!
+//! `save` writes a live session out as that template in TOML, which `load`
+//! reads back (any `.toml` file) and which also fits a `[[templates]]` entry.
+
+use std::path::Path;
+
+use color_eyre::eyre::{bail, eyre};
+use serde::Deserialize;
+
+use crate::config::{SessionTemplate, TemplateWindow};
+
+/// tmux's preset layouts, as accepted by `select-layout`.
+const LAYOUTS: [&str; 7] = [
+    "even-horizontal",
+    "even-vertical",
+    "main-horizontal",
+    "main-horizontal-mirrored",
+    "main-vertical",
+    "main-vertical-mirrored",
+    "tiled",
+];
+
+/// One session file, as tmuxp writes it.
+#[derive(Debug, Deserialize)]
+pub struct SessionFile {
+    #[serde(default)]
+    pub session_name: String,
+    pub start_directory: Option<String>,
+    pub shell_command_before: Option<Commands>,
+    #[serde(default)]
+    pub windows: Vec<WindowFile>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WindowFile {
+    pub window_name: Option<String>,
+    pub layout: Option<String>,
+    pub shell_command_before: Option<Commands>,
+    #[serde(default)]
+    pub panes: Vec<Option<PaneFile>>,
+}
+
+/// A pane: a bare command, or a map with a `shell_command`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum PaneFile {
+    Command(String),
+    Detailed { shell_command: Option<Commands> },
+}
+
+/// One command or a list of them; tmuxp accepts both everywhere.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Commands {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Commands {
+    fn list(commands: Option<&Self>) -> Vec<String> {
+        match commands {
+            None => Vec::new(),
+            Some(Self::One(c)) => vec![c.clone()],
+            Some(Self::Many(cs)) => cs.clone(),
+        }
+    }
+}
+
+/// Read and check the session file at `path`: a TOML template as `save`
+/// writes it when the name ends in `.toml`, tmuxp YAML otherwise.
+pub fn load(path: &Path) -> color_eyre::Result<SessionTemplate> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| eyre!("cannot read {}: {e}", path.display()))?;
+    let template = if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str::<SessionTemplate>(&text)
+            .map_err(|e| eyre!("{}: invalid session file: {e}", path.display()))
+            .and_then(check)
+    } else {
+        serde_yaml::from_str::<SessionFile>(&text)
+            .map_err(|e| eyre!("{}: invalid session file: {e}", path.display()))?
+            .into_template()
+    };
+    template.map_err(|e| eyre!("{}: {e}", path.display()))
+}
+
+/// `template` as a TOML session file.
+pub fn to_toml(template: &SessionTemplate) -> color_eyre::Result<String> {
+    Ok(toml::to_string(template)?)
+}
+
+/// Refuse a template without a name or with a layout tmux would reject.
+fn check(template: SessionTemplate) -> color_eyre::Result<SessionTemplate> {
+    if template.name.trim().is_empty() {
+        bail!("the session name is missing or empty");
+    }
+    for (i, window) in template.windows.iter().enumerate() {
+        if let Some(layout) = window.layout.as_deref()
+            && !is_layout(layout)
+        {
+            bail!(
+                "window {}: unknown layout '{layout}' (expected one of {} or a \
+                 layout string from `tmux list-windows`)",
+                i + 1,
+                LAYOUTS.join(", ")
+            );
+        }
+    }
+    Ok(template)
+}
+
+impl SessionFile {
+    /// The template that builds this session. Each pane's commands become
+    /// one line apiece of the pane command, typed in order.
+    pub fn into_template(self) -> color_eyre::Result<SessionTemplate> {
+        let before = Commands::list(self.shell_command_before.as_ref());
+        let mut windows = Vec::with_capacity(self.windows.len());
+        for window in self.windows {
+            let mut window_before = before.clone();
+            window_before.extend(Commands::list(window.shell_command_before.as_ref()));
+            let mut panes: Vec<String> = window
+                .panes
+                .iter()
+                .map(|pane| {
+                    let own = match pane {
+                        None => Vec::new(),
+                        Some(PaneFile::Command(c)) => vec![c.clone()],
+                        Some(PaneFile::Detailed { shell_command }) => {
+                            Commands::list(shell_command.as_ref())
+                        }
+                    };
+                    let mut lines = window_before.clone();
+                    lines.extend(own);
+                    lines.join("\n")
+                })
+                .collect();
+            // A window without panes still gets its one shell.
+            if panes.is_empty() && !window_before.is_empty() {
+                panes.push(window_before.join("\n"));
+            }
+            windows.push(TemplateWindow {
+                name: window.window_name,
+                panes,
+                layout: window.layout,
+            });
+        }
+        check(SessionTemplate {
+            name: self.session_name.trim().to_string(),
+            description: String::new(),
+            root: self.start_directory,
+            windows,
+        })
+    }
+}
+
+/// A preset name, or a custom layout string (`checksum,WxH,X,Y…`).
+fn is_layout(layout: &str) -> bool {
+    if LAYOUTS.contains(&layout) {
+        return true;
+    }
+    let Some((checksum, rest)) = layout.split_once(',') else {
+        return false;
+    };
+    checksum.len() == 4 && checksum.chars().all(|c| c.is_ascii_hexdigit()) && !rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(yaml: &str) -> color_eyre::Result<SessionTemplate> {
+        serde_yaml::from_str::<SessionFile>(yaml)?.into_template()
+    }
+
+    #[test]
+    fn tmuxp_file_becomes_a_template() {
+        let template = parse(
+            "session_name: dev\n\
+             start_directory: ~/src/app\n\
+             shell_command_before: source .env\n\
+             windows:\n\
+             - window_name: editor\n\
+             \x20 layout: main-vertical\n\
+             \x20 panes:\n\
+             \x20 - nvim\n\
+             \x20 - shell_command:\n\
+             \x20   - cd src\n\
+             \x20   - ls\n\
+             \x20 -\n\
+             - window_name: logs\n",
+        )
+        .unwrap();
+        assert_eq!(template.name, "dev");
+        assert_eq!(template.root.as_deref(), Some("~/src/app"));
+        let editor = &template.windows[0];
+        assert_eq!(editor.layout.as_deref(), Some("main-vertical"));
+        assert_eq!(
+            editor.panes,
+            ["source .env\nnvim", "source .env\ncd src\nls", "source .env"]
+        );
+        // No panes listed: the window's shell still runs the `before` commands.
+        assert_eq!(template.windows[1].panes, ["source .env"]);
+    }
+
+    #[test]
+    fn bad_files_are_rejected_clearly() {
+        let err = parse("windows: []\n").unwrap_err();
+        assert!(err.to_string().contains("session name"));
+
+        let err = parse("session_name: x\nwindows:\n- layout: diagonal\n").unwrap_err();
+        assert!(err.to_string().contains("unknown layout 'diagonal'"));
+
+        let custom = "session_name: x\nwindows:\n- layout: 5e3b,204x50,0,0\n";
+        assert!(parse(custom).is_ok());
+    }
+
+    #[test]
+    fn saved_toml_reads_back() {
+        let template = SessionTemplate {
+            name: "dev".to_string(),
+            root: Some("/src/app".to_string()),
+            windows: vec![TemplateWindow {
+                name: Some("editor".to_string()),
+                panes: vec![String::new(), "cd '/tmp'".to_string()],
+                layout: Some("5e3b,204x50,0,0".to_string()),
+            }],
+            ..SessionTemplate::default()
+        };
+        let text = to_toml(&template).unwrap();
+        assert!(!text.contains("description"));
+        let back = check(toml::from_str(&text).unwrap()).unwrap();
+        assert_eq!(back.name, "dev");
+        assert_eq!(back.root.as_deref(), Some("/src/app"));
+        assert_eq!(back.windows[0].panes, template.windows[0].panes);
+        assert_eq!(back.windows[0].layout, template.windows[0].layout);
+    }
+}
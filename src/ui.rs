@@ -5,10 +5,12 @@ use ratatui::{
 
 use crate::agents::{self, AgentSession, AgentState};
 use crate::app::{
-    ClaudeState, Focus, InputMode, PopupMode, SessionRow, TmuxPane, TmuxWindow, UIState,
-    UNGROUPED_LABEL, ViewMode,
+    ClaudeState, Focus, InactiveSessions, InputMode, KILL_LIST_MAX_ROWS, PopupMode, SessionRow,
+    TYPED_CONFIRMATION, TmuxPane, TmuxSession, TmuxWindow, UIState, UNGROUPED_LABEL, ViewMode,
+    display_target,
 };
-use crate::config::{Action, MarkerSet, Theme};
+use crate::config::{Action, MarkerSet, MonitorPosition, Theme};
+use crate::preview;
 
 /// Braille "dots" spinner frames (cli-spinners `dots`). Rendered for a marker
 /// configured as `"spinner"` (the default `Working` Claude state) so it
@@ -16,6 +18,11 @@ use crate::config::{Action, MarkerSet, Theme};
 const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 /// Milliseconds each spinner frame is shown.
 const SPINNER_FRAME_MS: u128 = 80;
+/// Shown after the name of a session with a client attached, so being
+/// attached stays visible whatever colour the selection gives the row/border.
+const ATTACHED_GLYPH: &str = "◉";
+/// Height of a collapsed TreeView list: its borders around the selected row.
+const COLLAPSED_LIST_HEIGHT: u16 = 3;
 
 fn now_millis() -> u128 {
     std::time::SystemTime::now()
@@ -73,10 +80,12 @@ fn claude_border_color(
 // =============================================================================
 
 pub fn render_ui(frame: &mut Frame, state: &mut UIState) {
+    let area = render_monitor_strip(frame, state);
     match state.view_mode {
-        ViewMode::TreeView => render_tree_view(frame, state),
-        ViewMode::MultiPreview => render_multi_preview(frame, state),
-        ViewMode::Dashboard => render_dashboard(frame, state),
+        ViewMode::TreeView => render_tree_view(frame, state, area),
+        ViewMode::MultiPreview => render_multi_preview(frame, state, area),
+        ViewMode::Dashboard => render_dashboard(frame, state, area),
+        ViewMode::AllPanes => render_all_panes(frame, state, area),
     }
 
     // Render input popup if in input mode
@@ -93,41 +102,175 @@ pub fn render_ui(frame: &mut Frame, state: &mut UIState) {
             PopupMode::NewGroup => {
                 render_session_name_popup(frame, state, "New Group", "New group name:")
             }
-            PopupMode::ConfirmKill => render_confirm_kill_popup(frame, state),
+            PopupMode::ConfirmKill
+            | PopupMode::ConfirmKillWindow
+            | PopupMode::ConfirmKillPane
+            | PopupMode::ConfirmKillOthers => render_confirm_kill_popup(frame, state),
+            PopupMode::NewWindow => {
+                let title = state
+                    .sessions
+                    .get(state.selected_session)
+                    .map(|s| format!("New Window in {}", s.name))
+                    .unwrap_or_else(|| "New Window".to_string());
+                let label = "Window name (blank: tmux names it):";
+                render_session_name_popup(frame, state, &title, label)
+            }
+            PopupMode::RenameWindow => {
+                render_session_name_popup(frame, state, "Rename Window", "Enter new name:")
+            }
+            PopupMode::TemplatePicker => render_template_select_popup(frame, state),
+            PopupMode::DeadPanes => render_dead_panes_popup(frame, state),
+            PopupMode::ServerPicker => render_server_select_popup(frame, state),
+            PopupMode::MoveWindow => render_move_window_popup(frame, state),
+            PopupMode::CommandPalette => render_command_palette_popup(frame, state),
+            PopupMode::WorkspacePicker => render_workspace_select_popup(frame, state),
+            PopupMode::NewWorkspace => {
+                render_session_name_popup(frame, state, "Save Workspace", "Workspace name:")
+            }
+            PopupMode::GotoPane => {
+                render_session_name_popup(frame, state, "Go to Pane", "Pane id, e.g. %12:")
+            }
+            PopupMode::CommandOutput => render_command_output_popup(frame, state),
+            // Typed into the TreeView's status bar, so the rows it dims stay
+            // in sight.
+            PopupMode::Search => {}
+            PopupMode::TypedConfirm => {
+                let title = if state.typed_confirm_origin == Some(PopupMode::DeadPanes) {
+                    format!("Kill {} Dead Pane(s)", state.dead_panes().len())
+                } else {
+                    format!("Kill {} Other Session(s)", state.other_session_names().len())
+                };
+                let label = format!("Type '{TYPED_CONFIRMATION}' to kill them all:");
+                render_session_name_popup(frame, state, &title, &label)
+            }
+            PopupMode::CaptureRange => render_session_name_popup(
+                frame,
+                state,
+                "Capture Range",
+                "Lines, e.g. -500 or -1000..-500 (blank: screen):",
+            ),
+            PopupMode::TemplateSessionName => {
+                let title = state
+                    .templates
+                    .get(state.template_choice_index)
+                    .map(|t| format!("New Session: {}", t.name))
+                    .unwrap_or_else(|| "New Session".to_string());
+                render_session_name_popup(frame, state, &title, "Enter session name:")
+            }
         }
     }
 }
 
 // =============================================================================
-// TreeView Rendering
+// Monitor Strip
 // =============================================================================
 
-fn render_tree_view(frame: &mut Frame, state: &mut UIState) {
+/// Draw the strip of monitored panes on its configured edge and return the
+/// area left for the view. With no pane monitored it takes no room.
+fn render_monitor_strip(frame: &mut Frame, state: &UIState) -> Rect {
     let area = frame.area();
+    let height = state.layout.monitor_height.min(area.height / 2);
+    if state.monitored.is_empty() || height == 0 {
+        return area;
+    }
+    let (strip, rest) = match state.layout.monitor_position {
+        MonitorPosition::Top => {
+            let chunks =
+                Layout::vertical([Constraint::Length(height), Constraint::Min(1)]).split(area);
+            (chunks[0], chunks[1])
+        }
+        MonitorPosition::Bottom => {
+            let chunks =
+                Layout::vertical([Constraint::Min(1), Constraint::Length(height)]).split(area);
+            (chunks[1], chunks[0])
+        }
+    };
 
-    // Main layout: left panel (lists) | right panel (preview)
-    let left_width = state.layout.session_panel_width.min(100);
-    let main_chunks = Layout::horizontal([
-        Constraint::Percentage(left_width),
-        Constraint::Percentage(100 - left_width),
-    ])
-    .split(area);
+    let count = state.monitored.len() as u32;
+    let cells = Layout::horizontal(state.monitored.iter().map(|_| Constraint::Ratio(1, count)))
+        .split(strip);
+    for (id, cell) in state.monitored.iter().zip(cells.iter()) {
+        let title = match state.find_pane(id) {
+            Some((session, window, pane)) => format!(
+                " {}:{}.{} [{}] ",
+                session.name, window.index, pane.index, pane.current_command
+            ),
+            None => format!(" {id} "),
+        };
+        let block = preview_block(
+            state.preview.borders,
+            Style::default().fg(state.theme.unfocus_border),
+            Line::from(title),
+        );
+        let inner = block.inner(*cell);
+        frame.render_widget(block, *cell);
+        if let Some(text) = state.monitor_content.get(id) {
+            let text = preview::tail(text, inner.height as usize, 0, inner.width as usize);
+            frame.render_widget(Paragraph::new(text), inner);
+        }
+    }
+    rest
+}
+
+// =============================================================================
+// TreeView Rendering
+// =============================================================================
+
+fn render_tree_view(frame: &mut Frame, state: &mut UIState, area: Rect) {
+    state.sync_preview_scroll();
+
+    // Main layout: left panel (lists) | right panel (preview). On a narrow
+    // terminal the lists go on top and the preview below, and the lists sit
+    // side by side instead of stacked.
+    let stacked = state.layout.tree_stacked(area.width);
+    let left_share = state.layout.session_panel_width.min(100);
+    let main_split = [
+        Constraint::Percentage(left_share),
+        Constraint::Percentage(100 - left_share),
+    ];
+    let main_chunks = if stacked {
+        Layout::vertical(main_split).split(area)
+    } else {
+        Layout::horizontal(main_split).split(area)
+    };
 
     let left_panel = main_chunks[0];
     let right_panel = main_chunks[1];
+    let lists = |constraints: &[Constraint]| {
+        if stacked {
+            Layout::horizontal(constraints.to_vec()).split(left_panel)
+        } else {
+            Layout::vertical(constraints.to_vec()).split(left_panel)
+        }
+    };
 
-    // Left panel: Sessions | Windows | Panes (vertical stack)
+    // Left panel: Sessions | Windows | Panes. Scoped to one session, the
+    // sessions list is dropped and the other two share its space. A collapsed
+    // list keeps one row (its selection) and the others share what it gave
+    // up; side by side, every list already has the full height.
     let [s, w, p] = state.layout.tree_split;
-    let left_chunks = Layout::vertical([
-        Constraint::Percentage(s),
-        Constraint::Percentage(w),
-        Constraint::Percentage(p),
-    ])
-    .split(left_panel);
-
-    render_sessions_list(frame, state, left_chunks[0]);
-    render_windows_list(frame, state, left_chunks[1]);
-    render_panes_list(frame, state, left_chunks[2]);
+    let share = |list: Focus, share: u16| {
+        if !stacked && state.collapsed_lists.contains(&list) {
+            Constraint::Length(COLLAPSED_LIST_HEIGHT)
+        } else {
+            Constraint::Fill(share.max(1))
+        }
+    };
+    if state.scoped_session.is_some() {
+        let left_chunks = lists(&[share(Focus::Windows, w), share(Focus::Panes, p)]);
+        render_windows_list(frame, state, left_chunks[0]);
+        render_panes_list(frame, state, left_chunks[1]);
+    } else {
+        let left_chunks = lists(&[
+            share(Focus::Sessions, s),
+            share(Focus::Windows, w),
+            share(Focus::Panes, p),
+        ]);
+
+        render_sessions_list(frame, state, left_chunks[0]);
+        render_windows_list(frame, state, left_chunks[1]);
+        render_panes_list(frame, state, left_chunks[2]);
+    }
 
     // Right panel: Preview with status bar
     let right_chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(right_panel);
@@ -135,6 +278,19 @@ fn render_tree_view(frame: &mut Frame, state: &mut UIState) {
     render_tree_status_bar(frame, state, right_chunks[1]);
 }
 
+/// Style of a list's selected row: a selection bar in the focused list,
+/// underlined coloured text in the others, so the bar always marks where keys
+/// go.
+fn selected_row_style(theme: &Theme, focused: bool) -> Style {
+    if focused {
+        Style::default().bg(theme.selection_bg).fg(theme.selection_fg)
+    } else {
+        Style::default()
+            .fg(theme.inactive_selection_fg)
+            .add_modifier(Modifier::UNDERLINED)
+    }
+}
+
 fn render_sessions_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
     let theme = state.theme;
     let is_focused = state.focus == Focus::Sessions;
@@ -181,7 +337,7 @@ fn render_sessions_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
                     .fg(theme.accent)
                     .add_modifier(Modifier::BOLD);
                 if is_selected {
-                    style = style.bg(theme.selection_bg).fg(theme.selection_fg);
+                    style = selected_row_style(&theme, is_focused).add_modifier(Modifier::BOLD);
                 }
                 items.push(ListItem::new(Line::from(vec![Span::styled(
                     format!("{} {} ({})", arrow, label, count),
@@ -194,7 +350,9 @@ fn render_sessions_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
                     selected_row = Some(row_idx);
                 }
                 let style = if *index == state.selected_session {
-                    Style::default().bg(theme.selection_bg).fg(theme.selection_fg)
+                    selected_row_style(&theme, is_focused)
+                } else if !state.search_matches_session(session) {
+                    Style::default().fg(theme.unfocus_border)
                 } else {
                     Style::default()
                 };
@@ -204,6 +362,12 @@ fn render_sessions_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
                 } else {
                     session.name.clone()
                 })];
+                if session.attached {
+                    spans.push(Span::styled(
+                        format!(" {ATTACHED_GLYPH}"),
+                        Style::default().fg(theme.success),
+                    ));
+                }
                 if let Some((sym, color)) =
                     claude_marker(&state.hooks.claude, session.claude_state, session.has_claude)
                 {
@@ -212,6 +376,20 @@ fn render_sessions_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
                         Style::default().fg(color),
                     ));
                 }
+                if let Some(change) = state.session_changes.get(&session.name) {
+                    spans.push(Span::styled(
+                        format!(" {}", change.badge()),
+                        Style::default().fg(theme.highlight),
+                    ));
+                }
+                if state.layout.show_client_size
+                    && let Some(sizes) = client_size_label(&session.client_sizes)
+                {
+                    spans.push(Span::styled(
+                        format!(" {}", sizes),
+                        Style::default().fg(theme.unfocus_border),
+                    ));
+                }
                 items.push(ListItem::new(Line::from(spans)).style(style));
             }
         }
@@ -238,6 +416,31 @@ fn render_sessions_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
     frame.render_stateful_widget(list, area, &mut state.session_list_state);
 }
 
+/// Attached clients' terminal sizes, e.g. `120x40` or `120x40,80x24`. `None`
+/// when no client is attached.
+fn client_size_label(sizes: &[(u32, u32)]) -> Option<String> {
+    if sizes.is_empty() {
+        return None;
+    }
+    let labels: Vec<String> = sizes.iter().map(|(w, h)| format!("{w}x{h}")).collect();
+    Some(labels.join(","))
+}
+
+/// A pane's resource use, e.g. `12% 340M`: CPU once two samples exist, and
+/// resident memory. `None` when there is no data (not sampled, or no /proc).
+fn resource_label(cpu_percent: Option<f32>, rss_kb: Option<u64>) -> Option<String> {
+    let mem = rss_kb.map(|kb| match kb {
+        kb if kb < 1024 => format!("{kb}K"),
+        kb if kb < 1024 * 1024 => format!("{}M", kb / 1024),
+        kb => format!("{:.1}G", kb as f64 / (1024.0 * 1024.0)),
+    });
+    let cpu = cpu_percent.map(|c| format!("{c:.0}%"));
+    match (cpu, mem) {
+        (Some(cpu), Some(mem)) => Some(format!("{cpu} {mem}")),
+        (cpu, mem) => cpu.or(mem),
+    }
+}
+
 fn render_windows_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
     let theme = state.theme;
     let is_focused = state.focus == Focus::Windows;
@@ -259,11 +462,22 @@ fn render_windows_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
         .enumerate()
         .map(|(i, window)| {
             let style = if i == state.selected_window {
-                Style::default().bg(theme.selection_bg).fg(theme.selection_fg)
+                selected_row_style(&theme, is_focused)
+            } else if !state.search_matches_window(window) {
+                Style::default().fg(theme.unfocus_border)
             } else {
                 Style::default()
             };
             let mut spans = vec![Span::raw(format!("{}:{}", window.index, window.name))];
+            if window.synchronized {
+                spans.push(Span::styled(" ⇄", Style::default().fg(theme.highlight)));
+            }
+            if !window.alerts.is_empty() {
+                spans.push(Span::styled(
+                    format!(" {}", window.alerts),
+                    Style::default().fg(theme.error).add_modifier(Modifier::BOLD),
+                ));
+            }
             if let Some((sym, color)) =
                 claude_marker(&state.hooks.claude, window.claude_state, window.has_claude)
             {
@@ -279,7 +493,10 @@ fn render_windows_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
     let title = state
         .sessions
         .get(state.selected_session)
-        .map(|s| format!(" Windows [{}] ({}) ", s.name, windows.len()))
+        .map(|s| {
+            let sort = state.window_sort.label();
+            format!(" Windows [{}] ({}) [{sort}] ", s.name, windows.len())
+        })
         .unwrap_or_else(|| " Windows ".to_string());
 
     let list = List::new(items)
@@ -317,14 +534,31 @@ fn render_panes_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
         .enumerate()
         .map(|(i, pane)| {
             let style = if i == state.selected_pane {
-                Style::default().bg(theme.selection_bg).fg(theme.selection_fg)
+                selected_row_style(&theme, is_focused)
+            } else if !state.search_matches_pane(pane) {
+                Style::default().fg(theme.unfocus_border)
             } else {
                 Style::default()
             };
-            let mut spans = vec![Span::raw(format!(
-                "{}:{} [{}]",
-                pane.index, pane.id, pane.current_command
-            ))];
+            // The command takes its `[theme.commands]` colour, except on
+            // rows whose style already says something (selected, dimmed).
+            let command_style = match state.command_colors.get(&pane.current_command) {
+                Some(&color) if style == Style::default() => Style::default().fg(color),
+                _ => Style::default(),
+            };
+            let mut spans = vec![
+                Span::raw(format!("{}:{} [", pane.index, pane.id)),
+                Span::styled(pane.current_command.clone(), command_style),
+                Span::raw("]"),
+            ];
+            if state.layout.show_resources
+                && let Some(usage) = resource_label(pane.cpu_percent, pane.rss_kb)
+            {
+                spans.push(Span::styled(
+                    format!(" {}", usage),
+                    Style::default().fg(theme.unfocus_border),
+                ));
+            }
             if let Some((sym, color)) =
                 claude_marker(&state.hooks.claude, pane.claude_state, pane.has_claude)
             {
@@ -357,47 +591,93 @@ fn render_panes_list(frame: &mut Frame, state: &mut UIState, area: Rect) {
     frame.render_stateful_widget(list, area, &mut state.pane_list_state);
 }
 
-fn render_pane_preview_tree(frame: &mut Frame, state: &UIState, area: Rect) {
-    let title = state
-        .get_selected_pane_target()
-        .map(|t| format!(" Preview: {} ", t))
-        .unwrap_or_else(|| " Preview ".to_string());
+fn render_pane_preview_tree(frame: &mut Frame, state: &mut UIState, area: Rect) {
+    let mut range = state
+        .capture_range
+        .map(|r| format!(" [{}]", r.label()))
+        .unwrap_or_default();
+    if state.preview_vscroll > 0 {
+        range.push_str(&format!(" ↑{}", state.preview_vscroll));
+    }
+    if state.preview_hscroll > 0 {
+        range.push_str(&format!(" →{}", state.preview_hscroll));
+    }
+    let title = match state.get_preview_pane_target() {
+        Some(t) if state.preview_follows_active_pane() => {
+            format!(" Preview: {}{} (active pane) ", display_target(&t), range)
+        }
+        Some(t) => format!(" Preview: {}{} ", display_target(&t), range),
+        None => " Preview ".to_string(),
+    };
 
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(Style::default().fg(state.theme.accent))
-        .title(title);
+    let block = preview_block(
+        state.preview.borders,
+        Style::default().fg(state.theme.accent),
+        Line::from(title),
+    );
 
     let inner = block.inner(area);
     let max_lines = inner.height as usize;
-
-    // Use cached parsed Text (rebuilt only when pane_content changes).
-    let text = if let Some(parsed) = state.pane_content_parsed.as_ref() {
-        if parsed.lines.len() > max_lines {
-            let start = parsed.lines.len().saturating_sub(max_lines);
-            Text::from(parsed.lines[start..].to_vec())
+    state.preview_rows = max_lines;
+    state.preview_cols = inner.width as usize;
+    let (skip, width) = (state.preview_hscroll, inner.width as usize);
+    let wrap = state.preview.wrap;
+    // A scroll past the capture (its history is still being fetched) shows
+    // the capture's top until the lines arrive.
+    let show = |text: &Text<'static>| {
+        if wrap {
+            // Counting the rows wraps every line; only a scroll needs it.
+            let up = match state.preview_vscroll {
+                0 => 0,
+                up => up.min(preview::wrapped_height(text, width).saturating_sub(max_lines)),
+            };
+            preview::tail_above_wrapped(text, max_lines, up, width)
         } else {
-            parsed.clone()
-        }
-    } else {
-        let mut raw: Vec<&str> = state.pane_content.lines().collect();
-        if raw.len() > max_lines {
-            raw = raw[raw.len().saturating_sub(max_lines)..].to_vec();
+            let up = state.preview_vscroll.min(text.lines.len().saturating_sub(max_lines));
+            preview::tail_above(text, max_lines, up, skip, width)
         }
-        Text::raw(raw.join("\n"))
     };
 
+    // Use cached parsed Text (rebuilt only when pane_content changes). It is
+    // only missing before the first capture, when pane_content is empty too.
+    let mut text = match state.pane_content_parsed.as_ref() {
+        Some(parsed) => show(parsed),
+        None => show(&Text::from(preview::to_plain_text(&state.pane_content))),
+    };
+    preview::align_underfilled(&mut text, state.preview.align, max_lines);
+
     let paragraph = Paragraph::new(text).block(block);
     frame.render_widget(paragraph, area);
 }
 
 fn render_tree_status_bar(frame: &mut Frame, state: &UIState, area: Rect) {
     let theme = state.theme;
-    let status_text = if let Some(ref err) = state.last_error {
+    let status_text = if state.popup_mode == Some(PopupMode::Search) {
+        let before: String = state.input_buffer.chars().take(state.input_cursor).collect();
+        let at = state.input_buffer.chars().nth(state.input_cursor).unwrap_or(' ');
+        let after: String = state.input_buffer.chars().skip(state.input_cursor + 1).collect();
+        let hints: Vec<String> =
+            popup_hints(state).iter().map(|(k, a)| format!("{k}:{a}")).collect();
+        Line::from(vec![
+            Span::styled(" /", Style::default().fg(theme.focus_border)),
+            Span::raw(before),
+            Span::styled(at.to_string(), Style::default().bg(Color::White).fg(Color::Black)),
+            Span::raw(after),
+            Span::styled(
+                format!("  {} match(es)  {} ", state.search_match_count(), hints.join(" ")),
+                Style::default().fg(theme.unfocus_border),
+            ),
+        ])
+    } else if let Some(ref err) = state.last_error {
         Line::from(vec![Span::styled(
             format!(" Error: {} ", err),
             Style::default().fg(theme.error),
         )])
+    } else if let Some(ref note) = state.status_message {
+        Line::from(vec![Span::styled(
+            format!(" Note: {} (any key to dismiss) ", note),
+            Style::default().fg(theme.accent),
+        )])
     } else {
         let kb = &state.keybindings;
         // `j/k`, `Tab`, `za` and `Space×2` are fixed (not remappable); the rest
@@ -407,10 +687,16 @@ fn render_tree_status_bar(frame: &mut Frame, state: &UIState, area: Rect) {
             Span::raw(":move "),
             Span::styled("Tab", Style::default().fg(theme.focus_border)),
             Span::raw(":focus "),
+            Span::styled(kb.label(Action::Refresh), Style::default().fg(theme.focus_border)),
+            Span::raw(":refresh "),
+            Span::styled(kb.label(Action::Recapture), Style::default().fg(theme.focus_border)),
+            Span::raw(":recapture "),
             Span::styled(kb.label(Action::Sort), Style::default().fg(theme.focus_border)),
             Span::raw(":sort "),
             Span::styled(kb.label(Action::Group), Style::default().fg(theme.focus_border)),
             Span::raw(":group "),
+            Span::styled(kb.label(Action::Search), Style::default().fg(theme.focus_border)),
+            Span::raw(":search "),
             Span::styled("za", Style::default().fg(theme.focus_border)),
             Span::raw(":fold "),
             Span::styled("Space×2", Style::default().fg(theme.highlight)),
@@ -419,6 +705,8 @@ fn render_tree_status_bar(frame: &mut Frame, state: &UIState, area: Rect) {
             Span::raw(":fleet "),
             Span::styled(kb.label(Action::NewSession), Style::default().fg(theme.success)),
             Span::raw(":new "),
+            Span::styled(kb.label(Action::NewFromTemplate), Style::default().fg(theme.success)),
+            Span::raw(":template "),
             Span::styled(kb.label(Action::RenameSession), Style::default().fg(theme.success)),
             Span::raw(":rename "),
             Span::styled(kb.label(Action::KillSession), Style::default().fg(theme.error)),
@@ -428,12 +716,65 @@ fn render_tree_status_bar(frame: &mut Frame, state: &UIState, area: Rect) {
         ])
     };
 
+    let mut status_text = with_status_badges(state, status_text);
+    if state.layout.show_metrics
+        && let Some((elapsed_ms, at)) = state.last_refresh
+    {
+        status_text.spans.push(Span::styled(
+            format!(
+                "  refresh {elapsed_ms}ms, {} ago",
+                format_elapsed(at.elapsed().as_secs() as i64)
+            ),
+            Style::default().fg(theme.unfocus_border),
+        ));
+    }
     frame.render_widget(
         Paragraph::new(status_text).style(Style::default().bg(theme.status_bar_bg)),
         area,
     );
 }
 
+/// Prefix a status bar line with a "READ-ONLY" badge in `--read-only` mode.
+fn with_status_badges<'a>(state: &UIState, mut line: Line<'a>) -> Line<'a> {
+    if state.alerts_only {
+        line.spans.insert(
+            0,
+            Span::styled(
+                " ALERTS ",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(state.theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        );
+    }
+    if state.follow_active {
+        line.spans.insert(
+            0,
+            Span::styled(
+                " FOLLOW ",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(state.theme.success)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        );
+    }
+    if state.read_only {
+        line.spans.insert(
+            0,
+            Span::styled(
+                " READ-ONLY ",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(state.theme.error)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        );
+    }
+    line
+}
+
 // =============================================================================
 // Fleet Dashboard Rendering
 // =============================================================================
@@ -464,8 +805,7 @@ fn agent_marker(markers: &MarkerSet, state: AgentState, theme: &Theme) -> (Strin
 
 /// Render the agent view: Claude Code background sessions grouped by working
 /// directory, like `claude agents`. `Enter` attaches to the selected session.
-fn render_dashboard(frame: &mut Frame, state: &UIState) {
-    let area = frame.area();
+fn render_dashboard(frame: &mut Frame, state: &UIState, area: Rect) {
     let theme = state.theme;
 
     let chunks = Layout::vertical([
@@ -717,7 +1057,8 @@ fn render_dashboard_status_bar(frame: &mut Frame, state: &UIState, area: Rect) {
         Span::raw(":quit"),
     ]);
     frame.render_widget(
-        Paragraph::new(status_text).style(Style::default().bg(theme.status_bar_bg)),
+        Paragraph::new(with_status_badges(state, status_text))
+            .style(Style::default().bg(theme.status_bar_bg)),
         area,
     );
 }
@@ -726,8 +1067,7 @@ fn render_dashboard_status_bar(frame: &mut Frame, state: &UIState, area: Rect) {
 // MultiPreview Rendering
 // =============================================================================
 
-fn render_multi_preview(frame: &mut Frame, state: &UIState) {
-    let area = frame.area();
+fn render_multi_preview(frame: &mut Frame, state: &mut UIState, area: Rect) {
     let theme = state.theme;
 
     let main_chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(area);
@@ -735,24 +1075,36 @@ fn render_multi_preview(frame: &mut Frame, state: &UIState) {
     let preview_area = main_chunks[0];
     let status_area = main_chunks[1];
 
-    if state.sessions.is_empty() {
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .title(" No sessions found ");
+    // Columns on screen: every session, or only attached ones when inactive
+    // sessions are hidden.
+    let visible = state.multi_visible_sessions();
+
+    if state.zoomed {
+        // The selected window alone, at full size: the same preview the
+        // TreeView draws, of the window's active pane.
+        render_pane_preview_tree(frame, state, preview_area);
+    } else if visible.is_empty() {
+        let title = if state.sessions.is_empty() {
+            " No sessions found "
+        } else {
+            " No attached sessions (inactive sessions hidden) "
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
         frame.render_widget(block, preview_area);
+    } else if state.layout.multi_columns > 0 {
+        render_multi_grid(frame, state, preview_area);
     } else {
         // Create horizontal layout for sessions: the selected session gets
         // `multi_selected_ratio`%, the rest share what remains.
         let selected_ratio = state.layout.multi_selected_ratio.min(100);
-        let session_constraints: Vec<Constraint> = if state.sessions.len() == 1 {
+        let session_constraints: Vec<Constraint> = if visible.len() == 1 {
             vec![Constraint::Percentage(100)]
         } else {
-            let other_count = state.sessions.len() - 1;
+            let other_count = visible.len() - 1;
             let other_percentage = (100 - selected_ratio) / other_count as u16;
-            state.sessions
+            visible
                 .iter()
-                .enumerate()
-                .map(|(idx, _)| {
+                .map(|&idx| {
                     if idx == state.multi_session {
                         Constraint::Percentage(selected_ratio)
                     } else {
@@ -764,16 +1116,19 @@ fn render_multi_preview(frame: &mut Frame, state: &UIState) {
 
         let session_chunks = Layout::horizontal(session_constraints).split(preview_area);
 
-        for (session_idx, (session, session_area)) in
-            state.sessions.iter().zip(session_chunks.iter()).enumerate()
-        {
+        for (&session_idx, session_area) in visible.iter().zip(session_chunks.iter()) {
+            let session = &state.sessions[session_idx];
             let is_selected_session = session_idx == state.multi_session;
+            let dimmed = !session.attached && state.inactive_sessions == InactiveSessions::Dim;
 
             // Session block style. Sessions running Claude are accented with
             // their Claude state colour unless they are the currently selected
-            // session (selection colour wins so focus is never lost).
+            // session (selection colour wins so focus is never lost); inactive
+            // sessions are dimmed below both.
             let session_border_style = if is_selected_session {
                 Style::default().fg(theme.focus_border).add_modifier(Modifier::BOLD)
+            } else if dimmed {
+                Style::default().fg(theme.unfocus_border)
             } else if let Some(color) =
                 claude_border_color(&state.hooks.claude, session.claude_state, session.has_claude)
             {
@@ -782,7 +1137,20 @@ fn render_multi_preview(frame: &mut Frame, state: &UIState) {
                 Style::default().fg(theme.unfocus_border)
             };
 
-            let mut title_spans = vec![Span::raw(format!(" {} ", session.name))];
+            let title_style = if dimmed && !is_selected_session {
+                Style::default().fg(theme.unfocus_border)
+            } else {
+                Style::default()
+            };
+            let mut title_spans = vec![Span::styled(format!(" {} ", session.name), title_style)];
+            // The border colour shows selection; attachment gets its own glyph
+            // so a selected session does not hide whether it is attached.
+            if session.attached {
+                title_spans.push(Span::styled(
+                    format!("{ATTACHED_GLYPH} "),
+                    Style::default().fg(theme.success),
+                ));
+            }
             if let Some((sym, color)) =
                 claude_marker(&state.hooks.claude, session.claude_state, session.has_claude)
             {
@@ -792,10 +1160,11 @@ fn render_multi_preview(frame: &mut Frame, state: &UIState) {
                 ));
             }
 
-            let session_block = Block::default()
-                .borders(Borders::ALL)
-                .border_style(session_border_style)
-                .title(Line::from(title_spans));
+            let session_block = preview_block(
+                state.preview.borders,
+                session_border_style,
+                Line::from(title_spans),
+            );
 
             let inner_area = session_block.inner(*session_area);
             frame.render_widget(session_block, *session_area);
@@ -816,19 +1185,21 @@ fn render_multi_preview(frame: &mut Frame, state: &UIState) {
 
             let window_chunks = Layout::vertical(window_constraints).split(inner_area);
 
-            for (window_idx, (window, window_area)) in
-                session.windows.iter().zip(window_chunks.iter()).enumerate()
+            for (window_idx, window_area) in
+                state.multi_window_order(session).into_iter().zip(window_chunks.iter())
             {
+                let window = &session.windows[window_idx];
                 let is_selected_window =
                     is_selected_session && window_idx == state.multi_window;
 
                 render_window_preview(
                     frame,
-                    &state.theme,
-                    &state.hooks.claude,
+                    state,
                     window,
+                    None,
                     *window_area,
                     is_selected_window,
+                    dimmed,
                 );
             }
         }
@@ -840,19 +1211,33 @@ fn render_multi_preview(frame: &mut Frame, state: &UIState) {
             format!(" Error: {} ", err),
             Style::default().fg(theme.error),
         )])
+    } else if let Some(ref note) = state.status_message {
+        Line::from(vec![Span::styled(
+            format!(" Note: {} (any key to dismiss) ", note),
+            Style::default().fg(theme.accent),
+        )])
     } else {
         let selected_info = state
             .get_multi_selected_target()
-            .unwrap_or_else(|| "None".to_string());
+            .map_or_else(|| "None".to_string(), |t| display_target(&t).to_string());
 
         let kb = &state.keybindings;
         Line::from(vec![
             Span::styled("h/l", Style::default().fg(theme.focus_border)),
             Span::raw(":session "),
             Span::styled("j/k", Style::default().fg(theme.focus_border)),
-            Span::raw(":window "),
+            Span::raw(if state.zoomed { ":scroll " } else { ":window " }),
+            Span::styled("z", Style::default().fg(theme.focus_border)),
+            Span::raw(if state.zoomed { ":grid " } else { ":zoom " }),
+            Span::styled("+/-", Style::default().fg(theme.focus_border)),
+            Span::raw(":columns "),
             Span::styled("Space×2", Style::default().fg(theme.highlight)),
-            Span::raw(":tree "),
+            Span::raw(":all panes "),
+            Span::styled(
+                kb.label(Action::ToggleInactive),
+                Style::default().fg(theme.focus_border),
+            ),
+            Span::raw(":idle "),
             Span::styled(kb.label(Action::NewSession), Style::default().fg(theme.success)),
             Span::raw(":new "),
             Span::styled(kb.label(Action::RenameSession), Style::default().fg(theme.success)),
@@ -870,38 +1255,46 @@ fn render_multi_preview(frame: &mut Frame, state: &UIState) {
     };
 
     frame.render_widget(
-        Paragraph::new(status_text).style(Style::default().bg(theme.status_bar_bg)),
+        Paragraph::new(with_status_badges(state, status_text))
+            .style(Style::default().bg(theme.status_bar_bg)),
         status_area,
     );
 }
 
+/// A window's thumbnail; `session`, when given, leads the title.
 fn render_window_preview(
     frame: &mut Frame,
-    theme: &Theme,
-    markers: &MarkerSet,
+    state: &UIState,
     window: &TmuxWindow,
+    session: Option<&str>,
     area: Rect,
     is_selected: bool,
+    dimmed: bool,
 ) {
+    let theme = &state.theme;
+    let markers = &state.hooks.claude;
     let border_style = if is_selected {
         Style::default()
             .fg(theme.accent)
             .add_modifier(Modifier::BOLD)
+    } else if dimmed {
+        Style::default().fg(theme.unfocus_border)
     } else if let Some(color) = claude_border_color(markers, window.claude_state, window.has_claude) {
         Style::default().fg(color)
     } else {
         Style::default().fg(theme.unfocus_border)
     };
 
-    let cmd = window
-        .get_active_pane()
-        .map(|p| p.current_command.as_str())
-        .unwrap_or("");
-
-    let mut title_spans = vec![Span::raw(format!(
-        " {}:{} [{}] ",
-        window.index, window.name, cmd
-    ))];
+    let mut title_spans = Vec::new();
+    if let Some(session) = session {
+        let style = Style::default().fg(theme.highlight);
+        title_spans.push(Span::styled(format!(" {session} ·"), style));
+    }
+    title_spans.push(Span::raw(window_preview_title(window)));
+    if !window.alerts.is_empty() {
+        let style = Style::default().fg(theme.error).add_modifier(Modifier::BOLD);
+        title_spans.push(Span::styled(format!("{} ", window.alerts), style));
+    }
     if let Some((sym, color)) = claude_marker(markers, window.claude_state, window.has_claude) {
         title_spans.push(Span::styled(
             format!("{} ", sym),
@@ -909,18 +1302,274 @@ fn render_window_preview(
         ));
     }
 
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .border_style(border_style)
-        .title(Line::from(title_spans));
+    let block = preview_block(state.preview.borders, border_style, Line::from(title_spans));
 
     frame.render_widget(block, area);
 }
 
+/// Every window of the visible sessions as an equal thumbnail, in a grid of
+/// `layout.multi_columns` columns and as many rows as that takes.
+fn render_multi_grid(frame: &mut Frame, state: &UIState, area: Rect) {
+    let cells = state.multi_grid_cells();
+    if cells.is_empty() {
+        let block = Block::default().borders(Borders::ALL).title(" No windows ");
+        frame.render_widget(block, area);
+        return;
+    }
+    let columns = state.layout.multi_columns.max(1) as usize;
+    let rows = cells.len().div_ceil(columns);
+    let row_areas = Layout::vertical(vec![Constraint::Ratio(1, rows as u32); rows]).split(area);
+    let cell_widths = vec![Constraint::Ratio(1, columns as u32); columns];
+    for (row, row_area) in cells.chunks(columns).zip(row_areas.iter()) {
+        let cell_areas = Layout::horizontal(cell_widths.clone()).split(*row_area);
+        for (&(s, w), cell_area) in row.iter().zip(cell_areas.iter()) {
+            let session = &state.sessions[s];
+            let dimmed = !session.attached && state.inactive_sessions == InactiveSessions::Dim;
+            let selected = s == state.multi_session && w == state.multi_window;
+            render_window_preview(
+                frame,
+                state,
+                &session.windows[w],
+                Some(&session.name),
+                *cell_area,
+                selected,
+                dimmed,
+            );
+        }
+    }
+}
+
+/// Every pane of the visible sessions as its own tile, in a grid of
+/// [`UIState::all_panes_columns`] columns; each tile shows the bottom of its
+/// pane's latest capture.
+fn render_all_panes(frame: &mut Frame, state: &UIState, area: Rect) {
+    let theme = &state.theme;
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(area);
+    let cells = state.all_pane_cells();
+    if cells.is_empty() {
+        let block = Block::default().borders(Borders::ALL).title(" No panes found ");
+        frame.render_widget(block, chunks[0]);
+    } else {
+        let columns = state.all_panes_columns();
+        let rows = cells.len().div_ceil(columns);
+        let row_areas =
+            Layout::vertical(vec![Constraint::Ratio(1, rows as u32); rows]).split(chunks[0]);
+        let cell_widths = vec![Constraint::Ratio(1, columns as u32); columns];
+        let captured = state.all_panes_captured_windows();
+        for (row, row_area) in cells.chunks(columns).zip(row_areas.iter()) {
+            let cell_areas = Layout::horizontal(cell_widths.clone()).split(*row_area);
+            for (&(s, w, p), cell_area) in row.iter().zip(cell_areas.iter()) {
+                let session = &state.sessions[s];
+                let window = &session.windows[w];
+                let pane = &window.panes[p];
+                let dimmed =
+                    !session.attached && state.inactive_sessions == InactiveSessions::Dim;
+                let selected =
+                    (s, w, p) == (state.multi_session, state.multi_window, state.multi_pane);
+                let border_style = if selected {
+                    Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+                } else if dimmed {
+                    Style::default().fg(theme.unfocus_border)
+                } else if let Some(color) =
+                    claude_border_color(&state.hooks.claude, pane.claude_state, pane.has_claude)
+                {
+                    Style::default().fg(color)
+                } else {
+                    Style::default().fg(theme.unfocus_border)
+                };
+                let title = format!(
+                    " {}:{}.{} [{}] ",
+                    session.name, window.index, pane.index, pane.current_command
+                );
+                let block = preview_block(state.preview.borders, border_style, Line::from(title));
+                let inner = block.inner(*cell_area);
+                frame.render_widget(block, *cell_area);
+                if !captured.contains(&(s, w)) {
+                    let placeholder = Span::styled(
+                        "(preview disabled)",
+                        Style::default().fg(theme.unfocus_border),
+                    );
+                    frame.render_widget(Paragraph::new(placeholder), inner);
+                } else if let Some(text) = state.monitor_content.get(&pane.id) {
+                    let text = preview::tail(text, inner.height as usize, 0, inner.width as usize);
+                    frame.render_widget(Paragraph::new(text), inner);
+                }
+            }
+        }
+    }
+
+    let status_text = if let Some(ref err) = state.last_error {
+        Line::from(vec![Span::styled(
+            format!(" Error: {} ", err),
+            Style::default().fg(theme.error),
+        )])
+    } else if let Some(ref note) = state.status_message {
+        Line::from(vec![Span::styled(
+            format!(" Note: {} (any key to dismiss) ", note),
+            Style::default().fg(theme.accent),
+        )])
+    } else {
+        let selected_info = state
+            .get_all_panes_target()
+            .map_or_else(|| "None".to_string(), |t| display_target(&t).to_string());
+        let kb = &state.keybindings;
+        Line::from(vec![
+            Span::styled("h/j/k/l", Style::default().fg(theme.focus_border)),
+            Span::raw(":pane "),
+            Span::styled("g/G", Style::default().fg(theme.focus_border)),
+            Span::raw(":first/last "),
+            Span::styled("+/-", Style::default().fg(theme.focus_border)),
+            Span::raw(":columns "),
+            Span::styled(kb.label(Action::Enter), Style::default().fg(theme.success)),
+            Span::raw(":switch "),
+            Span::styled("Space×2", Style::default().fg(theme.highlight)),
+            Span::raw(":tree "),
+            Span::styled(kb.label(Action::Quit), Style::default().fg(theme.focus_border)),
+            Span::raw(":quit "),
+            Span::raw("| "),
+            Span::styled(format!("Sel:{}", selected_info), Style::default().fg(theme.accent)),
+        ])
+    };
+    frame.render_widget(
+        Paragraph::new(with_status_badges(state, status_text))
+            .style(Style::default().bg(theme.status_bar_bg)),
+        chunks[1],
+    );
+}
+
+/// ` index:name [command] ` for a window thumbnail, where the command is the
+/// active pane's. With several panes, `.N/M` adds the active pane's index and
+/// the pane count.
+fn window_preview_title(window: &TmuxWindow) -> String {
+    let active = window.get_active_pane();
+    let cmd = active.map(|p| p.current_command.as_str()).unwrap_or("");
+    let mut title = format!(" {}:{} [{}] ", window.index, window.name, cmd);
+    if let Some(pane) = active
+        && window.panes.len() > 1
+    {
+        title.push_str(&format!(".{}/{} ", pane.index, window.panes.len()));
+    }
+    title
+}
+
+/// The block around a preview: a border in `style`, or with
+/// `preview.borders` off just the title row, drawn in `style` instead.
+fn preview_block(borders: bool, style: Style, title: Line<'_>) -> Block<'_> {
+    if borders {
+        Block::default().borders(Borders::ALL).border_style(style).title(title)
+    } else {
+        Block::default().title(title).title_style(style)
+    }
+}
+
 // =============================================================================
 // Input Popup
 // =============================================================================
 
+// =============================================================================
+// Popup Footers
+// =============================================================================
+
+/// `(key, action)` hints for the open popup (or the send-keys popup when no
+/// [`PopupMode`] is set), in display order.
+fn popup_hints(state: &UIState) -> Vec<(&'static str, &'static str)> {
+    let confirm = [("Enter", "confirm"), ("Esc", "cancel")];
+    match state.popup_mode {
+        None => vec![
+            ("Enter", "send"),
+            ("Tab", "scope"),
+            ("M-Enter", "send+output"),
+            ("C-v", "special key"),
+            ("Esc", "cancel"),
+        ],
+        Some(
+            PopupMode::GroupSession
+            | PopupMode::TemplatePicker
+            | PopupMode::ServerPicker
+            | PopupMode::MoveWindow,
+        ) => {
+            [("↑↓", "select")].into_iter().chain(confirm).collect()
+        }
+        Some(PopupMode::CaptureRange) => {
+            [("Tab", "preset")].into_iter().chain(confirm).collect()
+        }
+        Some(PopupMode::CommandPalette) => {
+            vec![("↑↓", "select"), ("Enter", "run"), ("Esc", "cancel")]
+        }
+        Some(PopupMode::WorkspacePicker) => vec![
+            ("↑↓", "select"),
+            ("x", "delete"),
+            ("Enter", "confirm"),
+            ("Esc", "cancel"),
+        ],
+        Some(PopupMode::DeadPanes) => vec![
+            ("j/k", "select"),
+            ("Enter", "show"),
+            ("r", "respawn"),
+            ("x", "kill"),
+            ("R/X", "all"),
+            ("Esc", "close"),
+        ],
+        Some(
+            PopupMode::ConfirmKill
+            | PopupMode::ConfirmKillWindow
+            | PopupMode::ConfirmKillPane
+            | PopupMode::ConfirmKillOthers,
+        ) => {
+            let mut hints = vec![("h/l", "toggle"), ("y/n", "set")];
+            if state.kill_contents().len() > KILL_LIST_MAX_ROWS {
+                hints.push(("j/k", "scroll"));
+            }
+            hints.extend(confirm);
+            hints
+        }
+        Some(
+            PopupMode::NewSession
+            | PopupMode::RenameSession
+            | PopupMode::NewWindow
+            | PopupMode::RenameWindow
+            | PopupMode::GotoPane,
+        ) => confirm.to_vec(),
+        // Prompts opened from a picker: Esc returns to it.
+        Some(PopupMode::NewGroup | PopupMode::NewWorkspace | PopupMode::TemplateSessionName) => {
+            vec![("Enter", "confirm"), ("Esc", "back")]
+        }
+        // Enter is only offered once the phrase matches.
+        Some(PopupMode::TypedConfirm) if state.typed_confirmation_matches() => {
+            vec![("Enter", "kill all"), ("Esc", "back")]
+        }
+        Some(PopupMode::TypedConfirm) => vec![("Esc", "back")],
+        Some(PopupMode::CommandOutput) => vec![
+            ("j/k", "scroll"),
+            ("r", "recapture"),
+            ("Esc", "close"),
+        ],
+        Some(PopupMode::Search) => vec![("Enter", "jump"), ("Esc", "cancel")],
+    }
+}
+
+/// The bottom-border hint line of a popup `width` columns wide. Hints that do
+/// not fit are dropped from the end, but Enter / Esc are always kept.
+fn popup_footer(state: &UIState, width: u16) -> Line<'static> {
+    if state.popup_mode.is_none() && state.compose_pending {
+        return Line::from(" Send next key: Esc, C-<k>, M-<k>, F1-F12, arrows, Tab, PgUp/Dn ")
+            .centered();
+    }
+    let render = |hints: &[(&str, &str)]| {
+        let body: Vec<String> = hints.iter().map(|(k, a)| format!("{k}:{a}")).collect();
+        format!(" {} ", body.join(" | "))
+    };
+    let mut hints = popup_hints(state);
+    let room = usize::from(width.saturating_sub(2));
+    while render(&hints).chars().count() > room {
+        let Some(drop) = hints.iter().rposition(|(k, _)| !matches!(*k, "Enter" | "Esc")) else {
+            break;
+        };
+        hints.remove(drop);
+    }
+    Line::from(render(&hints)).centered()
+}
+
 fn render_input_popup(frame: &mut Frame, state: &UIState, area: Rect) {
     let popup_width = (area.width * 70 / 100).clamp(40, 80);
     let popup_height = 7;
@@ -936,7 +1585,7 @@ fn render_input_popup(frame: &mut Frame, state: &UIState, area: Rect) {
     };
 
     let target_info = state
-        .get_current_target()
+        .send_scope_label()
         .unwrap_or_else(|| "None".to_string());
 
     frame.render_widget(Clear, popup_area);
@@ -945,7 +1594,7 @@ fn render_input_popup(frame: &mut Frame, state: &UIState, area: Rect) {
         .borders(Borders::ALL)
         .border_style(Style::default().fg(state.theme.accent))
         .title(format!(" Send to: {} ", target_info))
-        .title_bottom(Line::from(" Enter:send | Esc:cancel ").centered());
+        .title_bottom(popup_footer(state, popup_width));
 
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -957,7 +1606,20 @@ fn render_input_popup(frame: &mut Frame, state: &UIState, area: Rect) {
     ])
     .split(inner);
 
-    let label = Paragraph::new("Enter message:").style(Style::default().fg(Color::White));
+    let label = if state.send_confirm_pending {
+        Paragraph::new(format!(
+            "Send to all {} panes of the session? Enter again to confirm",
+            state.send_targets().len()
+        ))
+        .style(Style::default().fg(state.theme.error))
+    } else {
+        let label = if state.behavior.submit_keys.is_empty() {
+            "Enter message (sent without a submit key):".to_string()
+        } else {
+            format!("Enter message (sent with {}):", state.behavior.submit_keys.join(" "))
+        };
+        Paragraph::new(label).style(Style::default().fg(Color::White))
+    };
     frame.render_widget(label, input_chunks[0]);
 
     let input_area = input_chunks[2];
@@ -986,7 +1648,7 @@ fn render_input_popup(frame: &mut Frame, state: &UIState, area: Rect) {
     ]);
 
     let input_paragraph = Paragraph::new(input_text)
-        .style(Style::default().fg(Color::White).bg(Color::DarkGray));
+        .style(Style::default().fg(Color::White).bg(state.theme.status_bar_bg));
 
     frame.render_widget(input_paragraph, input_area);
 }
@@ -1018,7 +1680,7 @@ fn render_session_name_popup(frame: &mut Frame, state: &UIState, title: &str, la
         .borders(Borders::ALL)
         .border_style(Style::default().fg(state.theme.accent))
         .title(format!(" {} ", title))
-        .title_bottom(Line::from(" Enter:confirm | Esc:cancel ").centered());
+        .title_bottom(popup_footer(state, popup_width));
 
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -1056,7 +1718,7 @@ fn render_session_name_popup(frame: &mut Frame, state: &UIState, title: &str, la
     ]);
 
     let input_paragraph = Paragraph::new(input_text)
-        .style(Style::default().fg(Color::White).bg(Color::DarkGray));
+        .style(Style::default().fg(Color::White).bg(state.theme.status_bar_bg));
 
     frame.render_widget(input_paragraph, input_area);
 }
@@ -1110,7 +1772,7 @@ fn render_group_select_popup(frame: &mut Frame, state: &UIState) {
         .borders(Borders::ALL)
         .border_style(Style::default().fg(state.theme.accent))
         .title(format!(" Group: {} ", session_name))
-        .title_bottom(Line::from(" ↑↓:select | Enter:confirm | Esc:cancel ").centered());
+        .title_bottom(popup_footer(state, popup_width));
 
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
@@ -1128,10 +1790,31 @@ fn render_group_select_popup(frame: &mut Frame, state: &UIState) {
     frame.render_stateful_widget(list, inner, &mut list_state);
 }
 
-fn render_confirm_kill_popup(frame: &mut Frame, state: &UIState) {
+/// Render the template picker: one row per `[[templates]]` entry, its name
+/// followed by the description. The highlighted row tracks
+/// [`UIState::template_choice_index`].
+fn render_template_select_popup(frame: &mut Frame, state: &UIState) {
     let area = frame.area();
-    let popup_width = (area.width * 50 / 100).clamp(40, 60);
-    let popup_height = 7;
+
+    let items: Vec<ListItem> = state
+        .templates
+        .iter()
+        .map(|t| {
+            let mut spans = vec![Span::raw(t.name.clone())];
+            if !t.description.is_empty() {
+                spans.push(Span::styled(
+                    format!("  {}", t.description),
+                    Style::default().fg(state.theme.unfocus_border),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list_len = items.len() as u16;
+    let popup_width = (area.width * 60 / 100).clamp(40, 70);
+    let max_height = area.height.saturating_sub(2).max(5);
+    let popup_height = (list_len + 4).min(max_height);
 
     let popup_x = (area.width.saturating_sub(popup_width)) / 2;
     let popup_y = (area.height.saturating_sub(popup_height)) / 2;
@@ -1145,41 +1828,515 @@ fn render_confirm_kill_popup(frame: &mut Frame, state: &UIState) {
 
     frame.render_widget(Clear, popup_area);
 
-    let session_name = state
-        .sessions
-        .get(state.selected_session)
-        .map(|s| s.name.as_str())
-        .unwrap_or("?");
-
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(state.theme.error))
-        .title(" Kill Session ")
-        .title_bottom(Line::from(" Enter:confirm | Esc:cancel ").centered());
+        .border_style(Style::default().fg(state.theme.accent))
+        .title(" New Session from Template ")
+        .title_bottom(popup_footer(state, popup_width));
 
     let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
-    let content_chunks = Layout::vertical([
-        Constraint::Length(2),
-        Constraint::Length(1),
-        Constraint::Min(1),
-    ])
-    .split(inner);
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.template_choice_index.min(items.len().saturating_sub(1))));
 
-    // Question text
-    let question = Paragraph::new(format!("Kill session '{}'?", session_name))
-        .style(Style::default().fg(Color::White))
-        .alignment(Alignment::Center);
-    frame.render_widget(question, content_chunks[0]);
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(state.theme.accent)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD),
+    );
 
-    // Yes/No buttons
-    let button_area = content_chunks[2];
-    let button_chunks = Layout::horizontal([
-        Constraint::Percentage(50),
-        Constraint::Percentage(50),
-    ])
-    .split(button_area);
+    frame.render_stateful_widget(list, inner, &mut list_state);
+}
+
+fn render_server_select_popup(frame: &mut Frame, state: &UIState) {
+    let area = frame.area();
+
+    let items: Vec<ListItem> = state
+        .server_choices
+        .iter()
+        .map(|s| {
+            let mut spans = vec![Span::raw(crate::server::label(s.as_deref()).to_string())];
+            if *s == state.tmux_server {
+                spans.push(Span::styled(
+                    "  (current)",
+                    Style::default().fg(state.theme.unfocus_border),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list_len = items.len() as u16;
+    let popup_width = (area.width * 60 / 100).clamp(40, 70);
+    let max_height = area.height.saturating_sub(2).max(5);
+    let popup_height = (list_len + 2).min(max_height);
+
+    let popup_x = (area.width.saturating_sub(popup_width)) / 2;
+    let popup_y = (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect {
+        x: popup_x,
+        y: popup_y,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(state.theme.accent))
+        .title(" tmux Server ")
+        .title_bottom(popup_footer(state, popup_width));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.server_choice_index.min(items.len().saturating_sub(1))));
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(state.theme.accent)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    frame.render_stateful_widget(list, inner, &mut list_state);
+}
+
+/// Render the command palette: the typed query, then the actions matching
+/// it with their key bindings, the highlighted one run on Enter.
+fn render_command_palette_popup(frame: &mut Frame, state: &UIState) {
+    let area = frame.area();
+    let matches = state.palette_matches();
+    let key_width = matches.iter().map(|(_, key)| key.chars().count()).max().unwrap_or(0);
+
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|(action, key)| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{key:<key_width$}  "),
+                    Style::default().fg(state.theme.focus_border),
+                ),
+                Span::raw(action.name().replace('_', " ")),
+            ]))
+        })
+        .collect();
+
+    let popup_width = (area.width * 60 / 100).clamp(40, 70);
+    let max_height = area.height.saturating_sub(2).max(6);
+    // border + query + the matches (at least one row) + border
+    let popup_height = (items.len().max(1) as u16 + 3).min(max_height);
+
+    let popup_x = (area.width.saturating_sub(popup_width)) / 2;
+    let popup_y = (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect {
+        x: popup_x,
+        y: popup_y,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(state.theme.accent))
+        .title(" Commands ")
+        .title_bottom(popup_footer(state, popup_width));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).split(inner);
+
+    let before: String = state.input_buffer.chars().take(state.input_cursor).collect();
+    let at = state.input_buffer.chars().nth(state.input_cursor).unwrap_or(' ');
+    let after: String = state.input_buffer.chars().skip(state.input_cursor + 1).collect();
+    let query = Line::from(vec![
+        Span::styled(":", Style::default().fg(state.theme.focus_border)),
+        Span::raw(before),
+        Span::styled(at.to_string(), Style::default().bg(Color::White).fg(Color::Black)),
+        Span::raw(after),
+    ]);
+    frame.render_widget(
+        Paragraph::new(query).style(Style::default().bg(state.theme.status_bar_bg)),
+        chunks[0],
+    );
+
+    if items.is_empty() {
+        frame.render_widget(
+            Paragraph::new(" No matching action")
+                .style(Style::default().fg(state.theme.unfocus_border)),
+            chunks[1],
+        );
+        return;
+    }
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.palette_index.min(items.len() - 1)));
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(state.theme.accent)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    frame.render_stateful_widget(list, chunks[1], &mut list_state);
+}
+
+fn render_move_window_popup(frame: &mut Frame, state: &UIState) {
+    let area = frame.area();
+
+    let items: Vec<ListItem> =
+        state.move_choices.iter().map(|name| ListItem::new(name.as_str())).collect();
+
+    let list_len = items.len() as u16;
+    let popup_width = (area.width * 60 / 100).clamp(40, 70);
+    let max_height = area.height.saturating_sub(2).max(5);
+    let popup_height = (list_len + 2).min(max_height);
+
+    let popup_x = (area.width.saturating_sub(popup_width)) / 2;
+    let popup_y = (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect {
+        x: popup_x,
+        y: popup_y,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let title = state
+        .sessions
+        .get(state.selected_session)
+        .and_then(|s| s.windows.get(state.selected_window))
+        .map(|w| format!(" Move {}:{} to ", w.index, w.name))
+        .unwrap_or_else(|| " Move Window to ".to_string());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(state.theme.accent))
+        .title(title)
+        .title_bottom(popup_footer(state, popup_width));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.move_choice_index.min(items.len().saturating_sub(1))));
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(state.theme.accent)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    frame.render_stateful_widget(list, inner, &mut list_state);
+}
+
+/// Render the workspace picker: each saved workspace with a summary of what
+/// it applies, then the "Save current" entry.
+fn render_workspace_select_popup(frame: &mut Frame, state: &UIState) {
+    let area = frame.area();
+
+    let mut items: Vec<ListItem> = state
+        .workspaces
+        .all()
+        .iter()
+        .map(|w| {
+            let view = match w.view {
+                ViewMode::MultiPreview => "multi",
+                ViewMode::AllPanes => "panes",
+                _ => "tree",
+            };
+            let mut summary = format!("  {view} · {}", w.sort.label());
+            if let Some(session) = &w.session {
+                summary.push_str(&format!(" · {session}"));
+            }
+            if !w.folded.is_empty() {
+                summary.push_str(&format!(" · {} folded", w.folded.len()));
+            }
+            ListItem::new(Line::from(vec![
+                Span::raw(w.name.clone()),
+                Span::styled(summary, Style::default().fg(state.theme.unfocus_border)),
+            ]))
+        })
+        .collect();
+    items.push(ListItem::new(Line::from(Span::styled(
+        "+ Save current…",
+        Style::default().fg(state.theme.success),
+    ))));
+
+    let list_len = items.len() as u16;
+    let popup_width = (area.width * 60 / 100).clamp(40, 70);
+    let max_height = area.height.saturating_sub(2).max(5);
+    let popup_height = (list_len + 2).min(max_height);
+
+    let popup_x = (area.width.saturating_sub(popup_width)) / 2;
+    let popup_y = (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect {
+        x: popup_x,
+        y: popup_y,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(state.theme.accent))
+        .title(" Workspaces ")
+        .title_bottom(popup_footer(state, popup_width));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut list_state = ListState::default();
+    list_state.select(Some(state.workspace_choice_index.min(items.len().saturating_sub(1))));
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(state.theme.accent)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    frame.render_stateful_widget(list, inner, &mut list_state);
+}
+
+fn render_dead_panes_popup(frame: &mut Frame, state: &UIState) {
+    let area = frame.area();
+    let panes = state.dead_panes();
+
+    let items: Vec<ListItem> = if panes.is_empty() {
+        vec![ListItem::new(Span::styled(
+            "No dead panes left",
+            Style::default().fg(state.theme.unfocus_border),
+        ))]
+    } else {
+        panes
+            .iter()
+            .map(|p| {
+                let (status, color) = match p.status {
+                    Some(0) => ("exit 0".to_string(), state.theme.success),
+                    Some(code) => (format!("exit {code}"), state.theme.error),
+                    None => ("signalled".to_string(), state.theme.error),
+                };
+                let location = truncate(&p.location, 20);
+                let command = truncate(&p.command, 14);
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{location:<20} {command:<14} ")),
+                    Span::styled(status, Style::default().fg(color)),
+                ]))
+            })
+            .collect()
+    };
+
+    let list_len = items.len() as u16;
+    let popup_width = (area.width * 60 / 100).clamp(70, 76).min(area.width);
+    let max_height = area.height.saturating_sub(2).max(5);
+    let popup_height = (list_len + 2).min(max_height);
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(popup_width)) / 2,
+        y: (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(state.theme.error))
+        .title(format!(" Dead Panes ({}) ", panes.len()))
+        .title_bottom(popup_footer(state, popup_width));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let mut list_state = ListState::default();
+    if !panes.is_empty() {
+        list_state.select(Some(state.dead_pane_index.min(panes.len() - 1)));
+    }
+
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .bg(state.theme.accent)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD),
+    );
+
+    frame.render_stateful_widget(list, inner, &mut list_state);
+}
+
+fn render_command_output_popup(frame: &mut Frame, state: &UIState) {
+    let area = frame.area();
+    let lines: Vec<Line> = if state.inspect_lines.is_empty() {
+        vec![Line::styled("(no new output)", Style::default().fg(state.theme.unfocus_border))]
+    } else {
+        state.inspect_lines.iter().map(|l| Line::raw(l.as_str())).collect()
+    };
+
+    let popup_width = (area.width * 80 / 100).max(40).min(area.width);
+    let max_height = (area.height * 80 / 100).max(5);
+    let popup_height = (lines.len() as u16 + 2).clamp(5, max_height).min(area.height);
+
+    let popup_area = Rect {
+        x: (area.width.saturating_sub(popup_width)) / 2,
+        y: (area.height.saturating_sub(popup_height)) / 2,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let command = state.inspection.as_ref().map_or("", |i| i.command.as_str());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(state.theme.accent))
+        .title(format!(" Output: {} ", truncate(command, 40)))
+        .title_bottom(popup_footer(state, popup_width));
+
+    let scroll = state.inspect_scroll.min(u16::MAX as usize) as u16;
+    frame.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), popup_area);
+}
+
+/// `Kill session 'dev' (4 windows, 9 panes)?`, so a busy session is not
+/// killed by accident.
+fn kill_session_question(session: &TmuxSession) -> String {
+    let windows = session.windows.len();
+    let panes: usize = session.windows.iter().map(|w| w.panes.len()).sum();
+    let plural = |n: usize, noun: &str| format!("{n} {noun}{}", if n == 1 { "" } else { "s" });
+    format!(
+        "Kill session '{}' ({}, {})?",
+        session.name,
+        plural(windows, "window"),
+        plural(panes, "pane")
+    )
+}
+
+fn render_confirm_kill_popup(frame: &mut Frame, state: &UIState) {
+    let area = frame.area();
+    let contents = state.kill_contents();
+    // Up to KILL_LIST_MAX_ROWS rows of "what dies" (plus a blank spacer row),
+    // shrunk to fit small terminals.
+    let list_rows = contents.len().min(KILL_LIST_MAX_ROWS) as u16;
+    // Wide enough for the footer's toggle hints on an 80-column terminal.
+    let popup_width = (area.width * 60 / 100).clamp(54, 66).min(area.width);
+    let popup_height = (7 + list_rows + u16::from(list_rows > 0)).min(area.height.max(7));
+
+    let popup_x = (area.width.saturating_sub(popup_width)) / 2;
+    let popup_y = (area.height.saturating_sub(popup_height)) / 2;
+
+    let popup_area = Rect {
+        x: popup_x,
+        y: popup_y,
+        width: popup_width,
+        height: popup_height,
+    };
+
+    frame.render_widget(Clear, popup_area);
+
+    let session = state.sessions.get(state.selected_session);
+    let window = session.and_then(|s| s.windows.get(state.selected_window));
+    let (title, question) = match state.popup_mode {
+        Some(PopupMode::ConfirmKillWindow) => {
+            let window = window.map_or("?".to_string(), |w| format!("{}:{}", w.index, w.name));
+            (" Kill Window ", format!("Kill window '{window}'?"))
+        }
+        Some(PopupMode::ConfirmKillPane) => {
+            let pane = window
+                .and_then(|w| w.panes.get(state.selected_pane))
+                .map_or("?".to_string(), |p| format!("{} ({})", p.id, p.current_command));
+            (" Kill Pane ", format!("Kill pane {pane}?"))
+        }
+        Some(PopupMode::ConfirmKillOthers) => {
+            let kept = session.map_or("?", |s| s.name.as_str());
+            let count = state.other_session_names().len();
+            (" Kill Other Sessions ", format!("Kill {count} session(s), keeping '{kept}'?"))
+        }
+        _ => {
+            let question = session.map_or("Kill session '?'?".to_string(), kill_session_question);
+            (" Kill Session ", question)
+        }
+    };
+    let mut question = vec![Line::from(question)];
+    if state.popup_mode == Some(PopupMode::ConfirmKill)
+        && session.is_some_and(|s| s.attached)
+    {
+        question.push(Line::styled(
+            "A client is attached to this session!",
+            Style::default().fg(state.theme.error).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(state.theme.error))
+        .title(title)
+        .title_bottom(popup_footer(state, popup_width));
+
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let list_height = inner.height.saturating_sub(5);
+    let content_chunks = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Length(list_height),
+        Constraint::Length(1),
+        Constraint::Min(1),
+    ])
+    .split(inner);
+
+    // Question text
+    let question = Paragraph::new(question)
+        .style(Style::default().fg(Color::White))
+        .alignment(Alignment::Center);
+    frame.render_widget(question, content_chunks[0]);
+
+    // Windows (and their pane commands) that go down with the session, or
+    // the panes of a window.
+    let visible = usize::from(list_height.saturating_sub(1));
+    if visible > 0 && !contents.is_empty() {
+        let scroll = state
+            .kill_list_scroll
+            .min(contents.len().saturating_sub(visible));
+        let hidden_below = contents.len().saturating_sub(scroll + visible);
+        let mut lines: Vec<Line> = contents
+            .iter()
+            .skip(scroll)
+            .take(visible)
+            .map(|l| Line::from(format!("  {}", l)))
+            .collect();
+        if hidden_below > 0 || scroll > 0 {
+            // The spacer row below the list doubles as the scroll hint.
+            lines.push(
+                Line::from(format!("  ↑↓ {} more", scroll + hidden_below))
+                    .style(Style::default().fg(state.theme.unfocus_border)),
+            );
+        }
+        let list = Paragraph::new(lines).style(Style::default().fg(state.theme.error));
+        frame.render_widget(list, content_chunks[1]);
+    }
+
+    // Yes/No buttons
+    let button_area = content_chunks[3];
+    let button_chunks = Layout::horizontal([
+        Constraint::Percentage(50),
+        Constraint::Percentage(50),
+    ])
+    .split(button_area);
 
     let yes_style = if state.confirm_yes_selected {
         Style::default().fg(Color::Black).bg(state.theme.error).add_modifier(Modifier::BOLD)
@@ -1259,6 +2416,257 @@ mod cursor_alignment_tests {
         assert_eq!(empty.1, jp.1, "cursor row must not shift with multibyte input");
     }
 
+    #[test]
+    fn kill_popup_lists_windows_with_scroll_hint() {
+        let mut state = UIState::new(crate::config::Config::default());
+        let windows = (0..12)
+            .map(|i| TmuxWindow {
+                index: i,
+                name: format!("w{i}"),
+                active: i == 0,
+                synchronized: false,
+                alerts: String::new(),
+                activity: 0,
+                panes: Vec::new(),
+                has_claude: false,
+                claude_state: None,
+            })
+            .collect();
+        state.sessions = vec![crate::app::TmuxSession {
+            name: "doomed".to_string(),
+            windows,
+            has_claude: false,
+            claude_state: None,
+            last_attached: 0,
+            activity: 0,
+            attached: false,
+            client_sizes: Vec::new(),
+            client_activity: 0,
+            group: None,
+        }];
+        state.open_kill_session_popup();
+        let mut term = Terminal::new(TestBackend::new(80, 30)).unwrap();
+        term.draw(|f| render_confirm_kill_popup(f, &state)).unwrap();
+        let buf = term.backend().buffer();
+        let text: String = (0..buf.area.height)
+            .map(|y| {
+                (0..buf.area.width)
+                    .map(|x| buf.cell((x, y)).unwrap().symbol().to_string())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(text.contains("0:w0"));
+        assert!(text.contains("7:w7"));
+        assert!(!text.contains("8:w8"));
+        assert!(text.contains("4 more"));
+        assert!(text.contains("[Y]es"));
+    }
+
+    #[test]
+    fn selected_attached_session_keeps_its_attached_glyph() {
+        let mut state = UIState::new(crate::config::Config::default());
+        let session = |name: &str, attached: bool| crate::app::TmuxSession {
+            name: name.to_string(),
+            windows: Vec::new(),
+            has_claude: false,
+            claude_state: None,
+            last_attached: 0,
+            activity: 0,
+            attached,
+            client_sizes: Vec::new(),
+            client_activity: 0,
+            group: None,
+        };
+        state.sessions = vec![session("on", true), session("off", false)];
+        state.multi_session = 0;
+        let mut term = Terminal::new(TestBackend::new(60, 8)).unwrap();
+        term.draw(|f| render_multi_preview(f, &mut state, f.area())).unwrap();
+        let buf = term.backend().buffer();
+        let top: String = (0..buf.area.width)
+            .map(|x| buf.cell((x, 0)).unwrap().symbol().to_string())
+            .collect();
+        assert!(top.contains(&format!(" on {ATTACHED_GLYPH}")));
+        assert!(!top.contains(&format!(" off {ATTACHED_GLYPH}")));
+        // Selection still shows on the border.
+        assert_eq!(buf.cell((0, 0)).unwrap().fg, state.theme.focus_border);
+    }
+
+    #[test]
+    fn window_thumbnail_title_shows_the_active_pane_and_pane_count() {
+        let pane = |index: u32, active: bool, cmd: &str| crate::app::TmuxPane {
+            id: format!("%{index}"),
+            index,
+            width: 80,
+            height: 24,
+            active,
+            current_command: cmd.to_string(),
+            pid: 0,
+            dead: false,
+            dead_status: None,
+            cpu_percent: None,
+            rss_kb: None,
+            has_claude: false,
+            claude_state: None,
+            claude_activity: None,
+            claude_state_since: None,
+            claude_cwd: None,
+        };
+        let mut window = TmuxWindow {
+            index: 1,
+            name: "dev".to_string(),
+            active: true,
+            synchronized: false,
+            alerts: String::new(),
+            activity: 0,
+            panes: vec![pane(0, true, "zsh")],
+            has_claude: false,
+            claude_state: None,
+        };
+        assert_eq!(window_preview_title(&window), " 1:dev [zsh] ");
+        window.panes[0].active = false;
+        window.panes.push(pane(1, true, "nvim"));
+        window.panes.push(pane(2, false, "zsh"));
+        assert_eq!(window_preview_title(&window), " 1:dev [nvim] .1/3 ");
+
+        // The kill confirmation counts them across the session.
+        let mut session = TmuxSession {
+            name: "dev".to_string(),
+            windows: vec![window.clone(), window],
+            has_claude: false,
+            claude_state: None,
+            last_attached: 0,
+            activity: 0,
+            attached: false,
+            client_sizes: Vec::new(),
+            client_activity: 0,
+            group: None,
+        };
+        assert_eq!(kill_session_question(&session), "Kill session 'dev' (2 windows, 6 panes)?");
+        session.windows.truncate(1);
+        session.windows[0].panes.truncate(1);
+        assert_eq!(kill_session_question(&session), "Kill session 'dev' (1 window, 1 pane)?");
+    }
+
+    #[test]
+    fn collapsed_sessions_list_keeps_only_the_selected_row() {
+        let mut state = UIState::new(crate::config::Config::default());
+        state.sessions = (0..8)
+            .map(|i| crate::app::TmuxSession {
+                name: format!("s{i}"),
+                windows: Vec::new(),
+                has_claude: false,
+                claude_state: None,
+                last_attached: 0,
+                activity: 0,
+                attached: false,
+                client_sizes: Vec::new(),
+                client_activity: 0,
+                group: None,
+            })
+            .collect();
+        state.selected_session = 5;
+        state.session_list_state.select(Some(5));
+        state.toggle_collapse_focused_list();
+
+        let mut term = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        term.draw(|f| render_tree_view(f, &mut state, f.area())).unwrap();
+        let buf = term.backend().buffer();
+        let row = |y: u16| {
+            (0..buf.area.width)
+                .map(|x| buf.cell((x, y)).unwrap().symbol().to_string())
+                .collect::<String>()
+        };
+        assert!(row(1).contains("s5"));
+        assert!(row(3).contains("Windows"));
+
+        state.toggle_collapse_focused_list();
+        assert_eq!(state.selected_session, 5);
+        assert!(state.collapsed_lists.is_empty());
+    }
+
+    #[test]
+    fn monitor_strip_takes_its_rows_from_the_view() {
+        let mut state = UIState::new(crate::config::Config::default());
+        state.monitored.push("%7".to_string());
+        state.update_monitor_content("%7", "deploying\n", 1.0);
+        state.layout.monitor_height = 4;
+        state.layout.monitor_position = MonitorPosition::Bottom;
+        let mut term = Terminal::new(TestBackend::new(40, 12)).unwrap();
+        let mut rest = Rect::default();
+        term.draw(|f| rest = render_monitor_strip(f, &state)).unwrap();
+        assert_eq!(rest, Rect::new(0, 0, 40, 8));
+        let buf = term.backend().buffer();
+        let row = |y: u16| {
+            (0..buf.area.width)
+                .map(|x| buf.cell((x, y)).unwrap().symbol().to_string())
+                .collect::<String>()
+        };
+        assert!(row(8).contains(" %7 "));
+        assert!(row(9).starts_with("│deploying"));
+
+        state.monitored.clear();
+        term.draw(|f| rest = render_monitor_strip(f, &state)).unwrap();
+        assert_eq!(rest, Rect::new(0, 0, 40, 12));
+    }
+
+    #[test]
+    fn borderless_preview_starts_content_at_the_left_edge() {
+        let mut state = UIState::new(crate::config::Config::default());
+        state.update_pane_content("hello".to_string(), 1.0);
+        let second_row = |state: &mut UIState| {
+            let mut term = Terminal::new(TestBackend::new(30, 6)).unwrap();
+            term.draw(|f| render_pane_preview_tree(f, state, f.area())).unwrap();
+            let buf = term.backend().buffer().clone();
+            (0..buf.area.width)
+                .map(|x| buf.cell((x, 1)).unwrap().symbol().to_string())
+                .collect::<String>()
+        };
+        assert!(second_row(&mut state).starts_with("│hello"));
+        state.preview.borders = false;
+        assert!(second_row(&mut state).starts_with("hello"));
+    }
+
+    #[test]
+    fn popup_footer_drops_optional_hints_to_fit() {
+        let mut state = UIState::new(crate::config::Config::default());
+        state.popup_mode = Some(PopupMode::ConfirmKill);
+        let wide = popup_footer(&state, 60).to_string();
+        assert_eq!(wide, " h/l:toggle | y/n:set | Enter:confirm | Esc:cancel ");
+        let narrow = popup_footer(&state, 46).to_string();
+        assert_eq!(narrow, " h/l:toggle | Enter:confirm | Esc:cancel ");
+        state.popup_mode = None;
+        assert!(popup_footer(&state, 80).to_string().contains("C-v:special key"));
+    }
+
+    #[test]
+    fn client_sizes_render_compactly() {
+        assert_eq!(client_size_label(&[]), None);
+        assert_eq!(client_size_label(&[(120, 40)]).as_deref(), Some("120x40"));
+        assert_eq!(
+            client_size_label(&[(120, 40), (80, 24)]).as_deref(),
+            Some("120x40,80x24")
+        );
+    }
+
+    #[test]
+    fn pane_resources_render_compactly() {
+        assert_eq!(resource_label(None, None), None);
+        assert_eq!(resource_label(None, Some(512)).as_deref(), Some("512K"));
+        assert_eq!(resource_label(Some(12.4), Some(348_160)).as_deref(), Some("12% 340M"));
+        assert_eq!(resource_label(Some(0.0), Some(3 << 19)).as_deref(), Some("0% 1.5G"));
+    }
+
+    #[test]
+    fn read_only_badge_prefixes_status_bar() {
+        let mut state = UIState::new(crate::config::Config::default());
+        let line = with_status_badges(&state, Line::from("hint"));
+        assert_eq!(line.spans.len(), 1);
+        state.read_only = true;
+        let line = with_status_badges(&state, Line::from("hint"));
+        assert_eq!(line.spans[0].content, " READ-ONLY ");
+    }
+
     #[test]
     fn dashboard_renders_without_panic() {
         // Empty agent view: exercises the empty-state, header and status bar.
@@ -0,0 +1,220 @@
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use tracing::{debug, warn};
+
+use crate::app::{InactiveSessions, SessionSort, ViewMode};
+
+// =============================================================================
+// WorkspaceStore — named snapshots of how the deck is looking at tmux
+// =============================================================================
+//
+// A workspace captures the deck-side view settings — view mode, session sort,
+// how the MultiPreview treats unattached sessions, which groups are folded and
+// the selected session — under a name, so switching between monitoring
+// contexts ("builds" with everything but the CI group folded, "dev" sorted by
+// name) is one pick rather than several toggles.
+//
+// Like the group store this is persisted as a small TSV file in the config
+// directory, one workspace per line:
+//
+//   name \t view \t sort \t inactive \t session \t folded group \t ...
+//
+// `session` is empty for "no selection", and a folded group that is empty
+// stands for the implicit "Ungrouped" bucket.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workspace {
+    pub name: String,
+    pub view: ViewMode,
+    pub sort: SessionSort,
+    pub inactive: InactiveSessions,
+    /// Session to select when the workspace is applied, if it still exists.
+    pub session: Option<String>,
+    /// Folded groups; `None` is the "Ungrouped" bucket.
+    pub folded: Vec<Option<String>>,
+}
+
+impl Workspace {
+    fn to_line(&self) -> Option<String> {
+        let mut fields = vec![
+            self.name.as_str(),
+            match self.view {
+                ViewMode::MultiPreview => "multi",
+                ViewMode::AllPanes => "panes",
+                _ => "tree",
+            },
+            self.sort.name(),
+            match self.inactive {
+                InactiveSessions::Dim => "dim",
+                InactiveSessions::Hide => "hide",
+            },
+            self.session.as_deref().unwrap_or(""),
+        ];
+        fields.extend(self.folded.iter().map(|g| g.as_deref().unwrap_or("")));
+        // Tabs/newlines in a name would corrupt the line format.
+        if fields.iter().any(|f| f.contains(['\t', '\n'])) {
+            return None;
+        }
+        Some(fields.join("\t"))
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let name = fields.next()?.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let view = match fields.next()? {
+            "multi" => ViewMode::MultiPreview,
+            "panes" => ViewMode::AllPanes,
+            _ => ViewMode::TreeView,
+        };
+        let sort = SessionSort::from_name(fields.next()?);
+        let inactive = match fields.next()? {
+            "hide" => InactiveSessions::Hide,
+            _ => InactiveSessions::Dim,
+        };
+        let session = Some(fields.next()?).filter(|s| !s.is_empty()).map(str::to_string);
+        let folded = fields
+            .map(|g| Some(g).filter(|g| !g.is_empty()).map(str::to_string))
+            .collect();
+        Some(Self {
+            name: name.to_string(),
+            view,
+            sort,
+            inactive,
+            session,
+            folded,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct WorkspaceStore {
+    /// Saved workspaces, in the order they were first saved.
+    workspaces: Vec<Workspace>,
+    /// Where the store is persisted. `None` when no config dir could be
+    /// resolved; the store then only lives for this run.
+    path: Option<PathBuf>,
+}
+
+impl WorkspaceStore {
+    /// Load the store from the user's config directory. Missing or unreadable
+    /// files yield an empty store, and malformed lines are skipped.
+    pub fn load() -> Self {
+        let path = Self::default_path();
+        let mut workspaces = Vec::new();
+        if let Some(p) = path.as_ref()
+            && let Ok(contents) = std::fs::read_to_string(p)
+        {
+            workspaces.extend(contents.lines().filter_map(Workspace::from_line));
+            debug!("loaded {} workspace(s)", workspaces.len());
+        }
+        Self { workspaces, path }
+    }
+
+    fn default_path() -> Option<PathBuf> {
+        let dirs = ProjectDirs::from("dev", "tkcd", "tmux-deck")?;
+        Some(dirs.config_dir().join("workspaces.tsv"))
+    }
+
+    pub fn all(&self) -> &[Workspace] {
+        &self.workspaces
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Workspace> {
+        self.workspaces.iter().find(|w| w.name == name)
+    }
+
+    /// Save `workspace`, replacing a saved one of the same name in place.
+    /// Persists immediately (best effort).
+    pub fn save(&mut self, workspace: Workspace) {
+        match self.workspaces.iter_mut().find(|w| w.name == workspace.name) {
+            Some(existing) => *existing = workspace,
+            None => self.workspaces.push(workspace),
+        }
+        self.persist();
+    }
+
+    /// Delete the workspace called `name`, if any.
+    pub fn remove(&mut self, name: &str) {
+        let before = self.workspaces.len();
+        self.workspaces.retain(|w| w.name != name);
+        if self.workspaces.len() != before {
+            self.persist();
+        }
+    }
+
+    fn persist(&self) {
+        let Some(path) = self.path.as_ref() else {
+            return;
+        };
+        if let Some(parent) = path.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            warn!("failed to create config dir for workspaces: {e}");
+            return;
+        }
+        let mut out = String::new();
+        for line in self.workspaces.iter().filter_map(Workspace::to_line) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        if let Err(e) = std::fs::write(path, out) {
+            warn!("failed to write workspaces: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{SessionSortKey, SortDirection};
+
+    fn builds() -> Workspace {
+        Workspace {
+            name: "builds".to_string(),
+            view: ViewMode::MultiPreview,
+            sort: SessionSort {
+                key: SessionSortKey::Alphabet,
+                direction: SortDirection::Asc,
+            },
+            inactive: InactiveSessions::Hide,
+            session: Some("ci".to_string()),
+            folded: vec![Some("dev".to_string()), None],
+        }
+    }
+
+    #[test]
+    fn line_format_round_trips() {
+        let ws = builds();
+        let line = ws.to_line().unwrap();
+        assert_eq!(line, "builds\tmulti\tabc_asc\thide\tci\tdev\t");
+        assert_eq!(Workspace::from_line(&line), Some(ws));
+
+        let plain = Workspace::from_line("dev\ttree\trecent\tdim\t").unwrap();
+        assert_eq!(plain.session, None);
+        assert!(plain.folded.is_empty());
+        assert_eq!(Workspace::from_line("truncated\ttree"), None);
+    }
+
+    #[test]
+    fn saving_a_name_again_replaces_it() {
+        let mut store = WorkspaceStore::default();
+        store.save(builds());
+        store.save(Workspace {
+            name: "dev".to_string(),
+            ..builds()
+        });
+        store.save(Workspace {
+            session: None,
+            ..builds()
+        });
+        let names: Vec<&str> = store.all().iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["builds", "dev"]);
+        assert_eq!(store.get("builds").unwrap().session, None);
+        store.remove("builds");
+        assert!(store.get("builds").is_none());
+    }
+}